@@ -0,0 +1,141 @@
+use super::*;
+
+macro_rules! fixed_width {
+    ($($name:ident => $t:ty, $message:literal;)*) => {
+        $(
+            #[doc = concat!("Match a `", stringify!($t), "` with the given byte order")]
+            #[inline]
+            pub fn $name(self, endian: Endian) -> ByteResult<'i, $t> {
+                let (state, bytes) = self.advance_view(core::mem::size_of::<$t>(), $message)?;
+                // SAFETY: the view has exactly the size of the number
+                let bytes = unsafe { bytes.try_into().unwrap_unchecked() };
+                match endian {
+                    Endian::Big => state.finish(<$t>::from_be_bytes(bytes)),
+                    Endian::Little => state.finish(<$t>::from_le_bytes(bytes)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'i> ByteState<'i> {
+    /// Match a single byte
+    #[inline]
+    pub fn match_u8(self) -> ByteResult<'i, u8> {
+        match self.residual.first() {
+            Some(byte) => self.advance(1).finish(*byte),
+            None => StopBecause::missing_string("u8", self.start_offset)?,
+        }
+    }
+    /// Match a single byte as a signed number
+    #[inline]
+    pub fn match_i8(self) -> ByteResult<'i, i8> {
+        self.match_u8().map_inner(|byte| byte as i8)
+    }
+    fixed_width! {
+        match_u16 => u16, "u16";
+        match_u32 => u32, "u32";
+        match_u64 => u64, "u64";
+        match_u128 => u128, "u128";
+        match_i16 => i16, "i16";
+        match_i32 => i32, "i32";
+        match_i64 => i64, "i64";
+        match_i128 => i128, "i128";
+        match_f32 => f32, "f32";
+        match_f64 => f64, "f64";
+    }
+    /// Match an unsigned LEB128 varint, fail if the number overflows `u64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::ByteState;
+    /// assert_eq!(ByteState::new(&[0xE5, 0x8E, 0x26]).match_uleb128().unwrap(), 624485);
+    /// assert!(ByteState::new(&[0x80, 0x80]).match_uleb128().is_failure());
+    /// ```
+    #[inline]
+    pub fn match_uleb128(self) -> ByteResult<'i, u64> {
+        let mut value = 0u64;
+        for (index, byte) in self.residual.iter().enumerate() {
+            let shift = index as u32 * 7;
+            let bits = (byte & 0x7F) as u64;
+            if shift >= 64 || (shift > 0 && bits >> (64 - shift) != 0) {
+                StopBecause::custom_error("LEB128 overflow", self.start_offset, self.start_offset + index + 1)?
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                return self.advance(index + 1).finish(value);
+            }
+        }
+        StopBecause::missing_string("LEB128", self.end_offset())?
+    }
+    /// Match a signed LEB128 varint, fail if the number overflows `i64`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::ByteState;
+    /// assert_eq!(ByteState::new(&[0xC0, 0xBB, 0x78]).match_sleb128().unwrap(), -123456);
+    /// assert_eq!(ByteState::new(&[0x3F]).match_sleb128().unwrap(), 63);
+    /// ```
+    #[inline]
+    pub fn match_sleb128(self) -> ByteResult<'i, i64> {
+        let mut value = 0i64;
+        for (index, byte) in self.residual.iter().enumerate() {
+            let shift = index as u32 * 7;
+            let bits = (byte & 0x7F) as i64;
+            if shift >= 64 || (shift == 63 && bits != 0 && bits != 0x7F) {
+                StopBecause::custom_error("LEB128 overflow", self.start_offset, self.start_offset + index + 1)?
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                // sign extend
+                if shift + 7 < 64 && byte & 0x40 != 0 {
+                    value |= -1i64 << (shift + 7);
+                }
+                return self.advance(index + 1).finish(value);
+            }
+        }
+        StopBecause::missing_string("LEB128", self.end_offset())?
+    }
+    /// Match a slice whose length is parsed by `length` first
+    #[inline]
+    pub fn match_length_prefixed<F, L>(self, length: F) -> ByteResult<'i, &'i [u8]>
+    where
+        F: FnOnce(ByteState<'i>) -> ByteResult<'i, L>,
+        L: TryInto<usize>,
+    {
+        let (state, length) = length(self)?;
+        match length.try_into() {
+            Ok(length) => state.advance_view(length, "LENGTH_PREFIXED"),
+            Err(_) => StopBecause::custom_error("Length out of range", self.start_offset, state.start_offset)?,
+        }
+    }
+    /// Match the magic number at the start of a binary format
+    #[inline]
+    pub fn match_magic(self, magic: &'static [u8], message: &'static str) -> ByteResult<'i, &'i [u8]> {
+        match self.residual.starts_with(magic) {
+            true => self.advance_view(magic.len(), message),
+            false => StopBecause::must_be(message, self.start_offset)?,
+        }
+    }
+    /// Skip the padding until the offset is a multiple of `align`, fail if there are not enough bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::ByteState;
+    /// let state = ByteState::new(&[1, 0, 0, 0, 2]).advance(1);
+    /// let (state, padding) = state.match_align(4).as_result().unwrap();
+    /// assert_eq!((state.start_offset, padding.len()), (4, 3));
+    /// assert!(state.advance(1).match_align(8).is_failure());
+    /// ```
+    #[inline]
+    pub fn match_align(self, align: usize) -> ByteResult<'i, &'i [u8]> {
+        let padding = match align {
+            0 | 1 => 0,
+            _ => (align - self.start_offset % align) % align,
+        };
+        self.advance_view(padding, "ALIGNMENT")
+    }
+}
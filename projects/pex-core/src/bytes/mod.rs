@@ -0,0 +1,263 @@
+use crate::{ParseContext, ParseResult, StopBecause};
+use alloc::vec::Vec;
+use core::{
+    convert::Infallible,
+    fmt::{Debug, Formatter},
+    ops::{ControlFlow, FromResidual, Try},
+};
+
+mod binary;
+
+/// Represent a parsed value of binary input
+pub type ByteParsed<'i, T> = (ByteState<'i>, T);
+
+/// The state of parsing binary input, the byte version of [ParseState](crate::ParseState)
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{ByteResult, ByteState, Endian};
+/// // magic, version, name
+/// fn header(state: ByteState) -> ByteResult<(u16, &[u8])> {
+///     let (state, _) = state.match_magic(b"PEX\0", "MAGIC")?;
+///     let (state, version) = state.match_u16(Endian::Little)?;
+///     let (state, name) = state.match_length_prefixed(|s| s.match_u8())?;
+///     state.finish((version, name))
+/// }
+/// let input = b"PEX\0\x02\x00\x03abc";
+/// assert_eq!(header(ByteState::new(input)).unwrap(), (2, &b"abc"[..]));
+/// assert!(header(ByteState::new(b"PEX\0\x02")).is_failure());
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ByteState<'i> {
+    /// Rest part of bytes
+    pub residual: &'i [u8],
+    /// Start offset of the bytes
+    pub start_offset: usize,
+    /// Shared context of the parse
+    pub context: Option<&'i ParseContext>,
+}
+
+/// Represent as parsing result of binary input, shares the residual with [ParseResult], so `?` works across them
+#[derive(Eq, PartialEq)]
+pub enum ByteResult<'i, T> {
+    /// The parsing is not finished yet
+    Pending(ByteState<'i>, T),
+    /// The parsing is finished, and give the reason why
+    Stop(StopBecause),
+}
+
+/// The byte order of fixed-width numbers
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Endian {
+    /// The most significant byte first
+    Big,
+    /// The least significant byte first
+    Little,
+}
+
+impl<'i> ByteState<'i> {
+    /// Create a new state
+    #[inline(always)]
+    pub const fn new(input: &'i [u8]) -> Self {
+        Self { residual: input, start_offset: 0, context: None }
+    }
+    /// Reset the cursor offset
+    #[inline(always)]
+    pub const fn with_start_offset(mut self, offset: usize) -> Self {
+        self.start_offset = offset;
+        self
+    }
+    /// Attach a shared context to the state
+    #[inline(always)]
+    pub const fn with_context(mut self, context: &'i ParseContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+    /// Get the end offset of the bytes
+    #[inline(always)]
+    pub const fn end_offset(&self) -> usize {
+        self.start_offset + self.residual.len()
+    }
+    /// Finish with given value
+    #[inline(always)]
+    pub const fn finish<T>(self, value: T) -> ByteResult<'i, T> {
+        ByteResult::Pending(self, value)
+    }
+    /// Check if the bytes are depleted
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.residual.is_empty()
+    }
+    /// Advance the parser to a new state.
+    #[inline]
+    pub fn advance(self, offset: usize) -> ByteState<'i> {
+        debug_assert!(offset <= self.residual.len(), "offset is out of range");
+        let residual = unsafe { self.residual.get_unchecked(offset..) };
+        ByteState { residual, start_offset: self.start_offset + offset, context: self.context }
+    }
+    /// Advance the parser state and return the view of these bytes, fail if there are not enough bytes.
+    #[inline]
+    pub fn advance_view(self, offset: usize, message: &'static str) -> ByteResult<'i, &'i [u8]> {
+        match self.residual.get(..offset) {
+            Some(view) => self.advance(offset).finish(view),
+            None => StopBecause::missing_string(message, self.start_offset)?,
+        }
+    }
+    /// Record an error that will be dropped by backtracking, do nothing if there is no context.
+    #[inline]
+    pub fn record_error(&self, error: StopBecause) {
+        if let Some(context) = self.context {
+            context.record_error(error)
+        }
+    }
+    /// Simple suffix call form
+    #[inline]
+    pub fn match_fn<T, F>(self, mut parse: F) -> ByteResult<'i, T>
+    where
+        F: FnMut(ByteState<'i>) -> ByteResult<'i, T>,
+    {
+        parse(self)
+    }
+    /// Parse the pattern, return `None` if failed.
+    #[inline]
+    pub fn match_optional<T, F>(self, mut parse: F) -> ByteResult<'i, Option<T>>
+    where
+        F: FnMut(ByteState<'i>) -> ByteResult<'i, T>,
+    {
+        match parse(self) {
+            ByteResult::Pending(state, value) => state.finish(Some(value)),
+            ByteResult::Stop(e) => {
+                self.record_error(e);
+                self.finish(None)
+            }
+        }
+    }
+    /// Parse the pattern exactly `count` times
+    #[inline]
+    pub fn match_count<T, F>(self, count: usize, mut parse: F) -> ByteResult<'i, Vec<T>>
+    where
+        F: FnMut(ByteState<'i>) -> ByteResult<'i, T>,
+    {
+        let mut result = Vec::with_capacity(count.min(self.residual.len()));
+        let mut state = self;
+        for _ in 0..count {
+            let (new, value) = parse(state)?;
+            state = new;
+            result.push(value);
+        }
+        state.finish(result)
+    }
+    /// Fail if there are bytes left
+    #[inline]
+    pub fn match_eof(self) -> ByteResult<'i, ()> {
+        match self.is_empty() {
+            true => self.finish(()),
+            false => StopBecause::expect_eof(self.start_offset)?,
+        }
+    }
+}
+
+impl<'i, T> Debug for ByteResult<'i, T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ByteResult::Pending(s, v) => f
+                .debug_struct("Pending")
+                .field("value", v)
+                .field("rest_bytes", &s.residual)
+                .field("start_offset", &s.start_offset)
+                .finish(),
+            ByteResult::Stop(e) => f.debug_struct("Stop").field("reason", e).finish(),
+        }
+    }
+}
+
+impl<'i, T> ByteResult<'i, T> {
+    /// Map inner value
+    #[inline(always)]
+    pub fn map_inner<F, U>(self, mut f: F) -> ByteResult<'i, U>
+    where
+        F: FnMut(T) -> U,
+    {
+        match self {
+            Self::Pending(state, value) => ByteResult::Pending(state, f(value)),
+            Self::Stop(reason) => ByteResult::Stop(reason),
+        }
+    }
+    /// Map inner value into target
+    #[inline(always)]
+    pub fn map_value<U>(self, value: U) -> ByteResult<'i, U> {
+        match self {
+            Self::Pending(state, _) => ByteResult::Pending(state, value),
+            Self::Stop(reason) => ByteResult::Stop(reason),
+        }
+    }
+    /// Convert a byte [`Result`](Self) to a std [`Result`]
+    #[inline(always)]
+    #[allow(clippy::wrong_self_convention)]
+    pub fn as_result(self) -> Result<ByteParsed<'i, T>, StopBecause> {
+        match self {
+            Self::Pending(state, value) => Ok((state, value)),
+            Self::Stop(reason) => Err(reason),
+        }
+    }
+    /// Returns the contained [`ByteResult::Pending`] value, drop current state, panic if state reach stopped.
+    #[track_caller]
+    #[inline(always)]
+    pub fn unwrap(self) -> T {
+        match self {
+            Self::Pending(_, v) => v,
+            Self::Stop(e) => panic!("{e:?}"),
+        }
+    }
+    /// Check whether a match is successful
+    #[inline(always)]
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Pending(..))
+    }
+    /// Check whether a match is failed
+    #[inline(always)]
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Stop(..))
+    }
+}
+
+impl<'i, T> Try for ByteResult<'i, T> {
+    type Output = ByteParsed<'i, T>;
+    type Residual = ParseResult<'i, Infallible>;
+
+    fn from_output(output: Self::Output) -> Self {
+        Self::Pending(output.0, output.1)
+    }
+
+    fn branch(self) -> ControlFlow<Self::Residual, Self::Output> {
+        match self {
+            Self::Pending(state, value) => ControlFlow::Continue((state, value)),
+            Self::Stop(e) => ControlFlow::Break(ParseResult::Stop(e)),
+        }
+    }
+}
+
+impl<'i, T> FromResidual for ByteResult<'i, T> {
+    fn from_residual(residual: ParseResult<'i, Infallible>) -> Self {
+        match residual {
+            ParseResult::Pending(_, _) => unreachable!(),
+            ParseResult::Stop(e) => Self::Stop(e),
+        }
+    }
+}
+
+impl<'i, T, E> FromResidual<Result<Infallible, E>> for ByteResult<'i, T>
+where
+    E: Into<StopBecause>,
+{
+    fn from_residual(residual: Result<Infallible, E>) -> Self {
+        match residual {
+            Ok(_) => unreachable!(),
+            Err(e) => Self::Stop(e.into()),
+        }
+    }
+}
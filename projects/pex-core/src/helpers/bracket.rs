@@ -0,0 +1,99 @@
+use super::*;
+use alloc::vec::Vec;
+
+/// Skip the text until a sync token at the top level, nested brackets are skipped as a whole, often used to recover
+/// from errors.
+///
+/// Stops before a sync token, before an unmatched close bracket, or at the end of input, never fails.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{helpers::BalancedSkip, ParseState};
+/// let skip = BalancedSkip::new(&[",", ";"]);
+/// let (state, skipped) = skip(ParseState::new("f(a, b)[;] , c")).as_result().unwrap();
+/// assert_eq!(skipped.as_string(), "f(a, b)[;] ");
+/// assert_eq!(state.residual, ", c");
+/// let (state, skipped) = skip(ParseState::new("a b) c")).as_result().unwrap();
+/// assert_eq!(skipped.as_string(), "a b");
+/// assert_eq!(state.residual, ") c");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct BalancedSkip {
+    /// The pairs of open and close brackets
+    pub brackets: &'static [(&'static str, &'static str)],
+    /// The tokens to stop at the top level
+    pub sync: &'static [&'static str],
+}
+
+impl BalancedSkip {
+    /// Create a new balanced skip with `()`, `[]` and `{}` brackets
+    pub const fn new(sync: &'static [&'static str]) -> Self {
+        Self { brackets: &[("(", ")"), ("[", "]"), ("{", "}")], sync }
+    }
+    /// Set the brackets to skip as a whole
+    pub const fn with_brackets(self, brackets: &'static [(&'static str, &'static str)]) -> Self {
+        Self { brackets, ..self }
+    }
+}
+
+impl<'i> FnOnce<(ParseState<'i>,)> for BalancedSkip {
+    type Output = ParseResult<'i, StringView<'i>>;
+    #[inline]
+    extern "rust-call" fn call_once(self, (input,): (ParseState<'i>,)) -> Self::Output {
+        let offset = balanced_offset(input.residual, self.brackets, self.sync);
+        let (state, skipped) = input.advance_view(offset)?;
+        state.finish(StringView::new(skipped, input.start_offset))
+    }
+}
+
+impl<'i> FnMut<(ParseState<'i>,)> for BalancedSkip {
+    #[inline]
+    extern "rust-call" fn call_mut(&mut self, args: (ParseState<'i>,)) -> Self::Output {
+        FnOnce::call_once(*self, args)
+    }
+}
+
+impl<'i> Fn<(ParseState<'i>,)> for BalancedSkip {
+    #[inline]
+    extern "rust-call" fn call(&self, args: (ParseState<'i>,)) -> Self::Output {
+        FnOnce::call_once(*self, args)
+    }
+}
+
+/// Find the offset of the first sync token or unmatched close bracket at the top level
+pub(crate) fn balanced_offset(text: &str, brackets: &[(&str, &str)], sync: &[&str]) -> usize {
+    let mut closes: Vec<&str> = Vec::new();
+    let mut offset = 0;
+    while let Some(rest) = text.get(offset..) {
+        let mut chars = rest.chars();
+        let c = match chars.next() {
+            Some(c) => c,
+            None => break,
+        };
+        match closes.last() {
+            Some(close) if rest.starts_with(close) => {
+                offset += close.len();
+                closes.pop();
+                continue;
+            }
+            Some(_) => {}
+            None => {
+                if sync.iter().any(|s| rest.starts_with(s)) {
+                    break;
+                }
+                if brackets.iter().any(|(_, close)| rest.starts_with(close)) {
+                    break;
+                }
+            }
+        }
+        match brackets.iter().find(|(open, _)| rest.starts_with(open)) {
+            Some((open, close)) => {
+                offset += open.len();
+                closes.push(close);
+            }
+            None => offset += c.len_utf8(),
+        }
+    }
+    offset
+}
@@ -0,0 +1,79 @@
+use self::table::{CASE_FOLDING_COMMON, CASE_FOLDING_FULL, CASE_FOLDING_SIMPLE, CASE_FOLDING_TURKIC};
+use core::str::Chars;
+
+mod table;
+
+/// The case folding modes of `CaseFolding.txt`, generated by `pex_trie::CaseFoldingTable`
+///
+/// # Examples
+///
+/// ```
+/// # use pex::helpers::CaseFolding;
+/// assert_eq!(CaseFolding::Full.fold('ẞ').collect::<String>(), "ss");
+/// assert_eq!(CaseFolding::Simple.fold('ẞ').collect::<String>(), "ß");
+/// assert_eq!(CaseFolding::Turkic.fold('I').collect::<String>(), "ı");
+/// assert!(CaseFolding::Full.eq_folded("STRASSE", "straße"));
+/// assert!(!CaseFolding::Simple.eq_folded("STRASSE", "straße"));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum CaseFolding {
+    /// Fold a character into a character, the status `C` and `S`
+    Simple,
+    /// Fold a character into a string, the status `C` and `F`
+    #[default]
+    Full,
+    /// Full folding with the dotted and dotless `I` of Turkish and Azerbaijani, the status `C`, `F` and `T`
+    Turkic,
+}
+
+/// The folded characters of a character, see [CaseFolding::fold]
+#[derive(Clone, Debug)]
+pub struct FoldedChars {
+    head: Option<char>,
+    rest: Chars<'static>,
+}
+
+impl Iterator for FoldedChars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.head.take().or_else(|| self.rest.next())
+    }
+}
+
+impl CaseFolding {
+    /// Fold a character, the character itself if it has no folding
+    pub fn fold(self, c: char) -> FoldedChars {
+        let single = |c| FoldedChars { head: Some(c), rest: "".chars() };
+        if self == CaseFolding::Turkic {
+            if let Some(folded) = lookup(CASE_FOLDING_TURKIC, c) {
+                return single(folded);
+            }
+        }
+        if self == CaseFolding::Simple {
+            if let Some(folded) = lookup(CASE_FOLDING_SIMPLE, c) {
+                return single(folded);
+            }
+        }
+        else if let Some(folded) = lookup(CASE_FOLDING_FULL, c) {
+            return FoldedChars { head: None, rest: folded.chars() };
+        }
+        single(lookup(CASE_FOLDING_COMMON, c).unwrap_or(c))
+    }
+    /// Fold every character of the text
+    pub fn fold_str(self, text: &str) -> impl Iterator<Item = char> + '_ {
+        text.chars().flat_map(move |c| self.fold(c))
+    }
+    /// Check if two texts are equal after folding
+    pub fn eq_folded(self, lhs: &str, rhs: &str) -> bool {
+        self.fold_str(lhs).eq(self.fold_str(rhs))
+    }
+}
+
+fn lookup<T: Copy>(table: &[(char, T)], c: char) -> Option<T> {
+    // ascii other than uppercase letters never folds
+    if c.is_ascii() && !c.is_ascii_uppercase() {
+        return None;
+    }
+    table.binary_search_by_key(&c, |(k, _)| *k).ok().map(|i| table[i].1)
+}
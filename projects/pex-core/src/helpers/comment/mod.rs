@@ -39,6 +39,7 @@ impl<'i> FnOnce<(ParseState<'i>,)> for CommentLine {
         let (_, head) = input.match_str(self.head)?;
         let offset = match input.residual.find(&['\r', '\n']) {
             Some(s) => s,
+            None if input.partial => StopBecause::incomplete(input.start_offset)?,
             None => input.residual.len(),
         };
         // SAFETY: find offset always valid
@@ -111,6 +112,7 @@ impl<'i> FnOnce<(ParseState<'i>,)> for CommentBlock {
                             tail: StringView::new(self.tail, input.start_offset + offset),
                         })
                     }
+                    None if state.partial => StopBecause::incomplete(input.start_offset)?,
                     None => StopBecause::missing_string(self.tail, state.start_offset)?,
                 }
             }
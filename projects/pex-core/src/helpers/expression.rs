@@ -0,0 +1,249 @@
+use super::*;
+use alloc::{string::String, vec::Vec};
+
+/// The position of an operator relative to its operands
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Fixity {
+    /// Before the operand, e.g. `-a`
+    Prefix,
+    /// Between two operands, e.g. `a + b`
+    Infix,
+    /// After the operand, e.g. `a?`
+    Postfix,
+}
+
+/// How infix operators with the same precedence are grouped
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`
+    Right,
+    /// `a == b == c` is an error
+    None,
+}
+
+/// An operator in the [ExpressionPattern] table
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Operator {
+    /// The text of the operator
+    pub token: String,
+    /// The binding power, higher binds tighter
+    pub precedence: u32,
+    /// The position of the operator
+    pub fixity: Fixity,
+    /// The grouping of infix operators, ignored by prefix and postfix operators
+    pub associativity: Associativity,
+}
+
+impl Operator {
+    /// Create a new prefix operator
+    pub fn prefix(token: impl Into<String>, precedence: u32) -> Self {
+        Self { token: token.into(), precedence, fixity: Fixity::Prefix, associativity: Associativity::None }
+    }
+    /// Create a new infix operator
+    pub fn infix(token: impl Into<String>, precedence: u32, associativity: Associativity) -> Self {
+        Self { token: token.into(), precedence, fixity: Fixity::Infix, associativity }
+    }
+    /// Create a new postfix operator
+    pub fn postfix(token: impl Into<String>, precedence: u32) -> Self {
+        Self { token: token.into(), precedence, fixity: Fixity::Postfix, associativity: Associativity::None }
+    }
+    /// The binding power on the left and right side, `None` if there is no operand on that side
+    fn binding_power(&self) -> (Option<u64>, Option<u64>) {
+        let power = self.precedence as u64 * 2;
+        match (self.fixity, self.associativity) {
+            (Fixity::Prefix, _) => (None, Some(power + 1)),
+            (Fixity::Postfix, _) => (Some(power), None),
+            (Fixity::Infix, Associativity::Right) => (Some(power + 1), Some(power)),
+            (Fixity::Infix, _) => (Some(power), Some(power + 1)),
+        }
+    }
+}
+
+/// A node built by the callback of [ExpressionPattern::parse]
+#[derive(Clone, Debug)]
+pub enum ExpressionNode<'i, T> {
+    /// A prefix operator and its operand
+    Prefix {
+        /// The matched operator
+        operator: StringView<'i>,
+        /// The operand on the right
+        rhs: T,
+    },
+    /// An infix operator and its operands
+    Infix {
+        /// The operand on the left
+        lhs: T,
+        /// The matched operator
+        operator: StringView<'i>,
+        /// The operand on the right
+        rhs: T,
+    },
+    /// A postfix operator and its operand
+    Postfix {
+        /// The operand on the left
+        lhs: T,
+        /// The matched operator
+        operator: StringView<'i>,
+    },
+}
+
+/// Parse expressions with prefix, infix and postfix operators by precedence climbing.
+///
+/// The operator table can be edited at runtime, so user-declared fixities are supported.
+/// The longest operator is matched if several operators share a prefix.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{helpers::{whitespace, Associativity, ExpressionNode, ExpressionPattern, Operator}, ParseResult, ParseState};
+/// let mut pattern = ExpressionPattern::default()
+///     .with_trivia(whitespace)
+///     .with_operator(Operator::infix("+", 1, Associativity::Left))
+///     .with_operator(Operator::infix("*", 2, Associativity::Left))
+///     .with_operator(Operator::infix("^", 3, Associativity::Right))
+///     .with_operator(Operator::prefix("-", 4))
+///     .with_operator(Operator::postfix("!", 5));
+/// let build = |node: ExpressionNode<String>| match node {
+///     ExpressionNode::Prefix { operator, rhs } => format!("({}{})", operator.as_string(), rhs),
+///     ExpressionNode::Infix { lhs, operator, rhs } => format!("({} {} {})", lhs, operator.as_string(), rhs),
+///     ExpressionNode::Postfix { lhs, operator } => format!("({}{})", lhs, operator.as_string()),
+/// };
+/// fn atom(state: ParseState) -> ParseResult<String> {
+///     state.match_str_if(|c| c.is_ascii_alphanumeric(), "ATOM").map_inner(String::from)
+/// }
+/// let text = "-a + b * c! ^ d ^ e";
+/// let value = pattern.parse(ParseState::new(text), atom, build).unwrap();
+/// assert_eq!(value, "((-a) + (b * ((c!) ^ (d ^ e))))");
+/// // declare a new operator at runtime
+/// pattern.insert_operator(Operator::infix("<+>", 1, Associativity::Right));
+/// let value = pattern.parse(ParseState::new("a <+> b <+> c"), atom, build).unwrap();
+/// assert_eq!(value, "(a <+> (b <+> c))");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExpressionPattern<'i> {
+    operators: Vec<Operator>,
+    trivia: Option<fn(ParseState<'i>) -> ParseResult<'i, &'i str>>,
+}
+
+impl<'i> ExpressionPattern<'i> {
+    /// Add an operator to the table, replace the operator with the same token and fixity
+    pub fn with_operator(mut self, operator: Operator) -> Self {
+        self.insert_operator(operator);
+        self
+    }
+    /// Skip the trivia around operators and atoms, such as whitespace and comments
+    pub fn with_trivia(mut self, trivia: fn(ParseState<'i>) -> ParseResult<'i, &'i str>) -> Self {
+        self.trivia = Some(trivia);
+        self
+    }
+    /// Add an operator to the table, return the old operator with the same token and fixity
+    pub fn insert_operator(&mut self, operator: Operator) -> Option<Operator> {
+        match self.operators.iter_mut().find(|o| o.token == operator.token && o.fixity == operator.fixity) {
+            Some(old) => Some(core::mem::replace(old, operator)),
+            None => {
+                self.operators.push(operator);
+                None
+            }
+        }
+    }
+    /// Remove an operator from the table
+    pub fn remove_operator(&mut self, token: &str, fixity: Fixity) -> Option<Operator> {
+        let index = self.operators.iter().position(|o| o.token == token && o.fixity == fixity)?;
+        Some(self.operators.remove(index))
+    }
+    /// Get an operator in the table
+    pub fn get_operator(&self, token: &str, fixity: Fixity) -> Option<&Operator> {
+        self.operators.iter().find(|o| o.token == token && o.fixity == fixity)
+    }
+    /// Get all operators in the table
+    pub fn get_operators(&self) -> &[Operator] {
+        &self.operators
+    }
+    /// Parse an expression, operands are parsed by `atom`, and the tree is built by `build`.
+    ///
+    /// If the right operand of an infix operator is missing, the expression stops before the operator.
+    pub fn parse<T, A, B>(&self, state: ParseState<'i>, mut atom: A, mut build: B) -> ParseResult<'i, T>
+    where
+        A: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        B: FnMut(ExpressionNode<'i, T>) -> T,
+    {
+        self.parse_power(state, 0, &mut atom, &mut build)
+    }
+    fn parse_power<T, A, B>(&self, state: ParseState<'i>, min_power: u64, atom: &mut A, build: &mut B) -> ParseResult<'i, T>
+    where
+        A: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        B: FnMut(ExpressionNode<'i, T>) -> T,
+    {
+        let state = self.skip_trivia(state);
+        let (mut state, mut lhs) = match self.match_operator(state, Fixity::Prefix) {
+            Some((operator, view, rest)) => {
+                let (_, rhs_power) = operator.binding_power();
+                let (rest, rhs) = self.parse_power(rest, rhs_power.unwrap_or(0), atom, build)?;
+                (rest, build(ExpressionNode::Prefix { operator: view, rhs }))
+            }
+            None => atom(state)?,
+        };
+        // the precedence of the last non-associative operator
+        let mut chained: Option<u32> = None;
+        loop {
+            let next = self.skip_trivia(state);
+            if let Some((operator, view, rest)) = self.match_operator(next, Fixity::Postfix) {
+                let (lhs_power, _) = operator.binding_power();
+                if lhs_power.unwrap_or(0) >= min_power {
+                    state = rest;
+                    lhs = build(ExpressionNode::Postfix { lhs, operator: view });
+                    continue;
+                }
+            }
+            let (operator, view, rest) = match self.match_operator(next, Fixity::Infix) {
+                Some(s) => s,
+                None => break,
+            };
+            let (lhs_power, rhs_power) = operator.binding_power();
+            if lhs_power.unwrap_or(0) < min_power {
+                break;
+            }
+            if operator.associativity == Associativity::None && chained == Some(operator.precedence) {
+                StopBecause::custom_error(
+                    "Non-associative operator can not be chained",
+                    view.start_offset(),
+                    view.end_offset(),
+                )?
+            }
+            let mark = rest.error_mark();
+            match self.parse_power(rest, rhs_power.unwrap_or(0), atom, build) {
+                Pending(rest, rhs) => {
+                    state = rest;
+                    lhs = build(ExpressionNode::Infix { lhs, operator: view, rhs });
+                }
+                Stop(e) => {
+                    rest.backtrack(mark, e);
+                    break;
+                }
+            }
+            chained = match operator.associativity {
+                Associativity::None => Some(operator.precedence),
+                _ => None,
+            };
+        }
+        state.finish(lhs)
+    }
+    fn skip_trivia(&self, state: ParseState<'i>) -> ParseState<'i> {
+        match self.trivia {
+            Some(trivia) => state.skip(trivia),
+            None => state,
+        }
+    }
+    /// Match the longest operator with the fixity
+    fn match_operator(&self, state: ParseState<'i>, fixity: Fixity) -> Option<(&Operator, StringView<'i>, ParseState<'i>)> {
+        let operator = self
+            .operators
+            .iter()
+            .filter(|o| o.fixity == fixity && !o.token.is_empty() && state.residual.starts_with(o.token.as_str()))
+            .max_by_key(|o| o.token.len())?;
+        let view = StringView::new(&state.residual[..operator.token.len()], state.start_offset);
+        Some((operator, view, state.advance(operator.token.len())))
+    }
+}
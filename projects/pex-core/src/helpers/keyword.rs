@@ -0,0 +1,125 @@
+use super::*;
+
+/// A set of reserved and soft keywords, matched only at identifier boundaries.
+///
+/// Reserved keywords can never be identifiers, soft keywords are keywords only in some contexts,
+/// so [KeywordSet::match_identifier] accepts them.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{helpers::{Keyword, KeywordSet}, ParseState, StopBecause};
+/// const KEYWORDS: KeywordSet = KeywordSet::new(&["if", "else", "in"]).with_soft(&["union"]);
+/// assert_eq!(KEYWORDS(ParseState::new("if x")).unwrap(), Keyword { text: "if", soft: false });
+/// assert_eq!(
+///     KEYWORDS(ParseState::new("union {")).unwrap(),
+///     Keyword { text: "union", soft: true }
+/// );
+/// // not a keyword, but a prefix of an identifier
+/// assert!(KEYWORDS(ParseState::new("iffy")).is_failure());
+/// assert_eq!(KEYWORDS.match_identifier(ParseState::new("iffy")).unwrap(), "iffy");
+/// assert_eq!(KEYWORDS.match_identifier(ParseState::new("union")).unwrap(), "union");
+/// assert_eq!(
+///     KEYWORDS.match_identifier(ParseState::new("in")).as_result().unwrap_err(),
+///     StopBecause::ReservedKeyword { keyword: "in", position: 0 }
+/// );
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct KeywordSet {
+    reserved: &'static [&'static str],
+    soft: &'static [&'static str],
+    message: &'static str,
+}
+
+/// A keyword matched by [KeywordSet]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Keyword {
+    /// The keyword in the set
+    pub text: &'static str,
+    /// Whether the keyword is a soft keyword
+    pub soft: bool,
+}
+
+impl<'i> FnOnce<(ParseState<'i>,)> for KeywordSet {
+    type Output = ParseResult<'i, Keyword>;
+    /// Match any keyword in the set
+    #[inline]
+    extern "rust-call" fn call_once(self, (input,): (ParseState<'i>,)) -> Self::Output {
+        let (state, word) = input.match_fn(match_word)?;
+        match self.get_keyword(word) {
+            Some(keyword) => state.finish(keyword),
+            None => StopBecause::missing_character_set(self.message, input.start_offset)?,
+        }
+    }
+}
+
+impl<'i> FnMut<(ParseState<'i>,)> for KeywordSet {
+    #[inline]
+    extern "rust-call" fn call_mut(&mut self, (input,): (ParseState<'i>,)) -> Self::Output {
+        FnOnce::call_once(*self, (input,))
+    }
+}
+
+impl<'i> Fn<(ParseState<'i>,)> for KeywordSet {
+    #[inline]
+    extern "rust-call" fn call(&self, (input,): (ParseState<'i>,)) -> Self::Output {
+        FnOnce::call_once(*self, (input,))
+    }
+}
+
+impl KeywordSet {
+    /// Create a new keyword set with the reserved keywords
+    pub const fn new(reserved: &'static [&'static str]) -> Self {
+        Self { reserved, soft: &[], message: "KEYWORD" }
+    }
+    /// Set the soft keywords, which can also be identifiers
+    pub const fn with_soft(self, soft: &'static [&'static str]) -> Self {
+        Self { soft, ..self }
+    }
+    /// Set the message when no keyword matches
+    pub const fn with_message(self, message: &'static str) -> Self {
+        Self { message, ..self }
+    }
+    /// Find the keyword equal to the word
+    pub fn get_keyword(&self, word: &str) -> Option<Keyword> {
+        if let Some(text) = self.reserved.iter().find(|k| word.eq(**k)) {
+            return Some(Keyword { text, soft: false });
+        }
+        let text = self.soft.iter().find(|k| word.eq(**k))?;
+        Some(Keyword { text, soft: true })
+    }
+    /// Check if the word is a reserved keyword
+    pub fn is_reserved(&self, word: &str) -> bool {
+        self.reserved.contains(&word)
+    }
+    /// Match an identifier of `XID_Start` or `_` followed by `XID_Continue`, fail with [StopBecause::ReservedKeyword] on reserved keywords.
+    pub fn match_identifier<'i>(&self, input: ParseState<'i>) -> ParseResult<'i, &'i str> {
+        let (state, word) = input.match_fn(match_word)?;
+        self.check_identifier(word, input.start_offset)?;
+        state.finish(word)
+    }
+    /// Reject the identifier parsed by other parsers if it is a reserved keyword
+    pub fn check_identifier(&self, word: &str, position: usize) -> Result<(), StopBecause> {
+        match self.reserved.iter().find(|k| word.eq(**k)) {
+            Some(keyword) => Err(StopBecause::ReservedKeyword { keyword, position }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Match a whole word of `XID_Start` or `_` followed by `XID_Continue`
+fn match_word<'i>(input: ParseState<'i>) -> ParseResult<'i, &'i str> {
+    let mut chars = input.residual.char_indices();
+    match chars.next() {
+        Some((_, c)) if c == '_' || XID_START.contains(c) => {}
+        None if input.partial => StopBecause::incomplete(input.start_offset)?,
+        _ => StopBecause::missing_character_set("XID_START", input.start_offset)?,
+    }
+    let offset = match chars.find(|(_, c)| !XID_CONTINUE.contains(*c)) {
+        Some((offset, _)) => offset,
+        // the word may continue in the next chunk
+        None if input.partial => StopBecause::incomplete(input.start_offset)?,
+        None => input.residual.len(),
+    };
+    input.advance_view(offset)
+}
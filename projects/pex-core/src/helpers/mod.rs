@@ -1,23 +1,33 @@
 #![doc = include_str!("readme.md")]
 use crate::{ParseResult, ParseState, StopBecause};
 mod bracket;
+mod case_folding;
 mod color;
 mod comment;
+mod expression;
+mod keyword;
 mod number;
 mod string;
 mod surround_pair;
 mod trie_set;
+mod xid;
 mod zero_base_byte;
 
+pub(crate) use self::bracket::balanced_offset;
 pub use self::{
+    bracket::BalancedSkip,
+    case_folding::{CaseFolding, FoldedChars},
     color::HexColor,
     comment::{CommentBlock, CommentLine},
+    expression::{Associativity, ExpressionNode, ExpressionPattern, Fixity, Operator},
+    keyword::{Keyword, KeywordSet},
     number::*,
     string::{
         quotation_pair, quotation_pair_escaped, quotation_pair_nested, surround_pair_with_escaper, unescape_us, UnicodeUnescape,
     },
     surround_pair::{SurroundPair, SurroundPattern},
     trie_set::CharactersTrie,
+    xid::{XID_CONTINUE, XID_START},
     zero_base_byte::ZeroBytePattern,
 };
 use crate::{
@@ -34,12 +44,17 @@ use crate::{
 /// # use pex::{ParseResult, ParseState, helpers::ascii_whitespace};
 /// let state = ParseState::new("  \na");
 /// state.skip(ascii_whitespace);
+/// // the whitespace may reach the end of input
+/// assert!(ascii_whitespace(ParseState::new(" \t\n")).is_success());
+/// assert!(ascii_whitespace(ParseState::new("")).is_failure());
 /// ```
 #[inline]
 pub fn ascii_whitespace<'i>(state: ParseState<'i>) -> ParseResult<&'i str> {
     match state.residual.find(|c: char| !c.is_ascii_whitespace()) {
         Some(len) => state.advance_view(len),
-        None => StopBecause::missing_character(' ', state.start_offset)?,
+        None if state.residual.is_empty() => StopBecause::missing_character(' ', state.start_offset)?,
+        // the rest are all whitespace
+        None => state.advance_view(state.residual.len()),
     }
 }
 
@@ -51,12 +66,17 @@ pub fn ascii_whitespace<'i>(state: ParseState<'i>) -> ParseResult<&'i str> {
 /// # use pex::{ParseResult, ParseState, helpers::whitespace};
 /// let state = ParseState::new("  \na");
 /// state.skip(whitespace);
+/// // the whitespace may reach the end of input
+/// assert!(whitespace(ParseState::new(" \t\n")).is_success());
+/// assert!(whitespace(ParseState::new("")).is_failure());
 /// ```
 #[inline]
 pub fn whitespace<'i>(state: ParseState<'i>) -> ParseResult<&'i str> {
     match state.residual.find(|c: char| !c.is_whitespace()) {
         Some(len) => state.advance_view(len),
-        None => StopBecause::missing_character(' ', state.start_offset)?,
+        None if state.residual.is_empty() => StopBecause::missing_character(' ', state.start_offset)?,
+        // the rest are all whitespace
+        None => state.advance_view(state.residual.len()),
     }
 }
 
@@ -119,9 +139,15 @@ pub fn optional<T, F>(mut parse: F) -> impl FnMut(ParseState) -> ParseResult<Opt
 where
     F: FnMut(ParseState) -> ParseResult<T>,
 {
-    move |input: ParseState| match parse(input) {
-        Pending(state, value) => state.finish(Some(value)),
-        Stop(_) => input.finish(None),
+    move |input: ParseState| {
+        let mark = input.error_mark();
+        match parse(input) {
+            Pending(state, value) => state.finish(Some(value)),
+            Stop(e) => {
+                input.backtrack(mark, e);
+                input.finish(None)
+            }
+        }
     }
 }
 
@@ -14,16 +14,30 @@
 extern crate alloc;
 
 pub use crate::{
+    bytes::{ByteParsed, ByteResult, ByteState, Endian},
+    locations::{ColumnMode, Diagnostic, DiagnosticLabel, DiagnosticRenderer, SourceIndex, SourcePosition},
     patterns::{
         bracket_pair::{BracketPair, BracketPattern},
+        literal_set::LiteralSet,
+        separated::{SeparatedList, SeparatedPattern},
         NamedPattern, StringView,
     },
-    results::{CustomError, ParseResult, StopBecause},
-    states::{advance::ParseAdvance, choice::ChoiceHelper, ParseState, Parsed},
-    traits::PexNode,
+    results::{CustomError, FurthestFailure, ParseResult, StopBecause, Suggester, Suggestion},
+    states::{
+        advance::ParseAdvance,
+        choice::ChoiceHelper,
+        context::ParseContext,
+        incremental::{IncrementalParser, ReuseStats, TextEdit},
+        memo::{MemoStats, MemoTable},
+        partial::{StreamDriver, StreamStatus},
+        ParseState, Parsed,
+    },
+    traits::{PexIncremental, PexNode, PexRule, RuleRegistry},
 };
 
+mod bytes;
 pub mod helpers;
+mod locations;
 mod patterns;
 mod results;
 mod states;
@@ -31,5 +45,10 @@ mod third_party;
 mod traits;
 mod utils;
 
+#[cfg(feature = "trace")]
+pub use crate::states::trace::{ParseTrace, TraceNode};
+pub use crate::third_party::cst;
+#[cfg(feature = "derive")]
+pub use pex_derive::PexNode;
 #[cfg(feature = "regex")]
 pub use regex::Regex;
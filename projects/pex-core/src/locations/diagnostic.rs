@@ -0,0 +1,285 @@
+use crate::{
+    results::{ExpectedList, SuggestionList},
+    ColumnMode, FurthestFailure, SourceIndex, StopBecause,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt::Write, ops::Range};
+
+/// An error report with labelled ranges and notes, rendered by [DiagnosticRenderer].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// The message in the headline
+    pub message: String,
+    /// The byte range of the error, underlined by `^`
+    pub range: Range<usize>,
+    /// The label after the `^`, may be empty
+    pub label: String,
+    /// The secondary ranges, underlined by `-`
+    pub labels: Vec<DiagnosticLabel>,
+    /// The notes after the source lines
+    pub notes: Vec<String>,
+}
+
+/// A secondary range of a [Diagnostic] with its label
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiagnosticLabel {
+    /// The byte range in the source text
+    pub range: Range<usize>,
+    /// The label after the `-`, may be empty
+    pub message: String,
+}
+
+/// Render diagnostics like rustc, with line numbers, the source lines and underlined ranges.
+///
+/// Lines longer than the max width are shortened around the labels, the cut text is replaced by `...`.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{Diagnostic, DiagnosticRenderer, StopBecause};
+/// let text = "let x = (1 + 2;\n";
+/// let error = StopBecause::MissingString { message: ")", position: 14 };
+/// let diagnostic = Diagnostic::from(error)
+///     .with_label("expected `)`")
+///     .with_secondary(8..9, "unclosed delimiter")
+///     .with_note("a group must be closed on the same line");
+/// let renderer = DiagnosticRenderer::new(text, "main.calc");
+/// assert_eq!(
+///     renderer.render(&diagnostic),
+///     "\
+/// error: Missing string ')'
+///  --> main.calc:1:15
+///   |
+/// 1 | let x = (1 + 2;
+///   |         - unclosed delimiter
+///   |               ^ expected `)`
+///   |
+///   = note: a group must be closed on the same line
+/// "
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct DiagnosticRenderer<'i> {
+    index: SourceIndex<'i>,
+    file: String,
+    colored: bool,
+    max_width: usize,
+    shallow_text: &'static str,
+}
+
+/// A range on a single line, in display columns
+#[derive(Debug)]
+struct Annotation<'a> {
+    line: usize,
+    start: usize,
+    end: usize,
+    label: &'a str,
+    primary: bool,
+}
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+impl Diagnostic {
+    /// Create a diagnostic at the byte range
+    pub fn new(message: impl Into<String>, range: Range<usize>) -> Self {
+        Self { message: message.into(), range, ..Self::default() }
+    }
+    /// Set the label of the error range
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+    /// Add a secondary range with a label
+    pub fn with_secondary(mut self, range: Range<usize>, label: impl Into<String>) -> Self {
+        self.labels.push(DiagnosticLabel { range, message: label.into() });
+        self
+    }
+    /// Add a note after the source lines
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+impl From<StopBecause> for Diagnostic {
+    fn from(error: StopBecause) -> Self {
+        Self::new(error.to_string(), error.range())
+    }
+}
+
+impl From<&FurthestFailure> for Diagnostic {
+    fn from(failure: &FurthestFailure) -> Self {
+        let diagnostic = Self::new(ExpectedList(failure).to_string(), failure.position..failure.position + 1);
+        match failure.suggestions.first() {
+            Some(suggestion) => {
+                Self { range: suggestion.range.clone(), ..diagnostic }.with_label(SuggestionList(failure).to_string())
+            }
+            None => diagnostic,
+        }
+    }
+}
+
+impl<'i> DiagnosticRenderer<'i> {
+    /// Render the diagnostics of the source text, the file name is shown after `-->`
+    pub fn new(text: &'i str, file: impl Into<String>) -> Self {
+        Self { index: SourceIndex::new(text), file: file.into(), colored: false, max_width: 120, shallow_text: "..." }
+    }
+    /// Paint the report with ANSI colors, off by default
+    pub fn with_color(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+    /// Shorten the source lines wider than the columns, 120 by default
+    pub fn with_max_width(mut self, width: usize) -> Self {
+        self.max_width = width;
+        self
+    }
+    /// Replace the cut text of long lines by the text, `...` by default
+    pub fn with_shallow_text(mut self, text: &'static str) -> Self {
+        self.shallow_text = text;
+        self
+    }
+    /// Render every diagnostic, separated by an empty line
+    pub fn render_all<'d>(&self, diagnostics: impl IntoIterator<Item = &'d Diagnostic>) -> String {
+        let reports: Vec<String> = diagnostics.into_iter().map(|diagnostic| self.render(diagnostic)).collect();
+        reports.join("\n")
+    }
+    /// Render the diagnostic as a report ending with a line break
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let mut annotations = vec![self.annotation(&diagnostic.range, &diagnostic.label, true)];
+        for label in &diagnostic.labels {
+            annotations.push(self.annotation(&label.range, &label.message, false));
+        }
+        annotations.sort_by_key(|annotation| (annotation.line, annotation.start));
+        let mut lines: Vec<usize> = annotations.iter().map(|annotation| annotation.line).collect();
+        lines.dedup();
+        let width = lines.iter().max().map_or(1, |line| line.to_string().len());
+        let pad = " ".repeat(width);
+        let gutter = self.paint(BLUE, "|");
+        let position = self.index.get_position(self.clamp(diagnostic.range.start), ColumnMode::Characters);
+        let mut out = String::new();
+        writeln!(out, "{}{}", self.paint(RED, "error"), self.paint(BOLD, &format!(": {}", diagnostic.message))).unwrap();
+        writeln!(out, "{}{} {}:{}", pad, self.paint(BLUE, "-->"), self.file, position).unwrap();
+        writeln!(out, "{} {}", pad, gutter).unwrap();
+        let mut previous = None;
+        for line in lines {
+            if previous.is_some_and(|previous| line > previous + 1) {
+                writeln!(out, "{}", self.paint(BLUE, "...")).unwrap();
+            }
+            previous = Some(line);
+            let on_line: Vec<&Annotation> = annotations.iter().filter(|annotation| annotation.line == line).collect();
+            let text = display_text(self.index.get_line_text(line).unwrap_or_default());
+            let chars: Vec<char> = text.chars().collect();
+            let focus = on_line.iter().map(|a| a.start).min().unwrap_or(0)..on_line.iter().map(|a| a.end).max().unwrap_or(0);
+            let (window, left, right) = self.window(chars.len(), focus);
+            let prefix = if left { self.shallow_text.chars().count() } else { 0 };
+            let mut shown: String = chars[window.clone()].iter().collect();
+            if left {
+                shown.insert_str(0, self.shallow_text);
+            }
+            if right {
+                shown.push_str(self.shallow_text);
+            }
+            let number = self.paint(BLUE, &format!("{:>width$}", line));
+            match shown.is_empty() {
+                true => writeln!(out, "{} {}", number, gutter).unwrap(),
+                false => writeln!(out, "{} {} {}", number, gutter, shown).unwrap(),
+            }
+            for annotation in on_line {
+                let start = annotation.start.clamp(window.start, window.end) - window.start + prefix;
+                let end = annotation.end.clamp(window.start, window.end) - window.start + prefix;
+                let (marker, color) = if annotation.primary { ('^', RED) } else { ('-', BLUE) };
+                let mut underline: String = core::iter::repeat_n(marker, end.max(start + 1) - start).collect();
+                if !annotation.label.is_empty() {
+                    underline.push(' ');
+                    underline.push_str(annotation.label);
+                }
+                writeln!(out, "{} {} {}{}", pad, gutter, " ".repeat(start), self.paint(color, &underline)).unwrap();
+            }
+        }
+        if !diagnostic.notes.is_empty() {
+            writeln!(out, "{} {}", pad, gutter).unwrap();
+        }
+        for note in &diagnostic.notes {
+            writeln!(out, "{} {} {}: {}", pad, self.paint(BLUE, "="), self.paint(BOLD, "note"), note).unwrap();
+        }
+        out
+    }
+    /// Locate the range on the line of its start, the part on the following lines is not underlined
+    fn annotation<'a>(&self, range: &Range<usize>, label: &'a str, primary: bool) -> Annotation<'a> {
+        let text = self.index.get_text();
+        let start = self.clamp(range.start);
+        let line = self.index.get_position(start, ColumnMode::Bytes).line;
+        let line_range = self.index.get_line_range(line).unwrap_or(start..start);
+        let end = self.clamp(range.end).clamp(start, line_range.end.max(start));
+        let (start, end) = (start.min(line_range.end), end.min(line_range.end));
+        let head = display_width(&text[line_range.start..start]);
+        let body = display_width(&text[start..end.max(start)]);
+        Annotation { line, start: head, end: head + body.max(1), label, primary }
+    }
+    /// Clamp the offset into the text and to a character boundary
+    fn clamp(&self, offset: usize) -> usize {
+        let text = self.index.get_text();
+        let mut offset = offset.min(text.len());
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        offset
+    }
+    /// The shown columns of a line, and whether the text is cut before and after them
+    fn window(&self, width: usize, focus: Range<usize>) -> (Range<usize>, bool, bool) {
+        if width <= self.max_width {
+            return (0..width, false, false);
+        }
+        let shallow = self.shallow_text.chars().count();
+        let inner = self.max_width.saturating_sub(2 * shallow).max(1);
+        let span = focus.end.saturating_sub(focus.start);
+        let start = match span < inner {
+            true => focus.start.saturating_sub((inner - span) / 2),
+            false => focus.start,
+        };
+        let start = start.min(width - inner);
+        let outer = self.max_width.saturating_sub(shallow).max(1);
+        if start == 0 {
+            (0..outer, false, true)
+        }
+        else if start + inner >= width {
+            (width - outer..width, true, false)
+        }
+        else {
+            (start..start + inner, true, true)
+        }
+    }
+    fn paint(&self, style: &str, text: &str) -> String {
+        match self.colored {
+            true => format!("{}{}{}", style, text, RESET),
+            false => text.to_string(),
+        }
+    }
+}
+
+/// The line as shown, a tab takes 4 columns
+fn display_text(line: &str) -> String {
+    line.replace('\t', "    ")
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| {
+            if c == '\t' {
+                4
+            }
+            else {
+                1
+            }
+        })
+        .sum()
+}
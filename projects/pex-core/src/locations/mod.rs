@@ -0,0 +1,196 @@
+pub use self::diagnostic::{Diagnostic, DiagnosticLabel, DiagnosticRenderer};
+use crate::{ParseState, StopBecause, StringView};
+use alloc::{vec, vec::Vec};
+use core::{
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+mod diagnostic;
+
+/// Defines how to count the columns of a line
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ColumnMode {
+    /// Count columns in utf8 bytes, used by rust
+    Bytes,
+    /// Count columns in unicode scalar values
+    Characters,
+    /// Count columns in utf16 code units, used by LSP and javascript
+    Utf16,
+}
+
+/// A 1-based line and column position in the source text
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SourcePosition {
+    /// The line number, starts from 1
+    pub line: usize,
+    /// The column number, starts from 1
+    pub column: usize,
+}
+
+/// Maps byte offsets to lines and columns and back, build once and query many times.
+///
+/// Line breaks are `\n`, `\r\n` and a single `\r`, the line break itself belongs to the line it ends.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{ColumnMode, SourceIndex, SourcePosition};
+/// let index = SourceIndex::new("let a = 1;\r\nlet 𝑥 = \"ß\";\n");
+/// assert_eq!(index.line_count(), 3);
+/// // `=` after `𝑥`
+/// let offset = "let a = 1;\r\nlet 𝑥 ".len();
+/// assert_eq!(
+///     index.get_position(offset, ColumnMode::Bytes),
+///     SourcePosition { line: 2, column: 10 }
+/// );
+/// assert_eq!(
+///     index.get_position(offset, ColumnMode::Characters),
+///     SourcePosition { line: 2, column: 7 }
+/// );
+/// assert_eq!(
+///     index.get_position(offset, ColumnMode::Utf16),
+///     SourcePosition { line: 2, column: 8 }
+/// );
+/// assert_eq!(
+///     index.get_offset(SourcePosition { line: 2, column: 8 }, ColumnMode::Utf16),
+///     Some(offset)
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct SourceIndex<'i> {
+    text: &'i str,
+    line_starts: Vec<usize>,
+}
+
+impl ColumnMode {
+    /// Count the columns occupied by the text
+    pub fn count(&self, text: &str) -> usize {
+        match self {
+            ColumnMode::Bytes => text.len(),
+            ColumnMode::Characters => text.chars().count(),
+            ColumnMode::Utf16 => text.chars().map(char::len_utf16).sum(),
+        }
+    }
+    /// Count the columns occupied by the character
+    pub fn count_char(&self, c: char) -> usize {
+        match self {
+            ColumnMode::Bytes => c.len_utf8(),
+            ColumnMode::Characters => 1,
+            ColumnMode::Utf16 => c.len_utf16(),
+        }
+    }
+}
+
+impl Display for SourcePosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl<'i> SourceIndex<'i> {
+    /// Scan all line breaks of the text
+    pub fn new(text: &'i str) -> Self {
+        let mut line_starts = vec![0];
+        let bytes = text.as_bytes();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            match bytes[offset] {
+                b'\r' if bytes.get(offset + 1) == Some(&b'\n') => {
+                    offset += 1;
+                    line_starts.push(offset + 1)
+                }
+                b'\r' | b'\n' => line_starts.push(offset + 1),
+                _ => {}
+            }
+            offset += 1;
+        }
+        Self { text, line_starts }
+    }
+    /// Get the indexed text
+    pub fn get_text(&self) -> &'i str {
+        self.text
+    }
+    /// Count the lines of the text, an empty text has one line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+    /// Get the byte range of the 1-based line, excluding the line break.
+    pub fn get_line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = match self.line_starts.get(line) {
+            Some(next) => {
+                let body = &self.text[start..*next];
+                next - (body.len() - body.trim_end_matches(['\n', '\r']).len())
+            }
+            None => self.text.len(),
+        };
+        Some(start..end)
+    }
+    /// Get the text of the 1-based line, excluding the line break.
+    pub fn get_line_text(&self, line: usize) -> Option<&'i str> {
+        self.get_line_range(line).map(|range| &self.text[range])
+    }
+    /// Get the line and column of the byte offset.
+    ///
+    /// Offsets beyond the text are clamped to the end, offsets inside a character or a line break are clamped to
+    /// the previous valid column.
+    pub fn get_position(&self, offset: usize, mode: ColumnMode) -> SourcePosition {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line + 1,
+            Err(line) => line,
+        };
+        let range = self.get_line_range(line).unwrap_or(offset..offset);
+        let column = mode.count(&self.text[range.start..offset.min(range.end)]) + 1;
+        SourcePosition { line, column }
+    }
+    /// Get the line and column range of the byte range.
+    pub fn get_position_range(&self, range: Range<usize>, mode: ColumnMode) -> Range<SourcePosition> {
+        self.get_position(range.start, mode)..self.get_position(range.end, mode)
+    }
+    /// Get the byte offset of the line and column.
+    ///
+    /// Returns `None` if the position is out of the line, or inside a character.
+    pub fn get_offset(&self, position: SourcePosition, mode: ColumnMode) -> Option<usize> {
+        let range = self.get_line_range(position.line)?;
+        let mut rest = position.column.checked_sub(1)?;
+        let mut offset = range.start;
+        for c in self.text[range].chars() {
+            if rest == 0 {
+                break;
+            }
+            rest = rest.checked_sub(mode.count_char(c))?;
+            offset += c.len_utf8();
+        }
+        match rest {
+            0 => Some(offset),
+            _ => None,
+        }
+    }
+}
+
+impl<'i> ParseState<'i> {
+    /// Get the line and column of the current offset
+    #[inline]
+    pub fn get_position(&self, index: &SourceIndex, mode: ColumnMode) -> SourcePosition {
+        index.get_position(self.start_offset, mode)
+    }
+}
+
+impl<'i> StringView<'i> {
+    /// Get the line and column range of the view
+    pub fn as_position_range(&self, index: &SourceIndex, mode: ColumnMode) -> Range<SourcePosition> {
+        index.get_position_range(self.as_range(), mode)
+    }
+}
+
+impl StopBecause {
+    /// Get the line and column range of the error
+    pub fn position_range(&self, index: &SourceIndex, mode: ColumnMode) -> Range<SourcePosition> {
+        index.get_position_range(self.range(), mode)
+    }
+}
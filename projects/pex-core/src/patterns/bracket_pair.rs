@@ -1,7 +1,29 @@
-use crate::{ParseResult, ParseState, StringView};
+use crate::{
+    helpers::balanced_offset,
+    ParseResult,
+    ParseResult::{Pending, Stop},
+    ParseState, StopBecause, StringView,
+};
 use alloc::vec::Vec;
 
 /// A bracket pattern like `[]` or `(1, )`
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{helpers::{decimal_string, whitespace}, BracketPattern, ParseContext, ParseState};
+/// let pattern = BracketPattern::new("[", "]").with_lossless(true).with_recover(true);
+/// let pair = pattern.consume(ParseState::new("[1 , 2,]"), whitespace, decimal_string).unwrap();
+/// assert_eq!(pair.body, ["1", "2"]);
+/// assert_eq!(pair.delimiters.iter().map(|d| d.start_offset()).collect::<Vec<_>>(), [3, 6]);
+/// assert_eq!(pair.trivia.iter().map(|t| t.as_range()).collect::<Vec<_>>(), [2..3, 4..5]);
+/// // skip the broken element to the next delimiter
+/// let context = ParseContext::default().with_max_errors(8);
+/// let state = ParseState::new("[1, x y, 3]").with_context(&context);
+/// let (pair, errors) = pattern.consume(state, whitespace, decimal_string).recovered(&context);
+/// assert_eq!(pair.unwrap().body, ["1", "3"]);
+/// assert_eq!(errors[0].range().start, 4);
+/// ```
 #[derive(Copy, Clone, Debug)]
 pub struct BracketPattern {
     /// The open bracket pattern
@@ -14,6 +36,10 @@ pub struct BracketPattern {
     pub dangling: Option<bool>,
     /// Need add delimiter if there is only one element
     pub one_tailing: bool,
+    /// Keep the delimiters, trivia and skipped elements in the [BracketPair]
+    pub lossless: bool,
+    /// Skip the failed element to the next delimiter or the close bracket, only works in recovery mode
+    pub recover: bool,
 }
 
 /// A bracket pair like `[1, 2, 3]`
@@ -25,12 +51,18 @@ pub struct BracketPair<'i, T> {
     pub rhs: StringView<'i>,
     /// The elements in the bracket
     pub body: Vec<T>,
+    /// The delimiters after the elements, empty if not lossless
+    pub delimiters: Vec<StringView<'i>>,
+    /// The text eaten by the ignore parser, empty if not lossless
+    pub trivia: Vec<StringView<'i>>,
+    /// The broken elements skipped by recovery, empty if not lossless
+    pub skipped: Vec<StringView<'i>>,
 }
 
 impl BracketPattern {
     /// Create a new bracket pattern
     pub fn new(open: &'static str, close: &'static str) -> Self {
-        Self { open, close, delimiter: ",", dangling: None, one_tailing: false }
+        Self { open, close, delimiter: ",", dangling: None, one_tailing: false, lossless: false, recover: false }
     }
     /// Create a new bracket pattern
     pub fn with_delimiter(mut self, delimiter: &'static str) -> Self {
@@ -47,6 +79,16 @@ impl BracketPattern {
         self.one_tailing = one_tailing;
         self
     }
+    /// Keep the delimiters and trivia, so that the brackets can be printed back as is
+    pub fn with_lossless(mut self, lossless: bool) -> Self {
+        self.lossless = lossless;
+        self
+    }
+    /// Skip the failed element and record the error, see [ParseContext::with_max_errors](crate::ParseContext::with_max_errors)
+    pub fn with_recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
 }
 
 impl BracketPattern {
@@ -73,12 +115,17 @@ impl BracketPattern {
     where
         I: FnMut(ParseState<'i>) -> ParseResult<'i, U>,
     {
-        let (s_rhs, lhs) = input.match_str(self.open)?;
-        let (finally, rhs) = s_rhs.skip(ignore).match_str(self.close)?;
+        let mut trivia = Vec::new();
+        let (state, lhs) = input.match_str(self.open)?;
+        let s_rhs = self.skip_trivia(state, ignore, &mut trivia);
+        let (finally, rhs) = s_rhs.match_str(self.close)?;
         finally.finish(BracketPair {
             lhs: StringView::new(lhs, input.start_offset),
             rhs: StringView::new(rhs, s_rhs.start_offset),
             body: Vec::new(),
+            delimiters: Vec::new(),
+            trivia,
+            skipped: Vec::new(),
         })
     }
     /// `[ ~ term (~ , ~ term)* ~ ,? ~ ]`
@@ -88,45 +135,134 @@ impl BracketPattern {
         I: FnMut(ParseState<'i>) -> ParseResult<'i, U> + Copy,
     {
         let mut terms = Vec::with_capacity(1);
+        let mut delimiters = Vec::new();
+        let mut trivia = Vec::new();
+        let mut skipped = Vec::new();
         let (state, lhs) = input.match_str(self.open)?;
-        let (state, first) = state.skip(ignore).match_fn(parser)?;
-        terms.push(first);
-        let (state, _) = state.match_repeats(|s| self.delimiter_term(s, ignore, parser, &mut terms))?;
+        let state = self.skip_trivia(state, ignore, &mut trivia);
+        let (state, first) = self.element(state, ignore, parser, &mut skipped)?;
+        terms.extend(first);
+        let (state, _) = state.match_repeats(|s| {
+            let mark = (trivia.len(), skipped.len());
+            match self.delimiter_term(s, ignore, parser, &mut trivia, &mut skipped) {
+                Pending(state, (delimiter, term)) => {
+                    if self.lossless {
+                        delimiters.push(delimiter);
+                    }
+                    terms.extend(term);
+                    state.finish(())
+                }
+                Stop(e) => {
+                    trivia.truncate(mark.0);
+                    skipped.truncate(mark.1);
+                    Stop(e)
+                }
+            }
+        })?;
+        let mark = trivia.len();
+        let s_delimiter = self.skip_trivia(state, ignore, &mut trivia);
         let s_rhs = if self.one_tailing && terms.len() == 1 {
-            state.skip(ignore).match_str(self.delimiter)?.0
+            self.match_delimiter(s_delimiter, &mut delimiters)?.0
         }
         else {
             match self.dangling {
-                Some(true) => state.skip(ignore).match_str(self.delimiter)?.0,
-                Some(false) => state,
-                None => match state.skip(ignore).match_str(self.delimiter) {
-                    ParseResult::Pending(s, _) => s,
-                    ParseResult::Stop(_) => state,
+                Some(true) => self.match_delimiter(s_delimiter, &mut delimiters)?.0,
+                Some(false) => {
+                    trivia.truncate(mark);
+                    state
+                }
+                None => match self.match_delimiter(s_delimiter, &mut delimiters) {
+                    Pending(s, _) => s,
+                    Stop(e) => {
+                        state.record_error(e);
+                        trivia.truncate(mark);
+                        state
+                    }
                 },
             }
         };
-        let (finally, rhs) = s_rhs.skip(ignore).match_str(self.close)?;
+        let s_rhs = self.skip_trivia(s_rhs, ignore, &mut trivia);
+        let (finally, rhs) = s_rhs.match_str(self.close)?;
         finally.finish(BracketPair {
             lhs: StringView::new(lhs, input.start_offset),
             rhs: StringView::new(rhs, s_rhs.start_offset),
             body: terms,
+            delimiters,
+            trivia,
+            skipped,
         })
     }
     /// `~ , ~ term`
-    fn delimiter_term<'i, 't, F, I, T, U>(
+    fn delimiter_term<'i, F, I, T, U>(
         &self,
         input: ParseState<'i>,
         ignore: I,
         parser: F,
-        terms: &'t mut Vec<T>,
-    ) -> ParseResult<'i, ()>
+        trivia: &mut Vec<StringView<'i>>,
+        skipped: &mut Vec<StringView<'i>>,
+    ) -> ParseResult<'i, (StringView<'i>, Option<T>)>
     where
         F: FnMut(ParseState<'i>) -> ParseResult<'i, T> + Copy,
         I: FnMut(ParseState<'i>) -> ParseResult<'i, U> + Copy,
     {
-        let (state, _) = input.skip(ignore).match_str(self.delimiter)?;
-        let (state, term) = state.skip(ignore).match_fn(parser)?;
-        terms.push(term);
+        let state = self.skip_trivia(input, ignore, trivia);
+        let (state, delimiter) = state.match_str(self.delimiter)?;
+        let delimiter = StringView::new(delimiter, state.start_offset - delimiter.len());
+        let state = self.skip_trivia(state, ignore, trivia);
+        let (state, term) = self.element(state, ignore, parser, skipped)?;
+        state.finish((delimiter, term))
+    }
+    /// `term`, or skip to `,` or `]` in recovery mode
+    fn element<'i, F, I, T, U>(
+        &self,
+        input: ParseState<'i>,
+        ignore: I,
+        mut parser: F,
+        skipped: &mut Vec<StringView<'i>>,
+    ) -> ParseResult<'i, Option<T>>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        I: FnMut(ParseState<'i>) -> ParseResult<'i, U> + Copy,
+    {
+        if !self.recover || !input.can_recover() {
+            return input.match_fn(parser).map_inner(Some);
+        }
+        let parse = |s: ParseState<'i>| {
+            let (state, term) = s.match_fn(&mut parser)?;
+            // the element must end before the delimiter or the close bracket
+            let next = state.skip(ignore);
+            if !next.residual.starts_with(self.delimiter) && !next.residual.starts_with(self.close) {
+                StopBecause::missing_string(self.delimiter, next.start_offset)?
+            }
+            state.finish(Some(term))
+        };
+        input.recover_with(parse, |s, _| {
+            let brackets = [("(", ")"), ("[", "]"), ("{", "}"), (self.open, self.close)];
+            let offset = balanced_offset(s.residual, &brackets, &[self.delimiter, self.close]);
+            let (state, text) = s.advance_view(offset)?;
+            if self.lossless && !text.is_empty() {
+                skipped.push(StringView::new(text, s.start_offset));
+            }
+            state.finish(None)
+        })
+    }
+    /// `,`
+    fn match_delimiter<'i>(&self, input: ParseState<'i>, delimiters: &mut Vec<StringView<'i>>) -> ParseResult<'i, ()> {
+        let (state, delimiter) = input.match_str(self.delimiter)?;
+        if self.lossless {
+            delimiters.push(StringView::new(delimiter, input.start_offset));
+        }
         state.finish(())
     }
+    /// `~`, keep the eaten text if lossless
+    fn skip_trivia<'i, I, U>(&self, input: ParseState<'i>, ignore: I, trivia: &mut Vec<StringView<'i>>) -> ParseState<'i>
+    where
+        I: FnMut(ParseState<'i>) -> ParseResult<'i, U>,
+    {
+        let state = input.skip(ignore);
+        if self.lossless && state.start_offset > input.start_offset {
+            trivia.push(StringView::new(&input.residual[..state.start_offset - input.start_offset], input.start_offset));
+        }
+        state
+    }
 }
@@ -0,0 +1,163 @@
+use crate::{ParseResult, ParseState, StopBecause, StringView};
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// A set of literals with values, always matches the longest literal at the current offset.
+///
+/// The literals are compiled to a byte trie, so the match takes one pass no matter how many literals share a prefix.
+/// If nothing matches, every literal is recorded as an expectation of the [ParseContext](crate::ParseContext),
+/// and without a context the error names the whole set.
+/// The text of the whole set is leaked each time the set changes, so build the set once and reuse it.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{LiteralSet, ParseContext, ParseState, StopBecause};
+/// let operators = LiteralSet::new([("=", 1), ("==", 2), ("===", 3), ("=>", 4)]);
+/// let (text, value) = operators.parse(ParseState::new("=== b")).unwrap();
+/// assert_eq!((text.as_ref(), *value), ("===", 3));
+/// let (text, value) = operators.parse(ParseState::new("=>")).unwrap();
+/// assert_eq!((text.as_ref(), *value), ("=>", 4));
+/// // backtrack to the longest literal that matched
+/// let (text, value) = operators.parse(ParseState::new("==!")).unwrap();
+/// assert_eq!((text.as_ref(), *value), ("==", 2));
+/// let context = ParseContext::default();
+/// assert!(operators.parse(ParseState::new("!=").with_context(&context)).is_failure());
+/// assert_eq!(
+///     context.get_furthest_failure().unwrap().to_string(),
+///     "expected one of `=`, `==`, `===`, `=>` at 0"
+/// );
+/// // without a context, the error still names the whole set
+/// assert_eq!(
+///     operators.parse(ParseState::new("!=")).as_result().unwrap_err(),
+///     StopBecause::MustBe { message: "one of `=`, `==`, `===`, `=>`", position: 0 }
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct LiteralSet<T> {
+    literals: Vec<(&'static str, T)>,
+    nodes: Vec<LiteralNode>,
+    /// The text of the whole set, `one of ...`
+    expected: &'static str,
+}
+
+#[derive(Clone, Debug, Default)]
+struct LiteralNode {
+    /// The next nodes by byte, sorted for binary search
+    next: Vec<(u8, usize)>,
+    /// The index of the literal ends here
+    literal: Option<usize>,
+}
+
+impl<T> Default for LiteralSet<T> {
+    fn default() -> Self {
+        Self { literals: Vec::new(), nodes: alloc::vec![LiteralNode::default()], expected: "LITERAL" }
+    }
+}
+
+impl<T> LiteralSet<T> {
+    /// Create a new literal set, the later value wins if a literal is given twice
+    pub fn new<I>(literals: I) -> Self
+    where
+        I: IntoIterator<Item = (&'static str, T)>,
+    {
+        let mut out = Self::default();
+        for (literal, value) in literals {
+            out.insert_literal(literal, value);
+        }
+        out.describe();
+        out
+    }
+    /// Add a literal to the set, replace the value if it was already in the set
+    pub fn with_literal(mut self, literal: &'static str, value: T) -> Self {
+        self.insert(literal, value);
+        self
+    }
+    /// Add a literal to the set, return the old value if it was already in the set
+    pub fn insert(&mut self, literal: &'static str, value: T) -> Option<T> {
+        let old = self.insert_literal(literal, value);
+        if old.is_none() {
+            self.describe();
+        }
+        old
+    }
+    fn insert_literal(&mut self, literal: &'static str, value: T) -> Option<T> {
+        let mut node = 0;
+        for byte in literal.bytes() {
+            node = match self.nodes[node].next.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(index) => self.nodes[node].next[index].1,
+                Err(index) => {
+                    let new = self.nodes.len();
+                    self.nodes[node].next.insert(index, (byte, new));
+                    self.nodes.push(LiteralNode::default());
+                    new
+                }
+            };
+        }
+        match self.nodes[node].literal {
+            Some(index) => Some(core::mem::replace(&mut self.literals[index].1, value)),
+            None => {
+                self.nodes[node].literal = Some(self.literals.len());
+                self.literals.push((literal, value));
+                None
+            }
+        }
+    }
+    fn describe(&mut self) {
+        let mut expected = String::new();
+        for (index, (literal, _)) in self.literals.iter().enumerate() {
+            expected.push_str(if index == 0 { "one of `" } else { ", `" });
+            expected.push_str(literal);
+            expected.push('`');
+        }
+        if !expected.is_empty() {
+            self.expected = Box::leak(expected.into_boxed_str());
+        }
+    }
+    /// Get all literals in the order they were added
+    pub fn get_literals(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.literals.iter().map(|(literal, _)| *literal)
+    }
+    /// Get the value of a literal
+    pub fn get_value(&self, literal: &str) -> Option<&T> {
+        let index = self.literals.iter().position(|(k, _)| literal.eq(*k))?;
+        Some(&self.literals[index].1)
+    }
+    /// Match the longest literal at the current offset
+    pub fn parse<'i>(&self, input: ParseState<'i>) -> ParseResult<'i, (StringView<'i>, &T)> {
+        let mut node = 0;
+        let mut longest = self.nodes[0].literal.map(|index| (0, index));
+        let mut exhausted = true;
+        for (offset, byte) in input.residual.bytes().enumerate() {
+            match self.nodes[node].next.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(index) => node = self.nodes[node].next[index].1,
+                Err(_) => {
+                    exhausted = false;
+                    break;
+                }
+            }
+            if let Some(index) = self.nodes[node].literal {
+                longest = Some((offset + 1, index));
+            }
+        }
+        // a longer literal may follow in the next chunk
+        if exhausted && input.partial && !self.nodes[node].next.is_empty() {
+            StopBecause::incomplete(input.start_offset)?
+        }
+        match longest {
+            Some((length, index)) => {
+                let (state, text) = input.advance_view(length)?;
+                state.finish((StringView::new(text, input.start_offset), &self.literals[index].1))
+            }
+            None => match (input.context, self.literals.first()) {
+                // the literals are listed one by one in the context, and the first one is merged again
+                (Some(context), Some((literal, _))) => {
+                    for (literal, _) in self.literals.iter() {
+                        context.record_error(StopBecause::MissingString { message: literal, position: input.start_offset });
+                    }
+                    StopBecause::missing_string(literal, input.start_offset)?
+                }
+                _ => StopBecause::must_be(self.expected, input.start_offset)?,
+            },
+        }
+    }
+}
@@ -6,6 +6,8 @@ use core::{
 };
 
 pub mod bracket_pair;
+pub mod literal_set;
+pub mod separated;
 
 /// A string pattern with a message for error reporting
 ///
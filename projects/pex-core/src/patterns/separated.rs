@@ -0,0 +1,146 @@
+use crate::{
+    ParseResult,
+    ParseResult::{Pending, Stop},
+    ParseState, StopBecause, StringView,
+};
+use alloc::vec::Vec;
+
+/// A list of items separated by a delimiter, such as `a, b, c` or `A | B | C`
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{helpers::{decimal_string, whitespace}, ParseState, SeparatedPattern};
+/// let union = SeparatedPattern::new("|").with_min(1);
+/// let list = union.consume(ParseState::new(" 1 | 23 |4 ;"), whitespace, decimal_string).unwrap();
+/// assert_eq!(list.items, ["1", "23", "4"]);
+/// assert_eq!(list.separators[1].as_range(), 8..9);
+/// // the trailing separator is not consumed if forbidden
+/// let forbid = SeparatedPattern::new(",").with_dangling(false);
+/// let (state, list) =
+///     forbid.consume(ParseState::new("1, 2,"), whitespace, decimal_string).as_result().unwrap();
+/// assert_eq!((list.items.len(), state.residual), (2, ","));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct SeparatedPattern {
+    /// The delimiter between items
+    pub delimiter: &'static str,
+    /// Whether the last item can be followed by a delimiter, `None` for optional
+    pub dangling: Option<bool>,
+    /// The minimum count of items
+    pub min: usize,
+    /// The maximum count of items
+    pub max: usize,
+}
+
+/// A list matched by [SeparatedPattern]
+#[derive(Debug)]
+pub struct SeparatedList<'i, T> {
+    /// The items in the list
+    pub items: Vec<T>,
+    /// The delimiters after each item except the last, plus the dangling one if any
+    pub separators: Vec<StringView<'i>>,
+}
+
+impl SeparatedPattern {
+    /// Create a new separated pattern
+    pub fn new(delimiter: &'static str) -> Self {
+        Self { delimiter, dangling: None, min: 0, max: usize::MAX }
+    }
+    /// Require or forbid the dangling delimiter after the last item
+    pub fn with_dangling(mut self, dangling: bool) -> Self {
+        self.dangling = Some(dangling);
+        self
+    }
+    /// Set the minimum count of items
+    pub fn with_min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+    /// Set the maximum count of items
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+}
+
+impl SeparatedPattern {
+    /// ```js
+    /// (~ term (~ , ~ term)* (~ ,)?)?
+    /// ```
+    pub fn consume<'i, F, I, T, U>(
+        &self,
+        input: ParseState<'i>,
+        mut ignore: I,
+        mut parser: F,
+    ) -> ParseResult<'i, SeparatedList<'i, T>>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        I: FnMut(ParseState<'i>) -> ParseResult<'i, U>,
+    {
+        let mut list = SeparatedList { items: Vec::new(), separators: Vec::new() };
+        if self.max == 0 {
+            return input.finish(list);
+        }
+        let mark = input.error_mark();
+        let mut state = match input.skip(&mut ignore).match_fn(&mut parser) {
+            Pending(state, item) => {
+                list.items.push(item);
+                state
+            }
+            Stop(e) if e.is_fatal() || self.min > 0 => return Stop(e),
+            Stop(e) => {
+                input.backtrack(mark, e);
+                return input.finish(list);
+            }
+        };
+        let mut dangling = false;
+        while list.items.len() < self.max {
+            let mark = state.error_mark();
+            let (sep_state, sep) = match state.skip(&mut ignore).match_str(self.delimiter) {
+                Pending(s, sep) => (s, sep),
+                Stop(e) if e.is_fatal() => return Stop(e),
+                Stop(e) => {
+                    state.backtrack(mark, e);
+                    break;
+                }
+            };
+            let separator = StringView::new(sep, sep_state.start_offset - sep.len());
+            match sep_state.skip(&mut ignore).match_fn(&mut parser) {
+                Pending(new, _) if new.start_offset == state.start_offset => StopBecause::zero_progress(new.start_offset)?,
+                Pending(new, item) => {
+                    list.items.push(item);
+                    list.separators.push(separator);
+                    state = new;
+                }
+                Stop(e) if e.is_fatal() => return Stop(e),
+                Stop(e) if self.dangling == Some(false) => {
+                    state.backtrack(mark, e);
+                    break;
+                }
+                Stop(e) => {
+                    state.backtrack(mark, e);
+                    list.separators.push(separator);
+                    state = sep_state;
+                    dangling = true;
+                    break;
+                }
+            }
+        }
+        if list.items.len() < self.min {
+            Err(StopBecause::ExpectRepeats { min: self.min, current: list.items.len(), position: input.start_offset })?
+        }
+        // the list is full, or the delimiter is missing
+        if !dangling && self.dangling != Some(false) {
+            match state.skip(&mut ignore).match_str(self.delimiter) {
+                Pending(s, sep) => {
+                    list.separators.push(StringView::new(sep, s.start_offset - sep.len()));
+                    state = s;
+                }
+                Stop(e) if self.dangling == Some(true) || e.is_fatal() => return Stop(e),
+                Stop(e) => state.record_error(e),
+            }
+        }
+        state.finish(list)
+    }
+}
@@ -0,0 +1,144 @@
+use super::*;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// The furthest position where parsing failed, and all expectations seen there.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{FurthestFailure, StopBecause};
+/// let mut failure = FurthestFailure::default();
+/// failure.merge(StopBecause::MissingString { message: "(", position: 3 });
+/// failure.merge(StopBecause::MissingString { message: ")", position: 12 });
+/// failure.merge(StopBecause::MissingString { message: ",", position: 12 });
+/// failure.merge(StopBecause::MustBe { message: "IDENT", position: 12 });
+/// assert_eq!(failure.to_string(), "expected one of `)`, `,`, IDENT at 12");
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FurthestFailure {
+    /// The furthest offset where an error occurred
+    pub position: usize,
+    /// Every expectation failed at the position, in the order they were seen
+    pub expected: Vec<StopBecause>,
+    /// The ranked suggestions for the word at the position, see [FurthestFailure::with_suggestions]
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl FurthestFailure {
+    /// Check if no error has been merged
+    pub fn is_empty(&self) -> bool {
+        self.expected.is_empty()
+    }
+    /// The expectations without the position and the suggestions, the message of [crate::Diagnostic]
+    ///
+    /// ```
+    /// # use pex::{FurthestFailure, StopBecause};
+    /// let mut failure = FurthestFailure::default();
+    /// failure.merge(StopBecause::MissingString { message: ")", position: 12 });
+    /// failure.merge(StopBecause::MustBe { message: "IDENT", position: 12 });
+    /// assert_eq!(failure.get_expected_message(), "expected one of `)`, IDENT");
+    /// ```
+    pub fn get_expected_message(&self) -> String {
+        ExpectedList(self).to_string()
+    }
+    /// Merge an error, keep it if it is further than or as far as the current errors.
+    pub fn merge(&mut self, error: StopBecause) {
+        if let StopBecause::Uninitialized = error {
+            return;
+        }
+        let position = error.range().start;
+        if self.expected.is_empty() || position > self.position {
+            self.position = position;
+            self.expected.clear();
+            self.suggestions.clear();
+            self.expected.push(error);
+        }
+        else if position == self.position && !self.expected.contains(&error) {
+            self.expected.push(error);
+        }
+    }
+}
+
+impl Error for FurthestFailure {}
+
+impl Display for FurthestFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.is_empty() {
+            true => f.write_str("Uninitialized")?,
+            false => write!(f, "{} at {}", ExpectedList(self), self.position)?,
+        }
+        match self.suggestions.is_empty() {
+            true => Ok(()),
+            false => write!(f, ", {}", SuggestionList(self)),
+        }
+    }
+}
+
+/// The suggestions of a failure, `did you mean ...?`
+pub(crate) struct SuggestionList<'a>(pub &'a FurthestFailure);
+
+impl<'a> Display for SuggestionList<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("did you mean ")?;
+        let count = self.0.suggestions.len();
+        for (index, suggestion) in self.0.suggestions.iter().enumerate() {
+            match index {
+                0 => {}
+                _ if index + 1 == count => f.write_str(" or ")?,
+                _ => f.write_str(", ")?,
+            }
+            write!(f, "`{}`", suggestion.text)?;
+        }
+        f.write_char('?')
+    }
+}
+
+/// The expectations of a failure without the position, `expected one of ...`
+pub(crate) struct ExpectedList<'a>(pub &'a FurthestFailure);
+
+impl<'a> Display for ExpectedList<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.0.expected.as_slice() {
+            [] => f.write_str("nothing")?,
+            [one] => {
+                f.write_str("expected ")?;
+                write_expected(one, f)?;
+            }
+            many => {
+                f.write_str("expected one of ")?;
+                for (index, error) in many.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_expected(error, f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_expected(error: &StopBecause, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match error {
+        StopBecause::Uninitialized => f.write_str("nothing"),
+        StopBecause::ExpectEOF { .. } => f.write_str("end of file"),
+        StopBecause::ExpectRepeats { min, .. } => write!(f, "at least {} repeats", min),
+        StopBecause::MissingCharacterRange { start, end, .. } => match start.eq(end) {
+            true => write!(f, "`{}`", start),
+            false => write!(f, "'{}'..='{}'", start, end),
+        },
+        StopBecause::MissingCharacterSet { expected, .. } => f.write_str(expected),
+        StopBecause::MissingString { message, .. } => write!(f, "`{}`", message),
+        StopBecause::MustBe { message, .. } => f.write_str(message),
+        StopBecause::ShouldNotBe { message, .. } => write!(f, "not {}", message),
+        StopBecause::Incomplete { .. } => f.write_str("more input"),
+        StopBecause::TooDeep { limit, .. } => write!(f, "nesting within {}", limit),
+        StopBecause::OutOfFuel { .. } => f.write_str("more fuel"),
+        StopBecause::ZeroProgress { .. } => f.write_str("progress"),
+        StopBecause::ReservedKeyword { keyword, .. } => write!(f, "not keyword `{}`", keyword),
+        StopBecause::Custom(v) => f.write_str(v.message),
+    }
+}
@@ -1,4 +1,6 @@
 use super::*;
+use crate::ParseContext;
+use alloc::vec::Vec;
 
 impl<'i, T> Debug for ParseResult<'i, T>
 where
@@ -126,6 +128,19 @@ impl<'i, T> ParseResult<'i, T> {
             ParseResult::Stop(e) => panic!("{e:?}"),
         }
     }
+    /// Finish the parse in recovery mode, get the partial value and every recovered error.
+    ///
+    /// If the parse stopped, the final error is appended to the errors.
+    #[inline]
+    pub fn recovered(self, context: &ParseContext) -> (Option<T>, Vec<StopBecause>) {
+        match self {
+            Self::Pending(_, value) => (Some(value), context.take_errors()),
+            Self::Stop(reason) => {
+                context.push_error(reason);
+                (None, context.take_errors())
+            }
+        }
+    }
     /// Check whether a match is successful, note that an empty match is always successful.
     ///
     /// # Examples
@@ -144,6 +159,14 @@ impl<'i, T> ParseResult<'i, T> {
             Self::Stop(..) => false,
         }
     }
+    /// Check whether a match stopped at the end of partial input, see [ParseState::with_partial].
+    #[inline(always)]
+    pub fn is_incomplete(&self) -> bool {
+        match self {
+            Self::Pending(..) => false,
+            Self::Stop(e) => e.is_incomplete(),
+        }
+    }
     /// Check whether a match is failed, note that an empty match never fails.
     ///
     /// # Examples
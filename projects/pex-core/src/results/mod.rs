@@ -1,16 +1,23 @@
 use core::{
     convert::Infallible,
     error::Error,
-    fmt::{Debug, Display, Formatter},
+    fmt::{Debug, Display, Formatter, Write},
     ops::{ControlFlow, FromResidual, Range, Try},
 };
 
+pub(crate) use self::furthest::{ExpectedList, SuggestionList};
+pub use self::{
+    furthest::FurthestFailure,
+    suggest::{Suggester, Suggestion},
+};
 use crate::{ParseState, Parsed};
 
 mod from_std;
+mod furthest;
 mod methods;
 mod reason;
 mod residual;
+mod suggest;
 
 /// Represent as parsing result
 #[derive(Eq, PartialEq)]
@@ -77,6 +84,35 @@ pub enum StopBecause {
         /// The offset of the location where the error occurred
         position: usize,
     },
+    /// Reach the end of partial input, need more input to decide
+    Incomplete {
+        /// The offset of the location where the error occurred
+        position: usize,
+    },
+    /// Nested calls went deeper than the limit, see [ParseContext::with_max_depth](crate::ParseContext::with_max_depth)
+    TooDeep {
+        /// The max nesting depth
+        limit: usize,
+        /// The offset of the location where the error occurred
+        position: usize,
+    },
+    /// The step budget ran out or the parse was cancelled, see [ParseContext::with_fuel](crate::ParseContext::with_fuel)
+    OutOfFuel {
+        /// The offset of the location where the error occurred
+        position: usize,
+    },
+    /// An iteration of a repeat succeeded without consuming input, which would loop forever
+    ZeroProgress {
+        /// The offset of the location where the error occurred
+        position: usize,
+    },
+    /// A reserved keyword used as an identifier, see [KeywordSet](crate::helpers::KeywordSet)
+    ReservedKeyword {
+        /// The reserved keyword
+        keyword: &'static str,
+        /// The offset of the location where the error occurred
+        position: usize,
+    },
     /// A custom error message
     Custom(CustomError<'static>),
 }
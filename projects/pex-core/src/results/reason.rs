@@ -26,6 +26,11 @@ impl Display for StopBecause {
             StopBecause::MissingString { message, .. } => f.write_fmt(format_args!("Missing string '{}'", message)),
             StopBecause::MustBe { message, .. } => f.write_fmt(format_args!("Must be `{}`", message)),
             StopBecause::ShouldNotBe { message, .. } => f.write_fmt(format_args!("Should not be `{}`", message)),
+            StopBecause::Incomplete { .. } => f.write_str("Incomplete input"),
+            StopBecause::TooDeep { limit, .. } => f.write_fmt(format_args!("Nesting deeper than {}", limit)),
+            StopBecause::OutOfFuel { .. } => f.write_str("Out of fuel"),
+            StopBecause::ZeroProgress { .. } => f.write_str("Repeat without progress"),
+            StopBecause::ReservedKeyword { keyword, .. } => f.write_fmt(format_args!("Reserved keyword `{}`", keyword)),
             StopBecause::Custom(v) => f.write_fmt(format_args!("Custom error: {}", v)),
         }
     }
@@ -79,6 +84,22 @@ impl StopBecause {
     pub const fn missing_string<T>(message: &'static str, position: usize) -> Result<T, StopBecause> {
         Err(Self::MissingString { message, position })
     }
+    /// Create a new `StopBecause::Incomplete` error
+    pub const fn incomplete<T>(position: usize) -> Result<T, StopBecause> {
+        Err(Self::Incomplete { position })
+    }
+    /// Check if the error asks for more input
+    pub const fn is_incomplete(&self) -> bool {
+        matches!(self, Self::Incomplete { .. })
+    }
+    /// Create a new `StopBecause::ZeroProgress` error
+    pub const fn zero_progress<T>(position: usize) -> Result<T, StopBecause> {
+        Err(Self::ZeroProgress { position })
+    }
+    /// Check if the error must not be caught by backtracking, such as incomplete input and runaway guards
+    pub const fn is_fatal(&self) -> bool {
+        matches!(self, Self::Incomplete { .. } | Self::TooDeep { .. } | Self::OutOfFuel { .. } | Self::ZeroProgress { .. })
+    }
     /// Create a new [CustomError]
     pub const fn custom_error<T>(message: &'static str, start: usize, end: usize) -> Result<T, StopBecause> {
         Err(Self::Custom(CustomError { message, start, end }))
@@ -94,6 +115,11 @@ impl StopBecause {
             StopBecause::MissingString { message, position } => position..position + message.len(),
             StopBecause::MustBe { message: _, position } => position..position + 1,
             StopBecause::ShouldNotBe { message: _, position } => position..position + 1,
+            StopBecause::Incomplete { position } => position..position,
+            StopBecause::TooDeep { limit: _, position } => position..position,
+            StopBecause::OutOfFuel { position } => position..position,
+            StopBecause::ZeroProgress { position } => position..position,
+            StopBecause::ReservedKeyword { keyword, position } => position..position + keyword.len(),
             StopBecause::Custom(e) => e.range(),
         }
     }
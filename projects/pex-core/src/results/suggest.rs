@@ -0,0 +1,154 @@
+use super::*;
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// Suggest the similar literals for the misspelled word at a failure, by the Damerau-Levenshtein distance.
+///
+/// The candidates are the strings expected by the failure, and the names added by [Suggester::with_candidates].
+/// If a misspelled keyword is parsed as an identifier, the failure moves past the word and only the candidates apply.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{FurthestFailure, StopBecause, Suggester};
+/// let input = "fucntion main() {}";
+/// let mut failure = FurthestFailure::default();
+/// failure.merge(StopBecause::MissingString { message: "function", position: 0 });
+/// failure.merge(StopBecause::MissingString { message: "fn", position: 0 });
+/// let failure = failure.with_suggestions(input, &Suggester::default());
+/// assert_eq!(
+///     failure.to_string(),
+///     "expected one of `function`, `fn` at 0, did you mean `function`?"
+/// );
+/// assert_eq!(failure.suggestions[0].range, 0..8);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suggester {
+    candidates: Vec<String>,
+    limit: usize,
+    max_distance: Option<usize>,
+}
+
+/// A ranked suggestion for the word at a failure
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Suggestion {
+    /// The suggested text
+    pub text: String,
+    /// The edit distance from the misspelled word
+    pub distance: usize,
+    /// The byte range of the misspelled word
+    pub range: Range<usize>,
+}
+
+impl Default for Suggester {
+    fn default() -> Self {
+        Self { candidates: vec![], limit: 3, max_distance: None }
+    }
+}
+
+impl Suggester {
+    /// Also compare the words with the candidates, such as the known identifiers
+    pub fn with_candidates<I, S>(mut self, candidates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.candidates.extend(candidates.into_iter().map(Into::into));
+        self
+    }
+    /// Keep at most `limit` suggestions, 3 by default
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    /// Ignore the candidates further than the distance, a third of the word length by default
+    pub fn with_max_distance(mut self, distance: usize) -> Self {
+        self.max_distance = Some(distance);
+        self
+    }
+    /// Rank the expected strings of the failure and the candidates against the input at the failure position
+    ///
+    /// The compared word is the identifier at the position, there is no suggestion if the input is a symbol.
+    pub fn suggest(&self, input: &str, failure: &FurthestFailure) -> Vec<Suggestion> {
+        let rest = input.get(failure.position..).unwrap_or_default();
+        let length: usize = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').map(char::len_utf8).sum();
+        if length == 0 {
+            return vec![];
+        }
+        let word = &rest[..length];
+        let expected = failure.expected.iter().filter_map(|error| match error {
+            StopBecause::MissingString { message, .. } => Some(*message),
+            _ => None,
+        });
+        let mut suggestions: Vec<Suggestion> = vec![];
+        for candidate in expected.chain(self.candidates.iter().map(String::as_str)) {
+            let distance = Self::distance(word, candidate);
+            if distance == 0 || distance > self.get_max_distance(word) || suggestions.iter().any(|s| s.text == candidate) {
+                continue;
+            }
+            let range = failure.position..failure.position + length;
+            suggestions.push(Suggestion { text: candidate.to_string(), distance, range });
+        }
+        suggestions.sort_by_key(|suggestion| suggestion.distance);
+        suggestions.truncate(self.limit);
+        suggestions
+    }
+    /// Rank the candidates against the word, the closest first
+    pub fn suggest_word(&self, word: &str) -> Vec<String> {
+        let mut ranked: Vec<(usize, &String)> = self
+            .candidates
+            .iter()
+            .map(|candidate| (Self::distance(word, candidate), candidate))
+            .filter(|(distance, _)| *distance != 0 && *distance <= self.get_max_distance(word))
+            .collect();
+        ranked.sort_by_key(|(distance, _)| *distance);
+        ranked.dedup_by(|a, b| a.1 == b.1);
+        ranked.into_iter().take(self.limit).map(|(_, candidate)| candidate.clone()).collect()
+    }
+    /// The max distance for the word
+    fn get_max_distance(&self, word: &str) -> usize {
+        self.max_distance.unwrap_or_else(|| word.chars().count().max(3) / 3)
+    }
+    /// The Damerau-Levenshtein distance of the optimal string alignment, counting insertions, deletions,
+    /// substitutions and transpositions of adjacent characters.
+    ///
+    /// ```
+    /// # use pex::Suggester;
+    /// assert_eq!(Suggester::distance("fucntion", "function"), 1);
+    /// assert_eq!(Suggester::distance("retrun", "return"), 1);
+    /// assert_eq!(Suggester::distance("whle", "while"), 1);
+    /// assert_eq!(Suggester::distance("let", "const"), 4);
+    /// ```
+    pub fn distance(a: &str, b: &str) -> usize {
+        let a: Vec<char> = a.chars().collect();
+        let b: Vec<char> = b.chars().collect();
+        // the rows of `i - 2`, `i - 1` and `i`
+        let mut before: Vec<usize> = vec![0; b.len() + 1];
+        let mut last: Vec<usize> = (0..=b.len()).collect();
+        let mut current: Vec<usize> = vec![0; b.len() + 1];
+        for i in 1..=a.len() {
+            current[0] = i;
+            for j in 1..=b.len() {
+                let cost = usize::from(a[i - 1] != b[j - 1]);
+                current[j] = (last[j] + 1).min(current[j - 1] + 1).min(last[j - 1] + cost);
+                if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                    current[j] = current[j].min(before[j - 2] + 1);
+                }
+            }
+            core::mem::swap(&mut before, &mut last);
+            core::mem::swap(&mut last, &mut current);
+        }
+        last[b.len()]
+    }
+}
+
+impl FurthestFailure {
+    /// Attach the suggestions for the word at the failure position, see [Suggester]
+    pub fn with_suggestions(mut self, input: &str, suggester: &Suggester) -> Self {
+        self.suggestions = suggester.suggest(input, &self);
+        self
+    }
+}
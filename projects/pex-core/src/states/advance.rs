@@ -39,7 +39,13 @@ impl<'i> ParseState<'i> {
         let offset = term.into().as_offset();
         debug_assert!(offset <= self.residual.len(), "offset is out of range");
         let residual = unsafe { self.residual.get_unchecked(offset..) };
-        ParseState { residual, start_offset: self.start_offset + offset, stop_reason: self.stop_reason }
+        ParseState {
+            residual,
+            start_offset: self.start_offset + offset,
+            stop_reason: self.stop_reason,
+            context: self.context,
+            partial: self.partial,
+        }
     }
     /// Advance the parser state and return the view of these string.
     #[inline]
@@ -47,7 +53,14 @@ impl<'i> ParseState<'i> {
         debug_assert!(offset <= self.residual.len(), "offset is out of range");
         let view = unsafe { self.residual.get_unchecked(..offset) };
         let residual = unsafe { self.residual.get_unchecked(offset..) };
-        ParseState { residual, start_offset: self.start_offset + offset, stop_reason: self.stop_reason }.finish(view)
+        ParseState {
+            residual,
+            start_offset: self.start_offset + offset,
+            stop_reason: self.stop_reason,
+            context: self.context,
+            partial: self.partial,
+        }
+        .finish(view)
     }
 }
 
@@ -11,6 +11,7 @@ impl<'i> ParseState<'i> {
     pub fn match_char(self, target: char) -> ParseResult<'i, char> {
         match self.get_character(0) {
             Some(c) if c.eq(&target) => self.advance(target).finish(target),
+            None if self.partial => StopBecause::incomplete(self.start_offset)?,
             _ => StopBecause::missing_character(target, self.start_offset)?,
         }
     }
@@ -23,6 +24,7 @@ impl<'i> ParseState<'i> {
     pub fn match_char_range(self, start: char, end: char) -> ParseResult<'i, char> {
         match self.get_character(0) {
             Some(c) if c <= end && c >= start => self.advance(c).finish(c),
+            None if self.partial => StopBecause::incomplete(self.start_offset)?,
             _ => StopBecause::missing_character_range(start, end, self.start_offset)?,
         }
     }
@@ -34,6 +36,7 @@ impl<'i> ParseState<'i> {
     pub fn match_eof(self) -> ParseResult<'i, ()> {
         match self.get_character(0) {
             Some(_) => StopBecause::expect_eof(self.start_offset)?,
+            None if self.partial => StopBecause::incomplete(self.start_offset)?,
             None => self.finish(()),
         }
     }
@@ -50,6 +53,7 @@ impl<'i> ParseState<'i> {
     {
         match self.get_character(0) {
             Some(c) if predicate(c) => self.advance(c).finish(c),
+            None if self.partial => StopBecause::incomplete(self.start_offset)?,
             _ => StopBecause::must_be(message, self.start_offset)?,
         }
     }
@@ -74,6 +78,7 @@ impl<'i> ParseState<'i> {
     pub fn match_str<'a>(self, target: &'static str) -> ParseResult<'i, &'i str> {
         let s = match self.get_string(0..target.len()) {
             Some(s) if s.eq(target) => s.len(),
+            None if self.partial && target.starts_with(self.residual) => StopBecause::incomplete(self.start_offset)?,
             _ => StopBecause::missing_string(target, self.start_offset)?,
         };
         self.advance_view(s)
@@ -84,10 +89,87 @@ impl<'i> ParseState<'i> {
     pub fn match_str_insensitive(self, target: &'static str) -> ParseResult<'i, &'i str> {
         let s = match self.get_string(0..target.len()) {
             Some(s) if s.eq_ignore_ascii_case(target) => s.len(),
+            None if self.partial
+                && target.get(..self.residual.len()).is_some_and(|s| s.eq_ignore_ascii_case(self.residual)) =>
+            {
+                StopBecause::incomplete(self.start_offset)?
+            }
             _ => StopBecause::missing_string(target, self.start_offset)?,
         };
         self.advance_view(s)
     }
+    /// Match a static keyword, fail if it is the prefix of a longer identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::ParseState;
+    /// assert!(ParseState::new("if (").match_keyword("if").is_success());
+    /// assert!(ParseState::new("iffy").match_keyword("if").is_failure());
+    /// assert!(ParseState::new("if").with_partial(true).match_keyword("if").is_incomplete());
+    /// ```
+    #[inline]
+    pub fn match_keyword(self, target: &'static str) -> ParseResult<'i, &'i str> {
+        let (state, text) = self.match_str(target)?;
+        match state.get_character(0) {
+            Some(c) if XID_CONTINUE.contains(c) => StopBecause::missing_string(target, self.start_offset)?,
+            None if state.partial => StopBecause::incomplete(self.start_offset)?,
+            _ => state.finish(text),
+        }
+    }
+    /// Match a static string after unicode case folding, the matched text may be longer or shorter than the target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::{helpers::CaseFolding, ParseState};
+    /// let state = ParseState::new("straße!");
+    /// assert_eq!(state.match_str_casefold("STRASSE", CaseFolding::Full).unwrap(), "straße");
+    /// assert!(state.match_str_casefold("STRASSE", CaseFolding::Simple).is_failure());
+    /// // a folded character can not be split
+    /// assert!(state.match_str_casefold("STRAS", CaseFolding::Full).is_failure());
+    /// ```
+    #[inline]
+    pub fn match_str_casefold(self, target: &'static str, mode: CaseFolding) -> ParseResult<'i, &'i str> {
+        let mut expected = mode.fold_str(target).peekable();
+        let mut length = 0;
+        for c in self.residual.chars() {
+            if expected.peek().is_none() {
+                break;
+            }
+            for folded in mode.fold(c) {
+                if expected.next() != Some(folded) {
+                    StopBecause::missing_string(target, self.start_offset)?
+                }
+            }
+            length += c.len_utf8();
+        }
+        if expected.peek().is_some() {
+            match self.partial {
+                true => StopBecause::incomplete(self.start_offset)?,
+                false => StopBecause::missing_string(target, self.start_offset)?,
+            }
+        }
+        self.advance_view(length)
+    }
+    /// Match a character after unicode case folding, the target is compared in its folded form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::{helpers::CaseFolding, ParseState};
+    /// let state = ParseState::new("ΣΑ");
+    /// assert_eq!(state.match_char_casefold('ς', CaseFolding::Simple).unwrap(), 'Σ');
+    /// assert!(ParseState::new("I").match_char_casefold('i', CaseFolding::Turkic).is_failure());
+    /// ```
+    #[inline]
+    pub fn match_char_casefold(self, target: char, mode: CaseFolding) -> ParseResult<'i, char> {
+        match self.get_character(0) {
+            Some(c) if mode.fold(c).eq(mode.fold(target)) => self.advance(c).finish(c),
+            None if self.partial => StopBecause::incomplete(self.start_offset)?,
+            _ => StopBecause::missing_character(target, self.start_offset)?,
+        }
+    }
     /// Match a string with given regex.
     #[cfg(feature = "regex")]
     pub fn match_regex(&self, re: &regex::Regex, message: &'static str) -> ParseResult<'i, Match> {
@@ -135,6 +217,10 @@ impl<'i> ParseState<'i> {
                 false => break,
             }
         }
+        // the run may continue in the next chunk
+        if self.partial && offset == self.residual.len() {
+            StopBecause::incomplete(self.start_offset)?;
+        }
         if offset == 0 {
             StopBecause::missing_string(message, self.start_offset)?;
         }
@@ -153,13 +239,15 @@ impl<'i> ParseState<'i> {
 impl<'i> ParseState<'i> {
     /// Simple suffix call form
     #[inline]
-    pub fn match_fn<T, F>(self, mut parse: F) -> ParseResult<'i, T>
+    pub fn match_fn<T, F>(self, parse: F) -> ParseResult<'i, T>
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        parse(self)
+        self.traced("match_fn", parse)
     }
     /// Parses a sequence of 0 or more repetitions of the given parser.
+    ///
+    /// Stop with [StopBecause::ZeroProgress] if an iteration consumes nothing.
     /// ```regex
     /// p*
     /// p+ <=> p p*
@@ -169,21 +257,31 @@ impl<'i> ParseState<'i> {
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        let mut result = Vec::new();
-        let mut state = self;
-        loop {
-            match parse(state) {
-                Pending(new, value) => {
-                    state = new;
-                    result.push(value);
+        self.traced("match_repeats", |mut state| {
+            let mut result = Vec::new();
+            loop {
+                let mark = state.error_mark();
+                match parse(state) {
+                    Pending(new, _) if new.start_offset == state.start_offset => StopBecause::zero_progress(new.start_offset)?,
+                    Pending(new, value) => {
+                        state = new;
+                        result.push(value);
+                    }
+                    Stop(e) if e.is_fatal() => return Stop(e),
+                    Stop(e) => {
+                        state.backtrack(mark, e);
+                        break;
+                    }
                 }
-                Stop(_) => break,
             }
-        }
-        state.finish(result)
+            state.finish(result)
+        })
     }
 
     /// Parses a sequence of 0 or more repetitions of the given parser.
+    ///
+    /// If an iteration consumes nothing, stop with [StopBecause::ZeroProgress] when `max` is `usize::MAX`,
+    /// otherwise the same empty match is repeated up to `min` times and the loop stops.
     /// ```regex
     /// p* <=> p{0, \inf}
     /// p+ <=> p{1, \inf}
@@ -194,27 +292,37 @@ impl<'i> ParseState<'i> {
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        let mut result = Vec::new();
-        let mut count = 0;
-        let position = self.start_offset;
-        let mut state = self;
-        loop {
-            match parse(state.clone()) {
-                Pending(new, value) => {
-                    state = new;
-                    result.push(value);
-                    count += 1;
-                    if count >= max {
+        self.traced("match_repeat_m_n", |mut state| {
+            let mut result = Vec::new();
+            let mut count = 0;
+            let position = self.start_offset;
+            loop {
+                let mark = state.error_mark();
+                match parse(state.clone()) {
+                    Pending(new, _) if new.start_offset == state.start_offset && max == usize::MAX => {
+                        StopBecause::zero_progress(new.start_offset)?
+                    }
+                    Pending(new, value) => {
+                        let empty = new.start_offset == state.start_offset;
+                        state = new;
+                        result.push(value);
+                        count += 1;
+                        if count >= max || empty && count >= min {
+                            break;
+                        }
+                    }
+                    Stop(e) if e.is_fatal() => return Stop(e),
+                    Stop(e) => {
+                        state.backtrack(mark, e);
                         break;
                     }
-                }
-                Stop(_) => break,
-            };
-        }
-        if count < min {
-            Err(StopBecause::ExpectRepeats { min, current: count, position })?
-        }
-        state.finish(result)
+                };
+            }
+            if count < min {
+                Err(StopBecause::ExpectRepeats { min, current: count, position })?
+            }
+            state.finish(result)
+        })
     }
     /// Parse an optional element
     /// ```regex
@@ -225,20 +333,62 @@ impl<'i> ParseState<'i> {
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        match parse(self.clone()) {
-            Pending(state, value) => state.finish(Some(value)),
-            Stop(_) => self.finish(None),
-        }
+        self.traced("match_optional", |state| {
+            let mark = state.error_mark();
+            match parse(state) {
+                Pending(state, value) => state.finish(Some(value)),
+                Stop(e) if e.is_fatal() => Stop(e),
+                Stop(e) => {
+                    state.backtrack(mark, e);
+                    state.finish(None)
+                }
+            }
+        })
     }
     /// Match but does not return the result
+    ///
+    /// Every stop is backtracked, including the fatal ones such as [StopBecause::Incomplete],
+    /// use [ParseState::try_skip] to pass them through.
     #[inline]
     pub fn skip<F, T>(self, mut parse: F) -> ParseState<'i>
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
+        let mark = self.error_mark();
         match parse(self.clone()) {
             Pending(new, _) => new,
-            Stop(_) => self,
+            Stop(e) => {
+                self.backtrack(mark, e);
+                self
+            }
+        }
+    }
+    /// Match but does not return the result, the fatal stops are returned instead of backtracked
+    ///
+    /// ```
+    /// # use pex::{helpers::whitespace, ParseResult, ParseState, StopBecause};
+    /// let state = ParseState::new("  a");
+    /// assert_eq!(state.try_skip(whitespace).as_result().unwrap().0.start_offset, 2);
+    /// assert!(state.try_skip(|s| s.match_char('b')).is_success());
+    /// // a comment cut off at the end of a chunk
+    /// let result = state.try_skip(|s| {
+    ///     ParseResult::<()>::Stop(StopBecause::Incomplete { position: s.start_offset })
+    /// });
+    /// assert!(matches!(result, ParseResult::Stop(StopBecause::Incomplete { position: 0 })));
+    /// ```
+    #[inline]
+    pub fn try_skip<F, T>(self, mut parse: F) -> ParseResult<'i, ()>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<T>,
+    {
+        let mark = self.error_mark();
+        match parse(self.clone()) {
+            Pending(new, _) => new.finish(()),
+            Stop(e) if e.is_fatal() => Stop(e),
+            Stop(e) => {
+                self.backtrack(mark, e);
+                self.finish(())
+            }
         }
     }
     /// Zero-width positive match, does not consume input
@@ -254,8 +404,13 @@ impl<'i> ParseState<'i> {
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        match parse(self.clone()) {
+        let mark = self.error_mark();
+        let result = parse(self.clone());
+        self.rollback_errors(mark);
+        self.touch_result(&result);
+        match result {
             Pending(..) => self.finish(()),
+            Stop(e) if e.is_fatal() => Stop(e),
             Stop(_) => Stop(StopBecause::MustBe { message, position: self.start_offset }),
         }
     }
@@ -269,8 +424,13 @@ impl<'i> ParseState<'i> {
     where
         F: FnMut(ParseState<'i>) -> ParseResult<T>,
     {
-        match parse(self.clone()) {
+        let mark = self.error_mark();
+        let result = parse(self.clone());
+        self.rollback_errors(mark);
+        self.touch_result(&result);
+        match result {
             Pending(..) => Stop(StopBecause::ShouldNotBe { message, position: self.start_offset }),
+            Stop(e) if e.is_fatal() => Stop(e),
             Stop(_) => self.finish(()),
         }
     }
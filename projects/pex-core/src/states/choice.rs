@@ -7,32 +7,51 @@ use super::*;
 pub struct ChoiceHelper<'a, T> {
     state: ParseState<'a>,
     result: Option<Parsed<'a, T>>,
+    /// The traced calls before the choice, the branches are grouped by [ChoiceHelper::end_choice]
+    #[cfg(feature = "trace")]
+    trace_mark: Option<usize>,
 }
 
 impl<'i> ParseState<'i> {
     /// Begin a choice progress
     #[inline]
     pub fn begin_choice<T>(self) -> ChoiceHelper<'i, T> {
-        ChoiceHelper { state: self, result: None }
+        ChoiceHelper::new(self)
     }
 }
 
 impl<'a, T> ChoiceHelper<'a, T> {
     /// Create a new choice helper
     #[inline]
-    pub fn new(state: ParseState<'a>) -> Self {
-        Self { state, result: None }
+    pub fn new(mut state: ParseState<'a>) -> Self {
+        state.stop_reason = None;
+        Self {
+            state,
+            result: None,
+            #[cfg(feature = "trace")]
+            trace_mark: state.context.and_then(|context| context.trace_mark()),
+        }
     }
-    /// Try to parse a value
+    /// Try to parse a value, keep the furthest error if all branches failed
+    ///
+    /// Stop trying if a branch needs more input or hits a runaway guard, see [StopBecause::is_fatal].
     #[inline]
     pub fn or_else<F>(mut self, mut parse: F) -> Self
     where
         F: FnMut(ParseState<'a>) -> ParseResult<'a, T>,
     {
-        if self.result.is_none() {
-            match parse(self.state.clone()) {
+        if self.result.is_none() && !self.state.get_error().is_fatal() {
+            let mark = self.state.error_mark();
+            match self.state.traced("or_else", &mut parse) {
                 Pending(s, v) => self.result = Some((s, v)),
-                Stop(err) => self.state.set_error(err),
+                Stop(err) if err.is_fatal() => self.state.set_error(err),
+                Stop(err) => {
+                    self.state.backtrack(mark, err);
+                    match self.state.stop_reason {
+                        Some(old) if old.range().start > err.range().start => {}
+                        _ => self.state.set_error(err),
+                    }
+                }
             }
         }
         self
@@ -40,6 +59,14 @@ impl<'a, T> ChoiceHelper<'a, T> {
     /// End choice
     #[inline]
     pub fn end_choice(self) -> ParseResult<'a, T> {
+        #[cfg(feature = "trace")]
+        if let (Some(context), Some(mark)) = (self.state.context, self.trace_mark) {
+            let outcome = match &self.result {
+                Some((state, _)) => Ok(state.start_offset),
+                None => Err(self.state.get_error()),
+            };
+            context.trace_group("begin_choice", self.state.start_offset, mark, outcome);
+        }
         match self.result {
             Some(ok) => Pending(ok.0, ok.1),
             None => Stop(self.state.get_error()),
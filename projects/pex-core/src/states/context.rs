@@ -0,0 +1,380 @@
+use super::*;
+#[cfg(feature = "trace")]
+use crate::ParseTrace;
+use crate::{
+    states::{
+        incremental::{ReuseStats, ReuseTable, TextEdit},
+        left_recursive::{SeedHit, SeedTable},
+        memo::{MemoHit, MemoStats, MemoTable},
+    },
+    FurthestFailure, PexIncremental,
+};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt::{Debug, Formatter},
+};
+
+/// Shared context of a whole parse, attached to the [ParseState] by [ParseState::with_context].
+///
+/// The state is copied everywhere, so everything need to survive backtracking is stored here.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{ParseContext, ParseResult, ParseState};
+/// fn tuple(state: ParseState) -> ParseResult<char> {
+///     let (state, _) = state.match_char('(')?;
+///     state
+///         .begin_choice()
+///         .or_else(|s| s.match_char(')'))
+///         .or_else(|s| s.match_char_if(char::is_numeric, "DIGIT"))
+///         .end_choice()
+/// }
+/// let context = ParseContext::default();
+/// let state = ParseState::new("(a; b)").with_context(&context);
+/// assert!(tuple(state).is_failure());
+/// let failure = context.get_furthest_failure().unwrap();
+/// assert_eq!(failure.to_string(), "expected one of `)`, DIGIT at 1");
+/// ```
+#[derive(Default)]
+pub struct ParseContext {
+    furthest: RefCell<FurthestFailure>,
+    errors: RefCell<Vec<StopBecause>>,
+    max_errors: usize,
+    memo: Option<RefCell<MemoTable>>,
+    seeds: RefCell<SeedTable>,
+    reuse: Option<RefCell<ReuseTable>>,
+    /// The furthest offset examined by the current rule
+    examined: Cell<usize>,
+    max_depth: Option<usize>,
+    depth: Cell<usize>,
+    fuel: Cell<Option<usize>>,
+    #[cfg(feature = "trace")]
+    trace: Option<RefCell<ParseTrace>>,
+}
+
+impl Debug for ParseContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ParseContext")
+            .field("furthest", &self.furthest.borrow())
+            .field("errors", &self.errors.borrow())
+            .field("max_errors", &self.max_errors)
+            .field("memo", &self.get_memo_stats())
+            .field("seeds", &self.seeds.borrow())
+            .field("reuse", &self.get_reuse_stats())
+            .field("max_depth", &self.max_depth)
+            .field("fuel", &self.fuel.get())
+            .finish()
+    }
+}
+
+/// Contexts are compared by identity
+impl PartialEq for ParseContext {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self, other)
+    }
+}
+
+impl Eq for ParseContext {}
+
+impl ParseContext {
+    /// Turn on the recovery mode, stop recovering after `max_errors` errors were recovered.
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+    /// Check if the recovery mode is on and the error cap is not reached
+    pub fn can_recover(&self) -> bool {
+        self.errors.borrow().len() < self.max_errors
+    }
+    /// Push a recovered error to the error sink
+    pub fn push_error(&self, error: StopBecause) {
+        self.touch(error.range().end);
+        self.errors.borrow_mut().push(error)
+    }
+    /// Get all recovered errors
+    pub fn get_errors(&self) -> Vec<StopBecause> {
+        self.errors.borrow().clone()
+    }
+    /// Take all recovered errors, and clear the error sink
+    pub fn take_errors(&self) -> Vec<StopBecause> {
+        core::mem::take(&mut *self.errors.borrow_mut())
+    }
+    /// Get the count of recovered errors, used to rollback errors of a failed branch
+    pub fn error_mark(&self) -> usize {
+        self.errors.borrow().len()
+    }
+    /// Drop the errors recovered after the mark
+    pub fn rollback_errors(&self, mark: usize) {
+        self.errors.borrow_mut().truncate(mark)
+    }
+    /// Attach a packrat memo table that holds at most `capacity` entries, see [ParseState::memoize].
+    pub fn with_memo(mut self, capacity: usize) -> Self {
+        self.memo = Some(RefCell::new(MemoTable::new(capacity)));
+        self
+    }
+    /// Check if a memo table is attached
+    pub fn has_memo(&self) -> bool {
+        self.memo.is_some()
+    }
+    /// Get the statistics of the memo table
+    pub fn get_memo_stats(&self) -> Option<MemoStats> {
+        self.memo.as_ref().map(|memo| memo.borrow().get_stats())
+    }
+    /// Drop all entries of the memo table
+    pub fn clear_memo(&self) {
+        if let Some(memo) = &self.memo {
+            memo.borrow_mut().clear()
+        }
+    }
+    pub(crate) fn memo_lookup<T>(&self, rule: &'static str, offset: usize) -> Option<MemoHit<T>>
+    where
+        T: Clone + 'static,
+    {
+        self.memo.as_ref()?.borrow_mut().lookup(rule, offset)
+    }
+    pub(crate) fn memo_insert<T>(
+        &self,
+        rule: &'static str,
+        offset: usize,
+        result: &ParseResult<T>,
+        mark: usize,
+        examined: usize,
+    ) where
+        T: Clone + 'static,
+    {
+        // results depending on a growing seed, partial input or the guards are not final
+        if self.seeds.borrow().is_growing_at(offset) || matches!(result, Stop(e) if e.is_fatal()) {
+            return;
+        }
+        if let Some(memo) = &self.memo {
+            memo.borrow_mut().insert(rule, offset, result, self.get_errors_since(mark), examined)
+        }
+    }
+    pub(crate) fn get_errors_since(&self, mark: usize) -> Vec<StopBecause> {
+        self.errors.borrow().get(mark..).map(|e| e.to_vec()).unwrap_or_default()
+    }
+    pub(crate) fn seed_lookup<T>(&self, rule: &'static str, offset: usize) -> Option<SeedHit<T>>
+    where
+        T: Clone + 'static,
+    {
+        self.seeds.borrow_mut().lookup(rule, offset)
+    }
+    pub(crate) fn seed_plant(&self, rule: &'static str, offset: usize) {
+        self.seeds.borrow_mut().plant(rule, offset)
+    }
+    pub(crate) fn seed_is_detected(&self, rule: &'static str, offset: usize) -> bool {
+        self.seeds.borrow().is_detected(rule, offset)
+    }
+    pub(crate) fn seed_update(&self, rule: &'static str, offset: usize, end_offset: usize, value: Box<dyn Any>) {
+        self.seeds.borrow_mut().update(rule, offset, end_offset, value)
+    }
+    pub(crate) fn seed_remove(&self, rule: &'static str, offset: usize) {
+        self.seeds.borrow_mut().remove(rule, offset)
+    }
+    pub(crate) fn with_reuse(mut self, lookahead: usize) -> Self {
+        self.reuse = Some(RefCell::new(ReuseTable::new(lookahead)));
+        self
+    }
+    /// Get the statistics of incremental reparsing, see [IncrementalParser](crate::IncrementalParser)
+    pub fn get_reuse_stats(&self) -> Option<ReuseStats> {
+        self.reuse.as_ref().map(|reuse| reuse.borrow().get_stats())
+    }
+    pub(crate) fn reuse_lookup<T>(&self, rule: &'static str, offset: usize) -> Option<(usize, usize, T)>
+    where
+        T: PexIncremental,
+    {
+        self.reuse.as_ref()?.borrow_mut().lookup(rule, offset)
+    }
+    pub(crate) fn reuse_insert<T>(&self, rule: &'static str, offset: usize, result: &ParseResult<T>, examined: usize)
+    where
+        T: PexIncremental,
+    {
+        if let Some(reuse) = &self.reuse {
+            reuse.borrow_mut().insert(rule, offset, result, examined)
+        }
+    }
+    /// Forget everything of the last parse except the reusable nodes, and invalidate the nodes touched by the edit
+    pub(crate) fn reuse_edit(&self, edit: &TextEdit) {
+        if let Some(reuse) = &self.reuse {
+            reuse.borrow_mut().apply_edit(edit)
+        }
+        self.clear_memo();
+        self.clear_furthest_failure();
+        self.take_errors();
+    }
+    /// Mark the text before the offset as examined by the current rule
+    pub(crate) fn touch(&self, offset: usize) {
+        self.examined.set(self.examined.get().max(offset))
+    }
+    /// Parse a rule, and get the furthest offset it examined, the outer rule examined it too
+    pub(crate) fn track_examined<'i, T, F>(&self, start: usize, parse: F) -> (ParseResult<'i, T>, usize)
+    where
+        F: FnOnce() -> ParseResult<'i, T>,
+    {
+        let outer = self.examined.replace(start);
+        let result = parse();
+        let end = match &result {
+            Pending(state, _) => state.start_offset,
+            Stop(error) => error.range().end,
+        };
+        let examined = self.examined.get().max(end);
+        self.examined.set(outer.max(examined));
+        (result, examined)
+    }
+    /// Stop with [StopBecause::TooDeep] if the calls nest deeper than `max_depth`, see [ParseState::match_rule].
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+    /// Stop with [StopBecause::OutOfFuel] after `fuel` calls, each call of combinators and named rules burns one.
+    pub fn with_fuel(self, fuel: usize) -> Self {
+        self.fuel.set(Some(fuel));
+        self
+    }
+    /// Get the remaining fuel, `None` if the budget is unlimited
+    pub fn get_fuel(&self) -> Option<usize> {
+        self.fuel.get()
+    }
+    /// Get the nesting depth of the current call
+    pub fn get_depth(&self) -> usize {
+        self.depth.get()
+    }
+    /// Cancel the parse, the next call stops with [StopBecause::OutOfFuel]
+    pub fn cancel(&self) {
+        self.fuel.set(Some(0))
+    }
+    /// Burn a unit of fuel and go one level deeper, or the reason why the guards stop the call
+    pub(crate) fn guard_enter(&self, position: usize) -> Result<(), StopBecause> {
+        if let Some(fuel) = self.fuel.get() {
+            match fuel.checked_sub(1) {
+                Some(rest) => self.fuel.set(Some(rest)),
+                None => Err(StopBecause::OutOfFuel { position })?,
+            }
+        }
+        let depth = self.depth.get() + 1;
+        match self.max_depth {
+            Some(limit) if depth > limit => Err(StopBecause::TooDeep { limit, position }),
+            _ => {
+                self.depth.set(depth);
+                Ok(())
+            }
+        }
+    }
+    pub(crate) fn guard_exit(&self) {
+        self.depth.set(self.depth.get() - 1)
+    }
+    /// Record every call of combinators and named rules, see [ParseState::match_rule]
+    #[cfg(feature = "trace")]
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(RefCell::new(ParseTrace::default()));
+        self
+    }
+    /// Take the recorded trace, and start a new one
+    #[cfg(feature = "trace")]
+    pub fn take_trace(&self) -> Option<ParseTrace> {
+        self.trace.as_ref().map(|trace| core::mem::take(&mut *trace.borrow_mut()))
+    }
+    /// Enter a call, return false if not tracing
+    #[cfg(feature = "trace")]
+    pub(crate) fn trace_enter(&self, name: &'static str, start: usize) -> bool {
+        match &self.trace {
+            Some(trace) => {
+                trace.borrow_mut().enter(name, start);
+                true
+            }
+            None => false,
+        }
+    }
+    #[cfg(feature = "trace")]
+    pub(crate) fn trace_exit(&self, outcome: Result<usize, StopBecause>) {
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().exit(outcome)
+        }
+    }
+    /// Mark the calls recorded so far, return none if not tracing
+    #[cfg(feature = "trace")]
+    pub(crate) fn trace_mark(&self) -> Option<usize> {
+        self.trace.as_ref().map(|trace| trace.borrow_mut().mark())
+    }
+    /// Record a call around the calls made after the mark
+    #[cfg(feature = "trace")]
+    pub(crate) fn trace_group(&self, name: &'static str, start: usize, mark: usize, outcome: Result<usize, StopBecause>) {
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().group(name, start, mark, outcome)
+        }
+    }
+    /// Record an error that will be dropped by backtracking
+    pub fn record_error(&self, error: StopBecause) {
+        self.touch(error.range().end);
+        self.furthest.borrow_mut().merge(error)
+    }
+    /// Get the furthest failure and every expectation seen there
+    pub fn get_furthest_failure(&self) -> Option<FurthestFailure> {
+        let furthest = self.furthest.borrow();
+        match furthest.is_empty() {
+            true => None,
+            false => Some(furthest.clone()),
+        }
+    }
+    /// Record the final error of the parse, and get the furthest failure
+    pub fn merge_error(&self, error: StopBecause) -> FurthestFailure {
+        self.record_error(error);
+        self.furthest.borrow().clone()
+    }
+    /// Forget the furthest failure
+    pub fn clear_furthest_failure(&self) {
+        *self.furthest.borrow_mut() = FurthestFailure::default();
+    }
+}
+
+impl<'i> ParseState<'i> {
+    /// Attach a shared context to the state
+    #[inline(always)]
+    pub const fn with_context(mut self, context: &'i ParseContext) -> Self {
+        self.context = Some(context);
+        self
+    }
+    /// Record an error that will be dropped by backtracking, do nothing if there is no context.
+    #[inline]
+    pub fn record_error(&self, error: StopBecause) {
+        if let Some(context) = self.context {
+            context.record_error(error)
+        }
+    }
+    /// Mark the text examined by a lookahead, which leaves no error.
+    #[inline]
+    pub(crate) fn touch_result<T>(&self, result: &ParseResult<T>) {
+        if let Some(context) = self.context {
+            match result {
+                Pending(state, _) => context.touch(state.start_offset),
+                Stop(error) => context.touch(error.range().end),
+            }
+        }
+    }
+    /// Get the count of recovered errors before trying a branch, always 0 if there is no context.
+    #[inline]
+    pub fn error_mark(&self) -> usize {
+        match self.context {
+            Some(context) => context.error_mark(),
+            None => 0,
+        }
+    }
+    /// Drop the errors recovered after the mark, do nothing if there is no context.
+    #[inline]
+    pub fn rollback_errors(&self, mark: usize) {
+        if let Some(context) = self.context {
+            context.rollback_errors(mark);
+        }
+    }
+    /// Backtrack from a failed branch, record its error and drop the errors it recovered.
+    #[inline]
+    pub fn backtrack(&self, mark: usize, error: StopBecause) {
+        if let Some(context) = self.context {
+            context.record_error(error);
+            context.rollback_errors(mark);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use super::*;
+
+impl<'i> ParseState<'i> {
+    /// Run a call under the depth limit and the fuel budget of the context.
+    #[inline(always)]
+    pub(crate) fn guarded<T, F>(self, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let context = match self.context {
+            Some(context) => context,
+            None => return parse(self),
+        };
+        context.guard_enter(self.start_offset)?;
+        let result = parse(self);
+        context.guard_exit();
+        result
+    }
+}
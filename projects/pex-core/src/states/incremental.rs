@@ -0,0 +1,256 @@
+use super::*;
+use crate::PexIncremental;
+use alloc::{boxed::Box, collections::BTreeMap, string::String};
+use core::any::Any;
+
+/// A text edit, replace the text in the range with the new text
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TextEdit {
+    /// The range of the old text to replace
+    pub range: Range<usize>,
+    /// The new text
+    pub text: String,
+}
+
+/// Statistics of incremental reparsing
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReuseStats {
+    /// How many nodes were reused from the last parse
+    pub reused: usize,
+    /// How many nodes were parsed again
+    pub parsed: usize,
+    /// How many nodes were dropped because the edit touched them
+    pub invalidated: usize,
+    /// How many nodes are kept for the next parse
+    pub entries: usize,
+}
+
+/// The nodes of the last parses that can be reused
+#[derive(Debug)]
+pub(crate) struct ReuseTable {
+    entries: BTreeMap<(usize, &'static str), ReuseEntry>,
+    lookahead: usize,
+    stats: ReuseStats,
+}
+
+#[derive(Debug)]
+struct ReuseEntry {
+    end_offset: usize,
+    /// The furthest offset examined while parsing the node, the node depends on the text before it
+    examined: usize,
+    /// The offset to shift the node when reused
+    delta: isize,
+    value: Box<dyn Any>,
+}
+
+impl TextEdit {
+    /// Create a new edit
+    pub fn new(range: Range<usize>, text: impl Into<String>) -> Self {
+        Self { range, text: text.into() }
+    }
+    /// Get how the edit changes the length of the text
+    pub fn get_delta(&self) -> isize {
+        self.text.len() as isize - self.range.len() as isize
+    }
+    /// Check if the text in `start..examined` is changed
+    pub fn is_touched(&self, start: usize, examined: usize) -> bool {
+        match self.range.is_empty() {
+            // text inserted at the edges does not change the text
+            true => start < self.range.start && self.range.start < examined,
+            false => self.range.start < examined && start < self.range.end,
+        }
+    }
+    /// Apply the edit to the text, panic if the range is out of bounds or not on char boundaries
+    pub fn apply(&self, text: &mut String) {
+        text.replace_range(self.range.clone(), &self.text)
+    }
+}
+
+impl ReuseTable {
+    pub fn new(lookahead: usize) -> Self {
+        Self { entries: BTreeMap::new(), lookahead: lookahead.max(1), stats: ReuseStats::default() }
+    }
+    pub fn get_stats(&self) -> ReuseStats {
+        ReuseStats { entries: self.entries.len(), ..self.stats }
+    }
+    pub fn lookup<T>(&mut self, rule: &'static str, offset: usize) -> Option<(usize, usize, T)>
+    where
+        T: PexIncremental,
+    {
+        let entry = self.entries.get_mut(&(offset, rule))?;
+        // same rule name with different types, treat as missing
+        let mut value = entry.value.downcast_ref::<T>()?.clone();
+        if entry.delta != 0 {
+            // shift once, and keep the shifted node
+            value.shift_range(entry.delta);
+            entry.value = Box::new(value.clone());
+            entry.delta = 0;
+        }
+        self.stats.reused += 1;
+        Some((entry.end_offset, entry.examined, value))
+    }
+    pub fn insert<T>(&mut self, rule: &'static str, offset: usize, result: &ParseResult<T>, examined: usize)
+    where
+        T: PexIncremental,
+    {
+        self.stats.parsed += 1;
+        if let Pending(state, value) = result {
+            let entry = ReuseEntry {
+                end_offset: state.start_offset,
+                examined: examined + self.lookahead,
+                delta: 0,
+                value: Box::new(value.clone()),
+            };
+            self.entries.insert((offset, rule), entry);
+        }
+    }
+    pub fn apply_edit(&mut self, edit: &TextEdit) {
+        let delta = edit.get_delta();
+        let entries = core::mem::take(&mut self.entries);
+        for ((start, rule), mut entry) in entries {
+            if edit.is_touched(start, entry.examined) {
+                self.stats.invalidated += 1;
+                continue;
+            }
+            if start < edit.range.start {
+                self.entries.insert((start, rule), entry);
+                continue;
+            }
+            entry.end_offset = entry.end_offset.wrapping_add_signed(delta);
+            entry.examined = entry.examined.wrapping_add_signed(delta);
+            entry.delta += delta;
+            self.entries.insert((start.wrapping_add_signed(delta), rule), entry);
+        }
+    }
+}
+
+impl<'i> ParseState<'i> {
+    /// Parse a node that can be reused by incremental reparsing, parse directly if the context is not incremental.
+    ///
+    /// The node is reused if the edit did not touch the text it examined, including its span and lookahead.
+    ///
+    /// See [IncrementalParser] for examples.
+    #[inline]
+    pub fn reuse<T, F>(self, rule: &'static str, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+        T: PexIncremental,
+    {
+        let context = match self.context {
+            Some(context) => context,
+            None => return self.traced(rule, parse),
+        };
+        match context.reuse_lookup::<T>(rule, self.start_offset) {
+            Some((end_offset, examined, value)) => {
+                context.touch(examined);
+                self.advance(end_offset - self.start_offset).finish(value)
+            }
+            None => {
+                let (result, examined) = context.track_examined(self.start_offset, || self.traced(rule, parse));
+                context.reuse_insert(rule, self.start_offset, &result, examined);
+                result
+            }
+        }
+    }
+}
+
+/// Reparse the text after edits, reuse every node marked by [ParseState::reuse] that the edits did not touch.
+///
+/// In verify mode, every parse is checked against a fresh parse, and panic if they differ.
+///
+/// # Examples
+///
+/// ```
+/// # use core::ops::Range;
+/// # use pex::{IncrementalParser, ParseResult, ParseState, PexIncremental, PexNode, TextEdit};
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Word {
+///     text: String,
+///     range: Range<usize>,
+/// }
+/// impl PexNode for Word {
+///     fn parse(input: ParseState) -> ParseResult<Self> {
+///         input.reuse("word", |s| {
+///             let (s, text) = s.match_str_if(|c| c.is_alphabetic(), "WORD")?;
+///             s.finish(Word { text: text.to_string(), range: input.start_offset..s.start_offset })
+///         })
+///     }
+///     fn get_range(&self) -> Range<usize> {
+///         self.range.clone()
+///     }
+/// }
+/// impl PexIncremental for Word {
+///     fn shift_range(&mut self, delta: isize) {
+///         self.range = self.range.start.wrapping_add_signed(delta)..self.range.end.wrapping_add_signed(delta);
+///     }
+/// }
+/// fn words(state: ParseState) -> ParseResult<Vec<Word>> {
+///     state.match_repeats(|s| s.skip(|s| s.match_char(' ')).match_fn(Word::parse))
+/// }
+/// let mut parser = IncrementalParser::new("alpha beta gamma").with_verify(true);
+/// assert_eq!(parser.parse(words).unwrap().len(), 3);
+/// parser.edit(TextEdit::new(6..10, "b"));
+/// let words = parser.parse(words).unwrap();
+/// assert_eq!(words[2], Word { text: "gamma".to_string(), range: 8..13 });
+/// // alpha and gamma are reused
+/// assert_eq!(parser.get_stats().reused, 2);
+/// ```
+#[derive(Debug)]
+pub struct IncrementalParser {
+    text: String,
+    context: ParseContext,
+    verify: bool,
+}
+
+impl IncrementalParser {
+    /// Create a new parser for the text
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), context: ParseContext::default().with_reuse(1), verify: false }
+    }
+    /// Set how many bytes after a node are treated as examined, for the lookahead that leaves no trace, at least 1.
+    pub fn with_lookahead(mut self, lookahead: usize) -> Self {
+        self.context = ParseContext::default().with_reuse(lookahead);
+        self
+    }
+    /// Check every parse against a fresh parse, used in tests
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+    /// Get the current text
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+    /// Get the statistics of node reuse
+    pub fn get_stats(&self) -> ReuseStats {
+        self.context.get_reuse_stats().unwrap_or_default()
+    }
+    /// Apply an edit to the text, the nodes touched by the edit are dropped
+    pub fn edit(&mut self, edit: TextEdit) {
+        edit.apply(&mut self.text);
+        self.context.reuse_edit(&edit);
+    }
+    /// Parse the current text, reuse the nodes of the last parse.
+    ///
+    /// Panic if the result differs from a fresh parse in verify mode.
+    pub fn parse<T, F>(&self, parse: F) -> ParseResult<'_, T>
+    where
+        F: for<'a> Fn(ParseState<'a>) -> ParseResult<'a, T>,
+        T: PartialEq + Debug,
+    {
+        let result = parse(ParseState::new(&self.text).with_context(&self.context));
+        if self.verify {
+            let context = ParseContext::default();
+            let fresh = parse(ParseState::new(&self.text).with_context(&context));
+            assert_eq!(outcome(&result), outcome(&fresh), "incremental parse differs from a fresh parse of {:?}", self.text);
+        }
+        result
+    }
+}
+
+fn outcome<'a, T>(result: &'a ParseResult<T>) -> Result<(usize, &'a T), StopBecause> {
+    match result {
+        Pending(state, value) => Ok((state.start_offset, value)),
+        Stop(error) => Err(*error),
+    }
+}
@@ -0,0 +1,157 @@
+use super::*;
+use alloc::{boxed::Box, collections::BTreeMap};
+use core::any::Any;
+
+/// The growing seeds of left recursive rules
+#[derive(Debug, Default)]
+pub(crate) struct SeedTable {
+    seeds: BTreeMap<(usize, &'static str), Seed>,
+}
+
+#[derive(Debug)]
+struct Seed {
+    /// The best result so far, `None` if the rule has not succeeded yet
+    value: Option<(usize, Box<dyn Any>)>,
+    /// Whether the rule called itself at the same offset
+    detected: bool,
+}
+
+pub(crate) enum SeedHit<T> {
+    Pending(usize, T),
+    Stop,
+}
+
+impl SeedTable {
+    pub fn is_growing_at(&self, offset: usize) -> bool {
+        self.seeds.keys().any(|(start, _)| *start == offset)
+    }
+    pub fn lookup<T>(&mut self, rule: &'static str, offset: usize) -> Option<SeedHit<T>>
+    where
+        T: Clone + 'static,
+    {
+        let seed = self.seeds.get_mut(&(offset, rule))?;
+        seed.detected = true;
+        match &seed.value {
+            Some((end_offset, value)) => match value.downcast_ref::<T>() {
+                Some(value) => Some(SeedHit::Pending(*end_offset, value.clone())),
+                None => Some(SeedHit::Stop),
+            },
+            None => Some(SeedHit::Stop),
+        }
+    }
+    pub fn plant(&mut self, rule: &'static str, offset: usize) {
+        self.seeds.insert((offset, rule), Seed { value: None, detected: false });
+    }
+    pub fn is_detected(&self, rule: &'static str, offset: usize) -> bool {
+        self.seeds.get(&(offset, rule)).map(|seed| seed.detected).unwrap_or(false)
+    }
+    pub fn update(&mut self, rule: &'static str, offset: usize, end_offset: usize, value: Box<dyn Any>) {
+        if let Some(seed) = self.seeds.get_mut(&(offset, rule)) {
+            seed.value = Some((end_offset, value));
+        }
+    }
+    pub fn remove(&mut self, rule: &'static str, offset: usize) {
+        self.seeds.remove(&(offset, rule));
+    }
+}
+
+impl<'i> ParseState<'i> {
+    /// Parse a left recursive rule by growing the seed, requires a [ParseContext].
+    ///
+    /// The first result of the rule is planted as the seed, then the rule is parsed again and again with the seed as
+    /// the result of the recursive call, until the match can not be longer.
+    /// So direct and indirect left recursion terminate, and produce left-associative results.
+    ///
+    /// The final result is cached if a [MemoTable](crate::MemoTable) is attached, see [ParseState::memoize].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::{ParseContext, ParseResult, ParseState};
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// enum Expr {
+    ///     Number(char),
+    ///     Minus(Box<Expr>, Box<Expr>),
+    /// }
+    /// // expr = expr "-" number | number
+    /// fn expr(state: ParseState) -> ParseResult<Expr> {
+    ///     state.left_recursive("expr", |s| {
+    ///         s.begin_choice()
+    ///             .or_else(|s| {
+    ///                 let (s, lhs) = s.match_fn(expr)?;
+    ///                 let (s, _) = s.match_char('-')?;
+    ///                 let (s, rhs) = s.match_fn(number)?;
+    ///                 s.finish(Expr::Minus(Box::new(lhs), Box::new(rhs)))
+    ///             })
+    ///             .or_else(number)
+    ///             .end_choice()
+    ///     })
+    /// }
+    /// fn number(state: ParseState) -> ParseResult<Expr> {
+    ///     state.match_char_range('0', '9').map_inner(Expr::Number)
+    /// }
+    /// let context = ParseContext::default();
+    /// let state = ParseState::new("3-2-1").with_context(&context);
+    /// let n = |c| Box::new(Expr::Number(c));
+    /// // (3 - 2) - 1
+    /// let expect = Expr::Minus(Box::new(Expr::Minus(n('3'), n('2'))), n('1'));
+    /// assert_eq!(expr(state).unwrap(), expect);
+    /// ```
+    #[inline]
+    pub fn left_recursive<T, F>(self, rule: &'static str, mut parse: F) -> ParseResult<'i, T>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        T: Clone + 'static,
+    {
+        match self.context {
+            Some(_) => self.memoize(rule, |s| s.grow_seed(rule, &mut parse)),
+            None => StopBecause::custom_error("Left recursion requires a ParseContext", self.start_offset, self.start_offset)?,
+        }
+    }
+    fn grow_seed<T, F>(self, rule: &'static str, parse: &mut F) -> ParseResult<'i, T>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        T: Clone + 'static,
+    {
+        let Some(context) = self.context
+        else {
+            return parse(self);
+        };
+        let offset = self.start_offset;
+        match context.seed_lookup::<T>(rule, offset) {
+            // the recursive call, returns the seed
+            Some(SeedHit::Pending(end_offset, value)) => return self.advance(end_offset - offset).finish(value),
+            Some(SeedHit::Stop) => return Stop(StopBecause::Uninitialized),
+            None => context.seed_plant(rule, offset),
+        }
+        let mark = context.error_mark();
+        let mut result = parse(self);
+        if !context.seed_is_detected(rule, offset) {
+            context.seed_remove(rule, offset);
+            return result;
+        }
+        let mut errors = context.get_errors_since(mark);
+        while let Pending(state, value) = &result {
+            let end_offset = state.start_offset;
+            context.seed_update(rule, offset, end_offset, Box::new(value.clone()));
+            context.rollback_errors(mark);
+            match parse(self) {
+                Pending(state, value) if state.start_offset > end_offset => {
+                    errors = context.get_errors_since(mark);
+                    result = Pending(state, value)
+                }
+                Pending(..) => break,
+                Stop(e) => {
+                    context.record_error(e);
+                    break;
+                }
+            }
+        }
+        context.rollback_errors(mark);
+        for error in errors {
+            context.push_error(error)
+        }
+        context.seed_remove(rule, offset);
+        result
+    }
+}
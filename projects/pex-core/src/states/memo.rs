@@ -0,0 +1,187 @@
+use super::*;
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+};
+use core::any::Any;
+
+/// A packrat memo table, caches the outcome of rules at each offset.
+///
+/// Attach it to a parse by [ParseContext::with_memo], the oldest entries are dropped when the table is full.
+#[derive(Debug)]
+pub struct MemoTable {
+    entries: BTreeMap<(usize, &'static str), MemoEntry>,
+    order: VecDeque<(usize, &'static str)>,
+    capacity: usize,
+    stats: MemoStats,
+}
+
+/// Statistics of a [MemoTable]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MemoStats {
+    /// How many lookups found a cached outcome
+    pub hits: usize,
+    /// How many lookups found nothing
+    pub misses: usize,
+    /// How many entries were dropped because the table is full
+    pub evictions: usize,
+    /// How many entries are in the table now
+    pub entries: usize,
+}
+
+#[derive(Debug)]
+enum MemoEntry {
+    Pending {
+        end_offset: usize,
+        value: Box<dyn Any>,
+        /// Errors recovered while parsing the rule, replayed when hit
+        errors: Vec<StopBecause>,
+        /// The furthest offset examined by the rule
+        examined: usize,
+    },
+    Stop(StopBecause, usize),
+}
+
+pub(crate) enum MemoHit<T> {
+    Pending(usize, T, Vec<StopBecause>, usize),
+    Stop(StopBecause, usize),
+}
+
+impl MemoTable {
+    /// Create a new table that holds at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: BTreeMap::new(), order: VecDeque::new(), capacity, stats: MemoStats::default() }
+    }
+    /// Get the statistics of the table
+    pub fn get_stats(&self) -> MemoStats {
+        MemoStats { entries: self.entries.len(), ..self.stats }
+    }
+    /// Drop all entries, the statistics are kept
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+    /// Drop all entries of the rule
+    pub fn remove_rule(&mut self, rule: &'static str) {
+        self.entries.retain(|(_, name), _| *name != rule);
+        self.order.retain(|(_, name)| *name != rule);
+    }
+    pub(crate) fn lookup<T>(&mut self, rule: &'static str, offset: usize) -> Option<MemoHit<T>>
+    where
+        T: Clone + 'static,
+    {
+        let hit = match self.entries.get(&(offset, rule)) {
+            // same rule name with different types, treat as missing
+            Some(MemoEntry::Pending { end_offset, value, errors, examined }) => {
+                value.downcast_ref::<T>().map(|value| MemoHit::Pending(*end_offset, value.clone(), errors.clone(), *examined))
+            }
+            Some(MemoEntry::Stop(error, examined)) => Some(MemoHit::Stop(*error, *examined)),
+            None => None,
+        };
+        match hit {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        hit
+    }
+    pub(crate) fn insert<T>(
+        &mut self,
+        rule: &'static str,
+        offset: usize,
+        result: &ParseResult<T>,
+        errors: Vec<StopBecause>,
+        examined: usize,
+    ) where
+        T: Clone + 'static,
+    {
+        if self.capacity == 0 {
+            return;
+        }
+        let entry = match result {
+            Pending(state, value) => {
+                MemoEntry::Pending { end_offset: state.start_offset, value: Box::new(value.clone()), errors, examined }
+            }
+            Stop(error) => MemoEntry::Stop(*error, examined),
+        };
+        if self.entries.insert((offset, rule), entry).is_none() {
+            self.order.push_back((offset, rule));
+        }
+        while self.entries.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(key) => {
+                    self.entries.remove(&key);
+                    self.stats.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'i> ParseState<'i> {
+    /// Parse the rule with memoization, the outcome is cached by the rule name and the start offset.
+    ///
+    /// Parse directly if there is no [MemoTable] attached, see [ParseContext::with_memo].
+    ///
+    /// The cached value must be `'static`, return owned values or ranges instead of borrowed strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::{ParseContext, ParseResult, ParseState};
+    /// fn digits(state: ParseState) -> ParseResult<String> {
+    ///     state.memoize("digits", |s| {
+    ///         s.match_str_if(|c| c.is_ascii_digit(), "DIGITS").map_inner(String::from)
+    ///     })
+    /// }
+    /// // digits ":" | digits ";"
+    /// fn entry(state: ParseState) -> ParseResult<String> {
+    ///     state
+    ///         .begin_choice()
+    ///         .or_else(|s| {
+    ///             let (s, digits) = s.match_fn(digits)?;
+    ///             s.match_str(":").map_value(digits)
+    ///         })
+    ///         .or_else(|s| {
+    ///             let (s, digits) = s.match_fn(digits)?;
+    ///             s.match_str(";").map_value(digits)
+    ///         })
+    ///         .end_choice()
+    /// }
+    /// let context = ParseContext::default().with_memo(1024);
+    /// let state = ParseState::new("123;").with_context(&context);
+    /// assert_eq!(entry(state).unwrap(), "123");
+    /// let stats = context.get_memo_stats().unwrap();
+    /// assert_eq!((stats.hits, stats.misses), (1, 1));
+    /// ```
+    #[inline]
+    pub fn memoize<T, F>(self, rule: &'static str, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        T: Clone + 'static,
+    {
+        let context = match self.context {
+            Some(context) if context.has_memo() => context,
+            _ => return self.traced(rule, parse),
+        };
+        match context.memo_lookup::<T>(rule, self.start_offset) {
+            Some(MemoHit::Pending(end_offset, value, errors, examined)) => {
+                for error in errors {
+                    context.push_error(error)
+                }
+                context.touch(examined);
+                self.advance(end_offset - self.start_offset).finish(value)
+            }
+            Some(MemoHit::Stop(error, examined)) => {
+                context.touch(examined);
+                Stop(error)
+            }
+            None => {
+                let mark = context.error_mark();
+                let (result, examined) = context.track_examined(self.start_offset, || self.traced(rule, parse));
+                context.memo_insert(rule, self.start_offset, &result, mark, examined);
+                result
+            }
+        }
+    }
+}
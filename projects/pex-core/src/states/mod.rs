@@ -1,5 +1,7 @@
 use crate::{
+    helpers::{CaseFolding, XID_CONTINUE},
     results::StopBecause,
+    states::context::ParseContext,
     ParseResult,
     ParseResult::{Pending, Stop},
 };
@@ -21,6 +23,14 @@ pub mod advance;
 mod builtin;
 pub mod choice;
 mod concat;
+pub mod context;
+mod guard;
+pub mod incremental;
+mod left_recursive;
+pub mod memo;
+pub mod partial;
+mod recover;
+pub mod trace;
 
 /// Represent a parsed value
 pub type Parsed<'i, T> = (ParseState<'i>, T);
@@ -34,13 +44,17 @@ pub struct ParseState<'i> {
     pub start_offset: usize,
     /// Stop reason
     pub stop_reason: Option<StopBecause>,
+    /// Shared context of the parse
+    pub context: Option<&'i ParseContext>,
+    /// Whether more input may follow the residual
+    pub partial: bool,
 }
 
 impl<'i> ParseState<'i> {
     /// Create a new state
     #[inline(always)]
     pub const fn new(input: &'i str) -> Self {
-        Self { residual: input, start_offset: 0, stop_reason: None }
+        Self { residual: input, start_offset: 0, stop_reason: None, context: None, partial: false }
     }
     /// Reset the cursor offset
     #[inline(always)]
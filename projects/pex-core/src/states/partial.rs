@@ -0,0 +1,124 @@
+use super::*;
+use alloc::string::String;
+
+impl<'i> ParseState<'i> {
+    /// Mark the residual as partial input, more input may follow it.
+    ///
+    /// Combinators that hit the end of partial input stop with [StopBecause::Incomplete] instead of a mismatch,
+    /// and backtracking combinators pass it through, see [StreamDriver] to resume the parse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::ParseState;
+    /// let state = ParseState::new("ret").with_partial(true);
+    /// assert!(state.match_str("return").is_incomplete());
+    /// assert!(state.match_str("raise").is_failure());
+    /// assert!(!state.match_str("raise").is_incomplete());
+    /// ```
+    #[inline(always)]
+    pub const fn with_partial(mut self, partial: bool) -> Self {
+        self.partial = partial;
+        self
+    }
+}
+
+/// Drive a parser over input that arrives in chunks.
+///
+/// The buffer is parsed as partial input until [StreamDriver::finish] is called,
+/// the consumed text is dropped after each successful parse, and the offsets still count from the start of the stream.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{ParseResult, ParseState, StreamDriver, StreamStatus};
+/// fn line(state: ParseState) -> ParseResult<String> {
+///     let (state, text) = state.match_str_until(|c| c == '\n', "LINE")?;
+///     let (state, _) = state.match_char('\n')?;
+///     state.finish(text.to_string())
+/// }
+/// let mut driver = StreamDriver::default();
+/// driver.push_str("GET /a\nGET");
+/// assert_eq!(driver.parse(line), StreamStatus::Ready("GET /a".to_string()));
+/// assert_eq!(driver.parse(line), StreamStatus::Incomplete);
+/// driver.push_str(" /b\n");
+/// assert_eq!(driver.parse(line), StreamStatus::Ready("GET /b".to_string()));
+/// assert_eq!(driver.get_offset(), 14);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StreamDriver {
+    buffer: String,
+    start_offset: usize,
+    finished: bool,
+}
+
+/// The outcome of [StreamDriver::parse]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StreamStatus<T> {
+    /// Parsed a value, the consumed text is dropped
+    Ready(T),
+    /// Need more input, push the next chunk and parse again
+    Incomplete,
+    /// The input does not match
+    Failed(StopBecause),
+}
+
+impl StreamDriver {
+    /// Append the next chunk of input
+    pub fn push_str(&mut self, chunk: &str) {
+        self.buffer.push_str(chunk)
+    }
+    /// Mark the end of the stream, the rest of the buffer is parsed as complete input
+    pub fn finish(&mut self) {
+        self.finished = true
+    }
+    /// Check if the end of the stream is reached
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Get the text that is not consumed yet
+    pub fn get_buffer(&self) -> &str {
+        &self.buffer
+    }
+    /// Get the offset of the unconsumed text in the whole stream
+    pub fn get_offset(&self) -> usize {
+        self.start_offset
+    }
+    /// Parse the buffered text, drop the consumed text if succeed.
+    pub fn parse<T, F>(&mut self, parse: F) -> StreamStatus<T>
+    where
+        F: for<'i> FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let state = ParseState::new(&self.buffer).with_start_offset(self.start_offset).with_partial(!self.finished);
+        let (end_offset, status) = split_status(parse(state));
+        self.consume(end_offset);
+        status
+    }
+    /// Parse the buffered text with a shared context, drop the consumed text if succeed.
+    pub fn parse_with_context<T, F>(&mut self, context: &ParseContext, parse: F) -> StreamStatus<T>
+    where
+        F: for<'i> FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let state = ParseState::new(&self.buffer)
+            .with_start_offset(self.start_offset)
+            .with_partial(!self.finished)
+            .with_context(context);
+        let (end_offset, status) = split_status(parse(state));
+        self.consume(end_offset);
+        status
+    }
+    fn consume(&mut self, end_offset: Option<usize>) {
+        if let Some(end_offset) = end_offset {
+            self.buffer.drain(..end_offset - self.start_offset);
+            self.start_offset = end_offset;
+        }
+    }
+}
+
+fn split_status<T>(result: ParseResult<T>) -> (Option<usize>, StreamStatus<T>) {
+    match result {
+        Pending(state, value) => (Some(state.start_offset), StreamStatus::Ready(value)),
+        Stop(e) if e.is_incomplete() => (None, StreamStatus::Incomplete),
+        Stop(e) => (None, StreamStatus::Failed(e)),
+    }
+}
@@ -0,0 +1,96 @@
+use super::*;
+use crate::StringView;
+
+/// Error recovery methods, only works if the context is in recovery mode.
+impl<'i> ParseState<'i> {
+    /// Check if the state can recover from an error, see [ParseContext::with_max_errors]
+    #[inline]
+    pub fn can_recover(&self) -> bool {
+        match self.context {
+            Some(context) => context.can_recover(),
+            None => false,
+        }
+    }
+    /// Parse with the given parser, if failed, record the error and recover with the `recover` parser.
+    ///
+    /// The error is pushed to the error sink only if the recovery succeed,
+    /// and replaces the errors recovered inside the failed attempt.
+    /// If the state can not recover, the original error is returned.
+    #[inline]
+    pub fn recover_with<T, F, R>(self, mut parse: F, recover: R) -> ParseResult<'i, T>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        R: FnOnce(ParseState<'i>, StopBecause) -> ParseResult<'i, T>,
+    {
+        let Some(context) = self.context
+        else {
+            return parse(self);
+        };
+        let mark = context.error_mark();
+        match parse(self) {
+            Pending(state, value) => Pending(state, value),
+            Stop(error) => {
+                context.rollback_errors(mark);
+                if !context.can_recover() {
+                    return Stop(error);
+                }
+                context.push_error(error);
+                match recover(self, error) {
+                    Pending(state, value) => Pending(state, value),
+                    Stop(_) => {
+                        context.rollback_errors(mark);
+                        Stop(error)
+                    }
+                }
+            }
+        }
+    }
+    /// Parse with the given parser, if failed, record the error and skip until the `sync` pattern matches,
+    /// then continue with a placeholder value.
+    ///
+    /// The sync pattern is not consumed, the skipped text is passed to the placeholder.
+    ///
+    /// ```ygg
+    /// p | (!sync ANY)*
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::{helpers::{str, whitespace}, ParseContext, ParseResult, ParseState};
+    /// fn number(state: ParseState) -> ParseResult<Option<u8>> {
+    ///     let (state, digits) = state.match_str_if(|c| c.is_ascii_digit(), "NUMBER")?;
+    ///     state.finish(digits.parse().ok())
+    /// }
+    /// fn statement(state: ParseState) -> ParseResult<Option<u8>> {
+    ///     let (state, value) = state.skip(whitespace).recover_until(number, str(";"), |_, _| None)?;
+    ///     let (state, _) = state.match_str(";")?;
+    ///     state.finish(value)
+    /// }
+    /// let context = ParseContext::default().with_max_errors(8);
+    /// let state = ParseState::new("1; x; 3;").with_context(&context);
+    /// let (value, errors) = state.match_repeats(statement).recovered(&context);
+    /// assert_eq!(value, Some(vec![Some(1), None, Some(3)]));
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].range().start, 3);
+    /// ```
+    #[inline]
+    pub fn recover_until<T, F, S, U, P>(self, parse: F, mut sync: S, placeholder: P) -> ParseResult<'i, T>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+        S: FnMut(ParseState<'i>) -> ParseResult<'i, U>,
+        P: FnOnce(StopBecause, StringView<'i>) -> T,
+    {
+        self.recover_with(parse, |input, error| {
+            let mut state = input;
+            while let Some(c) = state.get_character(0) {
+                if sync(state).is_success() {
+                    break;
+                }
+                state = state.advance(c);
+            }
+            let skipped = StringView::new(&input.residual[..state.start_offset - input.start_offset], input.start_offset);
+            state.finish(placeholder(error, skipped))
+        })
+    }
+}
@@ -0,0 +1,218 @@
+use super::*;
+#[cfg(feature = "trace")]
+use alloc::string::String;
+#[cfg(feature = "trace")]
+use core::fmt::Write;
+
+/// The recorded calls of a parse, enable the `trace` feature and attach by [ParseContext::with_trace].
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{ParseContext, ParseResult, ParseState};
+/// fn digit(state: ParseState) -> ParseResult<char> {
+///     state.match_rule("digit", |s| s.match_char_range('0', '9'))
+/// }
+/// let context = ParseContext::default().with_trace();
+/// let state = ParseState::new("12a").with_context(&context);
+/// state.match_rule("number", |s| s.match_repeats(digit)).unwrap();
+/// let trace = context.take_trace().unwrap();
+/// assert_eq!(
+///     trace.to_text(),
+///     "number 0..2\n  match_repeats 0..2\n    digit 0..1\n    digit 1..2\n    digit 2 failed: Expect character in range '0'..='9'\n"
+/// );
+/// assert!(trace.to_json().starts_with(r#"[{"name":"number","start":0,"end":2,"#));
+/// assert!(trace.to_dot().starts_with("digraph trace {"));
+/// ```
+#[cfg(feature = "trace")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParseTrace {
+    /// The top level calls
+    pub roots: Vec<TraceNode>,
+    /// The calls not exited yet
+    stack: Vec<TraceNode>,
+}
+
+/// A recorded call of a combinator or rule
+#[cfg(feature = "trace")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceNode {
+    /// The name of the combinator or rule
+    pub name: &'static str,
+    /// The offset where the call entered
+    pub start: usize,
+    /// The offset where the call exited, or the reason why it stopped
+    pub outcome: Result<usize, StopBecause>,
+    /// The calls made inside
+    pub children: Vec<TraceNode>,
+}
+
+#[cfg(feature = "trace")]
+impl ParseTrace {
+    pub(crate) fn enter(&mut self, name: &'static str, start: usize) {
+        self.stack.push(TraceNode { name, start, outcome: Err(StopBecause::Uninitialized), children: Vec::new() })
+    }
+    pub(crate) fn exit(&mut self, outcome: Result<usize, StopBecause>) {
+        if let Some(mut node) = self.stack.pop() {
+            node.outcome = outcome;
+            self.siblings().push(node);
+        }
+    }
+    /// The count of the calls recorded in the current call
+    pub(crate) fn mark(&mut self) -> usize {
+        self.siblings().len()
+    }
+    /// Move the calls recorded after the mark into a new call
+    pub(crate) fn group(&mut self, name: &'static str, start: usize, mark: usize, outcome: Result<usize, StopBecause>) {
+        let siblings = self.siblings();
+        let children = siblings.split_off(mark.min(siblings.len()));
+        siblings.push(TraceNode { name, start, outcome, children });
+    }
+    fn siblings(&mut self) -> &mut Vec<TraceNode> {
+        match self.stack.last_mut() {
+            Some(parent) => &mut parent.children,
+            None => &mut self.roots,
+        }
+    }
+    /// Export as an indented text tree
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for node in &self.roots {
+            node.write_text(&mut out, 0);
+        }
+        out
+    }
+    /// Export as a JSON array of nodes
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_json_list(&self.roots, &mut out);
+        out
+    }
+    /// Export as a Graphviz DOT graph, failed calls are red
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph trace {\n    node [shape=box];\n");
+        let mut id = 0;
+        for node in &self.roots {
+            node.write_dot(&mut out, &mut id);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(feature = "trace")]
+impl TraceNode {
+    fn write_text(&self, out: &mut String, depth: usize) {
+        for _ in 0..depth {
+            out.push_str("  ");
+        }
+        let _ = match self.outcome {
+            Ok(end) => writeln!(out, "{} {}..{}", self.name, self.start, end),
+            Err(e) => writeln!(out, "{} {} failed: {}", self.name, self.start, e),
+        };
+        for child in &self.children {
+            child.write_text(out, depth + 1)
+        }
+    }
+    fn write_json(&self, out: &mut String) {
+        out.push_str(r#"{"name":"#);
+        write_json_string(self.name, out);
+        let _ = write!(out, r#","start":{},"#, self.start);
+        let _ = match self.outcome {
+            Ok(end) => write!(out, r#""end":{},"error":null"#, end),
+            Err(e) => {
+                out.push_str(r#""end":null,"error":"#);
+                write_json_string(&alloc::format!("{}", e), out);
+                Ok(())
+            }
+        };
+        out.push_str(r#","children":"#);
+        write_json_list(&self.children, out);
+        out.push('}');
+    }
+    fn write_dot(&self, out: &mut String, id: &mut usize) -> usize {
+        let this = *id;
+        *id += 1;
+        let mut label = String::new();
+        let _ = match self.outcome {
+            Ok(end) => write!(label, "{}\n{}..{}", self.name, self.start, end),
+            Err(e) => write!(label, "{}\n{}: {}", self.name, self.start, e),
+        };
+        out.push_str("    n");
+        let _ = write!(out, "{} [label=", this);
+        write_json_string(&label, out);
+        if self.outcome.is_err() {
+            out.push_str(", color=red");
+        }
+        out.push_str("];\n");
+        for child in &self.children {
+            let child_id = child.write_dot(out, id);
+            let _ = writeln!(out, "    n{} -> n{};", this, child_id);
+        }
+        this
+    }
+}
+
+#[cfg(feature = "trace")]
+fn write_json_list(nodes: &[TraceNode], out: &mut String) {
+    out.push('[');
+    for (index, node) in nodes.iter().enumerate() {
+        if index != 0 {
+            out.push(',');
+        }
+        node.write_json(out);
+    }
+    out.push(']');
+}
+
+/// Quoted and escaped, also valid in DOT
+#[cfg(feature = "trace")]
+fn write_json_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+impl<'i> ParseState<'i> {
+    /// Parse a named rule, the name shows in the trace if the `trace` feature is on.
+    #[inline(always)]
+    pub fn match_rule<T, F>(self, name: &'static str, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        self.traced(name, parse)
+    }
+    /// Record the call in the trace and check the runaway guards, the trace is compiled out if the `trace` feature is off.
+    #[inline(always)]
+    pub(crate) fn traced<T, F>(self, name: &'static str, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        #[cfg(feature = "trace")]
+        if let Some(context) = self.context {
+            if context.trace_enter(name, self.start_offset) {
+                let result = self.guarded(parse);
+                context.trace_exit(match &result {
+                    Pending(state, _) => Ok(state.start_offset),
+                    Stop(e) => Err(*e),
+                });
+                return result;
+            }
+        }
+        #[cfg(not(feature = "trace"))]
+        let _ = name;
+        self.guarded(parse)
+    }
+}
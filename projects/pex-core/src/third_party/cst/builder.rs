@@ -0,0 +1,185 @@
+use super::*;
+
+/// Build a green tree while parsing, every method takes `&self` so that all branches of a choice can share the builder.
+///
+/// Tokens are pushed only if the parser succeeded, nodes started by a failed branch are dropped by [NodeBuilder::parse_node].
+///
+/// # Examples
+///
+/// ```
+/// # use pex::{cst::{NodeBuilder, SyntaxKind, SyntaxNode}, ParseState};
+/// const BINARY: SyntaxKind = SyntaxKind(0);
+/// const NUMBER: SyntaxKind = SyntaxKind(1);
+/// const PLUS: SyntaxKind = SyntaxKind(2);
+/// const SPACE: SyntaxKind = SyntaxKind(3);
+/// let builder = NodeBuilder::default();
+/// let spaces = |s: ParseState<'static>| s.match_str_if(|c| c == ' ', "SPACE");
+/// let digits = |s: ParseState<'static>| s.match_str_if(|c| c.is_ascii_digit(), "NUMBER");
+/// // parse the lhs first, then wrap it into a binary node
+/// let checkpoint = builder.checkpoint();
+/// let (state, _) =
+///     builder.parse_token(NUMBER, ParseState::new("1 + 2"), digits).as_result().unwrap();
+/// builder.start_node_at(checkpoint, BINARY);
+/// let state = builder.skip_trivia(SPACE, state, spaces);
+/// let (state, _) = builder.parse_token(PLUS, state, |s| s.match_char('+')).as_result().unwrap();
+/// let state = builder.skip_trivia(SPACE, state, spaces);
+/// builder.parse_token(NUMBER, state, digits).unwrap();
+/// builder.finish_node();
+/// let root = SyntaxNode::new_root(builder.finish().unwrap());
+/// assert_eq!((root.get_kind(), root.to_string()), (BINARY, "1 + 2".to_string()));
+/// assert_eq!(root.token_at_offset(2).unwrap().get_range(), 2..3);
+/// assert!(root.token_at_offset(3).unwrap().is_trivia());
+/// ```
+#[derive(Debug, Default)]
+pub struct NodeBuilder {
+    state: RefCell<BuilderState>,
+}
+
+/// A position in the [NodeBuilder], used to wrap the nodes built after it, or to drop them
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    parents: usize,
+    children: usize,
+}
+
+#[derive(Debug, Default)]
+struct BuilderState {
+    /// The started nodes, and where their children start
+    parents: Vec<(SyntaxKind, usize)>,
+    children: Vec<GreenElement>,
+    tokens: BTreeSet<Rc<GreenToken>>,
+    /// Small nodes by kind and the addresses of the children, the children are interned so equal nodes have equal keys
+    nodes: BTreeMap<(SyntaxKind, Vec<usize>), Rc<GreenNode>>,
+}
+
+impl NodeBuilder {
+    /// Start a new node, the following tokens and nodes are its children until [NodeBuilder::finish_node]
+    pub fn start_node(&self, kind: SyntaxKind) {
+        let mut state = self.state.borrow_mut();
+        let start = state.children.len();
+        state.parents.push((kind, start));
+    }
+    /// Finish the last started node
+    ///
+    /// # Panics
+    ///
+    /// Panics if no node is started
+    pub fn finish_node(&self) {
+        let mut state = self.state.borrow_mut();
+        let (kind, start) = state.parents.pop().expect("no node to finish");
+        let children = state.children.split_off(start);
+        let node = state.intern_node(kind, children);
+        state.children.push(GreenElement::Node(node));
+    }
+    /// Push a significant token to the current node
+    pub fn token(&self, kind: SyntaxKind, text: &str) {
+        let mut state = self.state.borrow_mut();
+        let token = state.intern_token(GreenToken::new(kind, text));
+        state.children.push(GreenElement::Token(token));
+    }
+    /// Push a trivia token to the current node, such as whitespace or comment
+    pub fn trivia(&self, kind: SyntaxKind, text: &str) {
+        let mut state = self.state.borrow_mut();
+        let token = state.intern_token(GreenToken::new_trivia(kind, text));
+        state.children.push(GreenElement::Token(token));
+    }
+    /// Mark the current position
+    pub fn checkpoint(&self) -> Checkpoint {
+        let state = self.state.borrow();
+        Checkpoint { parents: state.parents.len(), children: state.children.len() }
+    }
+    /// Start a new node which wraps everything built after the checkpoint
+    ///
+    /// # Panics
+    ///
+    /// Panics if the nodes started after the checkpoint are not finished
+    pub fn start_node_at(&self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        let mut state = self.state.borrow_mut();
+        assert_eq!(checkpoint.parents, state.parents.len(), "unfinished nodes after the checkpoint");
+        assert!(checkpoint.children <= state.children.len(), "checkpoint is rolled back");
+        state.parents.push((kind, checkpoint.children));
+    }
+    /// Drop everything built after the checkpoint, used when a branch failed
+    pub fn rollback(&self, checkpoint: Checkpoint) {
+        let mut state = self.state.borrow_mut();
+        state.parents.truncate(checkpoint.parents);
+        state.children.truncate(checkpoint.children);
+    }
+    /// Finish the tree, returns `None` if some nodes are not finished or there is not exactly one root node
+    pub fn finish(self) -> Option<Rc<GreenNode>> {
+        let mut state = self.state.into_inner();
+        if !state.parents.is_empty() || state.children.len() != 1 {
+            return None;
+        }
+        match state.children.pop()? {
+            GreenElement::Node(node) => Some(node),
+            GreenElement::Token(_) => None,
+        }
+    }
+}
+
+impl NodeBuilder {
+    /// Parse a token, push the consumed text if succeed
+    pub fn parse_token<'i, F, T>(&self, kind: SyntaxKind, input: ParseState<'i>, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let (state, value) = parse(input)?;
+        self.token(kind, &input.residual[..state.start_offset - input.start_offset]);
+        state.finish(value)
+    }
+    /// Parse the trivia like [ParseState::skip], push the consumed text if not empty
+    pub fn skip_trivia<'i, F, T>(&self, kind: SyntaxKind, input: ParseState<'i>, parse: F) -> ParseState<'i>
+    where
+        F: FnMut(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let state = input.skip(parse);
+        if state.start_offset > input.start_offset {
+            self.trivia(kind, &input.residual[..state.start_offset - input.start_offset]);
+        }
+        state
+    }
+    /// Parse the children of a node, everything built by the parser is dropped if failed
+    pub fn parse_node<'i, F, T>(&self, kind: SyntaxKind, input: ParseState<'i>, parse: F) -> ParseResult<'i, T>
+    where
+        F: FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+    {
+        let checkpoint = self.checkpoint();
+        self.start_node(kind);
+        match parse(input) {
+            ParseResult::Pending(state, value) => {
+                self.finish_node();
+                state.finish(value)
+            }
+            ParseResult::Stop(e) => {
+                self.rollback(checkpoint);
+                ParseResult::Stop(e)
+            }
+        }
+    }
+}
+
+impl BuilderState {
+    fn intern_token(&mut self, token: GreenToken) -> Rc<GreenToken> {
+        if let Some(old) = self.tokens.get(&token) {
+            return old.clone();
+        }
+        let token = Rc::new(token);
+        self.tokens.insert(token.clone());
+        token
+    }
+    fn intern_node(&mut self, kind: SyntaxKind, children: Vec<GreenElement>) -> Rc<GreenNode> {
+        // big nodes are unlikely to repeat
+        if children.len() > 3 {
+            return Rc::new(GreenNode::new(kind, children));
+        }
+        let key = children
+            .iter()
+            .map(|child| match child {
+                GreenElement::Node(node) => Rc::as_ptr(node) as usize,
+                GreenElement::Token(token) => Rc::as_ptr(token) as usize,
+            })
+            .collect();
+        self.nodes.entry((kind, key)).or_insert_with(|| Rc::new(GreenNode::new(kind, children))).clone()
+    }
+}
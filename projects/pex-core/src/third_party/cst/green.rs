@@ -0,0 +1,125 @@
+use super::*;
+
+/// The kind of a node or token, usually converted from an user defined enum
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SyntaxKind(pub u16);
+
+impl From<u16> for SyntaxKind {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+/// An immutable token in the green tree, equal tokens are shared by the [NodeBuilder]
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    trivia: bool,
+    text: String,
+}
+
+/// An immutable node in the green tree, only knows the lengths of the children but not the offsets
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    length: usize,
+    children: Vec<GreenElement>,
+}
+
+/// A child of the [GreenNode]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GreenElement {
+    /// A child node
+    Node(Rc<GreenNode>),
+    /// A child token
+    Token(Rc<GreenToken>),
+}
+
+impl GreenToken {
+    /// Create a new significant token
+    pub fn new(kind: SyntaxKind, text: &str) -> Self {
+        Self { kind, trivia: false, text: text.to_string() }
+    }
+    /// Create a new trivia token, such as whitespace or comment
+    pub fn new_trivia(kind: SyntaxKind, text: &str) -> Self {
+        Self { kind, trivia: true, text: text.to_string() }
+    }
+    /// Get the kind of the token
+    pub fn get_kind(&self) -> SyntaxKind {
+        self.kind
+    }
+    /// Get the text of the token
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+    /// Get the length of the text in bytes
+    pub fn get_length(&self) -> usize {
+        self.text.len()
+    }
+    /// Check if the token is a trivia
+    pub fn is_trivia(&self) -> bool {
+        self.trivia
+    }
+}
+
+impl GreenNode {
+    /// Create a new node, the length is the sum of the children
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let length = children.iter().map(|c| c.get_length()).sum();
+        Self { kind, length, children }
+    }
+    /// Get the kind of the node
+    pub fn get_kind(&self) -> SyntaxKind {
+        self.kind
+    }
+    /// Get the length of the text in bytes, trivia included
+    pub fn get_length(&self) -> usize {
+        self.length
+    }
+    /// Get the children in source order
+    pub fn get_children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+impl GreenElement {
+    /// Get the kind of the node or token
+    pub fn get_kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind,
+            GreenElement::Token(token) => token.kind,
+        }
+    }
+    /// Get the length of the text in bytes
+    pub fn get_length(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.length,
+            GreenElement::Token(token) => token.get_length(),
+        }
+    }
+}
+
+impl Display for GreenToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Print the source text back, byte by byte
+impl Display for GreenNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for child in &self.children {
+            Display::fmt(child, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for GreenElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GreenElement::Node(node) => Display::fmt(node, f),
+            GreenElement::Token(token) => Display::fmt(token, f),
+        }
+    }
+}
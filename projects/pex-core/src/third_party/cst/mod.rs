@@ -1 +1,27 @@
+//! A lossless concrete syntax tree, every byte of the source including whitespace and comments is kept.
+//!
+//! The green tree is immutable and interned, it only knows the kinds and the text lengths,
+//! the red tree is a cursor layer over it with parents and absolute offsets.
+//! Build the green tree with a [NodeBuilder] while parsing, then walk it by [SyntaxNode::new_root].
+use crate::{ParseResult, ParseState};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Display, Formatter},
+    ops::Range,
+};
 
+mod builder;
+mod green;
+mod red;
+
+pub use self::{
+    builder::{Checkpoint, NodeBuilder},
+    green::{GreenElement, GreenNode, GreenToken, SyntaxKind},
+    red::{SyntaxElement, SyntaxNode, SyntaxToken},
+};
@@ -0,0 +1,221 @@
+use super::*;
+
+/// A node in the red tree, a cursor over the [GreenNode] which knows the parent and the absolute offset
+#[derive(Clone)]
+pub struct SyntaxNode {
+    data: Rc<NodeData>,
+}
+
+struct NodeData {
+    green: Rc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    index: usize,
+    offset: usize,
+}
+
+/// A token in the red tree, always has a parent node
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+/// A child of the [SyntaxNode]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SyntaxElement {
+    /// A child node
+    Node(SyntaxNode),
+    /// A child token
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Create the root cursor of a green tree
+    pub fn new_root(green: Rc<GreenNode>) -> Self {
+        Self { data: Rc::new(NodeData { green, parent: None, index: 0, offset: 0 }) }
+    }
+    /// Get the kind of the node
+    pub fn get_kind(&self) -> SyntaxKind {
+        self.data.green.get_kind()
+    }
+    /// Get the green node under the cursor
+    pub fn get_green(&self) -> &Rc<GreenNode> {
+        &self.data.green
+    }
+    /// Get the absolute range in the source, trivia included
+    pub fn get_range(&self) -> Range<usize> {
+        self.data.offset..self.data.offset + self.data.green.get_length()
+    }
+    /// Get the parent node, `None` for the root
+    pub fn get_parent(&self) -> Option<&SyntaxNode> {
+        self.data.parent.as_ref()
+    }
+    /// Get the index in the children of the parent
+    pub fn get_index(&self) -> usize {
+        self.data.index
+    }
+    /// Get the ancestors, from the parent to the root
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        core::iter::successors(self.get_parent().cloned(), |node| node.get_parent().cloned())
+    }
+    /// Get the child node or token at the index
+    pub fn get_child(&self, index: usize) -> Option<SyntaxElement> {
+        let green = self.data.green.get_children().get(index)?;
+        let offset = self.data.green.get_children()[..index].iter().map(|c| c.get_length()).sum::<usize>();
+        Some(self.make_child(index, self.data.offset + offset, green))
+    }
+    /// Get all child nodes and tokens in source order
+    pub fn children(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.data.offset;
+        self.data.green.get_children().iter().enumerate().map(move |(index, green)| {
+            let child = self.make_child(index, offset, green);
+            offset += green.get_length();
+            child
+        })
+    }
+    /// Get the child nodes in source order, tokens are skipped
+    pub fn child_nodes(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+    /// Get all tokens in the subtree in source order, trivia included
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        let mut out = Vec::new();
+        self.collect_tokens(&mut out);
+        out
+    }
+    fn collect_tokens(&self, out: &mut Vec<SyntaxToken>) {
+        for child in self.children() {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(out),
+                SyntaxElement::Token(token) => out.push(token),
+            }
+        }
+    }
+    /// Find the token which covers the offset
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        if !self.get_range().contains(&offset) {
+            return None;
+        }
+        for child in self.children() {
+            if !child.get_range().contains(&offset) {
+                continue;
+            }
+            return match child {
+                SyntaxElement::Node(node) => node.token_at_offset(offset),
+                SyntaxElement::Token(token) => Some(token),
+            };
+        }
+        None
+    }
+    fn make_child(&self, index: usize, offset: usize, green: &GreenElement) -> SyntaxElement {
+        match green {
+            GreenElement::Node(node) => SyntaxElement::Node(SyntaxNode {
+                data: Rc::new(NodeData { green: node.clone(), parent: Some(self.clone()), index, offset }),
+            }),
+            GreenElement::Token(token) => {
+                SyntaxElement::Token(SyntaxToken { green: token.clone(), parent: self.clone(), index, offset })
+            }
+        }
+    }
+}
+
+impl SyntaxToken {
+    /// Get the kind of the token
+    pub fn get_kind(&self) -> SyntaxKind {
+        self.green.get_kind()
+    }
+    /// Get the green token under the cursor
+    pub fn get_green(&self) -> &Rc<GreenToken> {
+        &self.green
+    }
+    /// Get the text of the token
+    pub fn get_text(&self) -> &str {
+        self.green.get_text()
+    }
+    /// Get the absolute range in the source
+    pub fn get_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.get_length()
+    }
+    /// Get the parent node
+    pub fn get_parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+    /// Get the index in the children of the parent
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+    /// Check if the token is a trivia
+    pub fn is_trivia(&self) -> bool {
+        self.green.is_trivia()
+    }
+}
+
+impl SyntaxElement {
+    /// Get the kind of the node or token
+    pub fn get_kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.get_kind(),
+            SyntaxElement::Token(token) => token.get_kind(),
+        }
+    }
+    /// Get the absolute range in the source
+    pub fn get_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.get_range(),
+            SyntaxElement::Token(token) => token.get_range(),
+        }
+    }
+    /// Get the parent node, `None` for the root
+    pub fn get_parent(&self) -> Option<&SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => node.get_parent(),
+            SyntaxElement::Token(token) => Some(token.get_parent()),
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data.green, &other.data.green) && self.data.offset == other.data.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl Eq for SyntaxToken {}
+
+impl Debug for SyntaxNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}@{:?}", self.get_kind(), self.get_range())
+    }
+}
+
+impl Debug for SyntaxToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}@{:?} {:?}", self.get_kind(), self.get_range(), self.get_text())
+    }
+}
+
+/// Print the source text of the subtree back
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self.data.green.as_ref(), f)
+    }
+}
+
+impl Display for SyntaxToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.get_text())
+    }
+}
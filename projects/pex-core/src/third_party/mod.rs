@@ -2,4 +2,4 @@
 // mod intern_string;
 // mod intern_table;
 
-mod cst;
+pub mod cst;
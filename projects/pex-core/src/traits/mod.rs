@@ -1,6 +1,10 @@
+pub use self::registry::{PexRule, RuleRegistry};
 use crate::{ParseResult, ParseState};
+use alloc::boxed::Box;
 use core::ops::Range;
 
+mod registry;
+
 /// A trait for parsing a node from a [`ParseState`].
 pub trait PexNode
 where
@@ -20,3 +24,24 @@ where
         Range { start: range.start as u32, end: range.end as u32 }
     }
 }
+
+impl<T> PexNode for Box<T>
+where
+    T: PexNode,
+{
+    fn parse(input: ParseState) -> ParseResult<Self> {
+        T::parse(input).map_inner(Box::new)
+    }
+    fn get_range(&self) -> Range<usize> {
+        T::get_range(self)
+    }
+}
+
+/// A node that can be reused by incremental reparsing, see [IncrementalParser](crate::IncrementalParser).
+pub trait PexIncremental
+where
+    Self: PexNode + Clone + 'static,
+{
+    /// Move the range of the node and all its children, used when the text before the node was edited.
+    fn shift_range(&mut self, delta: isize);
+}
@@ -0,0 +1,85 @@
+use crate::PexNode;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter};
+
+/// A node described by a rule of the ygg notation, implemented by `derive(PexNode)` with `#[pex(export)]`.
+pub trait PexRule: PexNode {
+    /// The name of the rule, the name of the type
+    const NAME: &'static str;
+    /// Add the rule of the node and the rules of its children to the registry
+    fn register(registry: &mut RuleRegistry);
+}
+
+impl<T> PexRule for Box<T>
+where
+    T: PexRule,
+{
+    const NAME: &'static str = T::NAME;
+    fn register(registry: &mut RuleRegistry) {
+        T::register(registry)
+    }
+}
+
+/// The rules of a language in the ygg notation, collected from the derived nodes and the parsers written by hand.
+///
+/// The registry is exported by `pex-grammar` as EBNF, railroad diagrams and a reference page.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::RuleRegistry;
+/// let registry = RuleRegistry::default()
+///     .with_rule("list", "\"[\" (digits (\",\" digits)*)? \"]\"")
+///     .with_rule("digits", "[0-9]+");
+/// assert!(registry.contains("digits"));
+/// assert_eq!(
+///     registry.to_string(),
+///     "list = \"[\" (digits (\",\" digits)*)? \"]\";\ndigits = [0-9]+;\n"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RuleRegistry {
+    rules: Vec<(String, String)>,
+}
+
+impl RuleRegistry {
+    /// Add the rule of the node and the rules of its children
+    pub fn with_node<T: PexRule>(mut self) -> Self {
+        T::register(&mut self);
+        self
+    }
+    /// Add a rule written by hand, such as the rule of a parser function
+    pub fn with_rule(mut self, name: impl Into<String>, body: impl Into<String>) -> Self {
+        self.insert(&name.into(), &[&body.into()]);
+        self
+    }
+    /// Add the rule with the body joined from the parts, returns false if the name is registered
+    pub fn insert(&mut self, name: &str, body: &[&str]) -> bool {
+        if self.contains(name) {
+            return false;
+        }
+        self.rules.push((name.to_string(), body.concat()));
+        true
+    }
+    /// Whether the rule is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.rules.iter().any(|(rule, _)| rule == name)
+    }
+    /// The names and bodies of the rules, in the order of registration
+    pub fn get_rules(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.rules.iter().map(|(name, body)| (name.as_str(), body.as_str()))
+    }
+}
+
+impl Display for RuleRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for (name, body) in &self.rules {
+            writeln!(f, "{} = {};", name, body)?;
+        }
+        Ok(())
+    }
+}
@@ -2,3 +2,852 @@
 fn ready() {
     println!("it works!")
 }
+
+#[test]
+fn source_index() {
+    use pex::{ColumnMode, SourceIndex, SourcePosition};
+    let index = SourceIndex::new("a\r\nb\rc\n\nd");
+    assert_eq!(index.line_count(), 5);
+    assert_eq!(index.get_line_text(1), Some("a"));
+    assert_eq!(index.get_line_text(2), Some("b"));
+    assert_eq!(index.get_line_text(4), Some(""));
+    assert_eq!(index.get_line_text(6), None);
+    // inside `\r\n`
+    assert_eq!(index.get_position(2, ColumnMode::Bytes), SourcePosition { line: 1, column: 2 });
+    assert_eq!(index.get_position(3, ColumnMode::Bytes), SourcePosition { line: 2, column: 1 });
+    assert_eq!(index.get_position(99, ColumnMode::Bytes), SourcePosition { line: 5, column: 2 });
+    assert_eq!(index.get_offset(SourcePosition { line: 5, column: 2 }, ColumnMode::Bytes), Some(9));
+    assert_eq!(index.get_offset(SourcePosition { line: 5, column: 3 }, ColumnMode::Bytes), None);
+    // surrogate pair
+    let index = SourceIndex::new("𝑥=1");
+    assert_eq!(index.get_offset(SourcePosition { line: 1, column: 2 }, ColumnMode::Utf16), None);
+    assert_eq!(index.get_offset(SourcePosition { line: 1, column: 3 }, ColumnMode::Utf16), Some(4));
+    assert_eq!(index.get_position(2, ColumnMode::Characters), SourcePosition { line: 1, column: 1 });
+}
+
+#[test]
+fn furthest_failure() {
+    use pex::{ParseContext, ParseResult, ParseState};
+    // `[` digit (`,` digit)* `]`
+    fn list(state: ParseState) -> ParseResult<Vec<char>> {
+        let (state, _) = state.match_char('[')?;
+        let (state, first) = state.match_char_range('0', '9')?;
+        let (state, mut rest) = state.match_repeats(|s| {
+            let (s, _) = s.match_char(',')?;
+            s.match_char_range('0', '9')
+        })?;
+        let (state, _) = state.match_char(']')?;
+        rest.insert(0, first);
+        state.finish(rest)
+    }
+    let context = ParseContext::default();
+    let state = ParseState::new("[1,2,x]").with_context(&context);
+    let error = match list(state) {
+        ParseResult::Pending(..) => unreachable!(),
+        ParseResult::Stop(e) => e,
+    };
+    // the direct error is at the closing bracket
+    assert_eq!(error.range().start, 4);
+    let failure = context.merge_error(error);
+    assert_eq!(failure.position, 5);
+    assert_eq!(failure.to_string(), "expected '0'..='9' at 5");
+}
+
+#[test]
+fn recovery_cap() {
+    use pex::{
+        helpers::{str, whitespace, BalancedSkip},
+        ParseContext, ParseResult, ParseState, StopBecause,
+    };
+    fn statement(state: ParseState) -> ParseResult<Option<&str>> {
+        let (state, value) = state.skip(whitespace).recover_with(
+            |s| s.match_str_if(|c| c.is_ascii_digit(), "NUMBER").map_inner(Some),
+            |s, _| BalancedSkip::new(&[";"])(s).map_value(None),
+        )?;
+        let (state, _) = state.match_str(";")?;
+        state.finish(value)
+    }
+    let context = ParseContext::default().with_max_errors(1);
+    let state = ParseState::new("1; f(;); x; 3;").with_context(&context);
+    let (value, errors) = state.match_repeats(statement).recovered(&context);
+    // the second error reaches the cap, so the repeats stop there
+    assert_eq!(value, Some(vec![Some("1"), None]));
+    assert_eq!(errors.len(), 1);
+    // the errors recovered inside a failed attempt are replaced by its error
+    let context = ParseContext::default().with_max_errors(8);
+    let state = ParseState::new("x;").with_context(&context);
+    let result = state.recover_with(
+        |s| {
+            let (s, _) = s.recover_with(|s| s.match_char('a'), |s, _| s.finish('?'))?;
+            s.match_char('b')
+        },
+        |s, _| BalancedSkip::new(&[";"])(s).map_value('!'),
+    );
+    let (value, errors) = result.recovered(&context);
+    assert_eq!(value, Some('!'));
+    assert!(matches!(errors[..], [StopBecause::MissingCharacterRange { start: 'b', .. }]), "{:?}", errors);
+    // no context, no recovery
+    let state = ParseState::new("x;");
+    assert!(state.match_fn(|s| s.recover_until(statement, str(";"), |_, _| None)).is_failure());
+}
+
+#[test]
+fn memo_bounded() {
+    use pex::{ParseContext, ParseResult, ParseState};
+    fn letter(state: ParseState) -> ParseResult<char> {
+        state.memoize("letter", |s| s.match_char_range('a', 'z'))
+    }
+    let context = ParseContext::default().with_memo(2);
+    let state = ParseState::new("abc").with_context(&context);
+    assert_eq!(state.match_repeats(letter).unwrap(), vec!['a', 'b', 'c']);
+    // 3 letters and 1 failure at the end, only 2 entries kept
+    let stats = context.get_memo_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.evictions, stats.entries), (0, 4, 2, 2));
+    // the last two offsets are still cached
+    assert!(state.advance(2).match_fn(letter).is_success());
+    assert!(state.advance(3).match_fn(letter).is_failure());
+    assert_eq!(context.get_memo_stats().unwrap().hits, 2);
+    context.clear_memo();
+    assert_eq!(context.get_memo_stats().unwrap().entries, 0);
+}
+
+#[test]
+fn left_recursion() {
+    use pex::{ParseContext, ParseResult, ParseState};
+    // call = member "(" ")" | "f"
+    fn call(state: ParseState) -> ParseResult<String> {
+        state.left_recursive("call", |s| {
+            s.begin_choice()
+                .or_else(|s| {
+                    let (s, callee) = s.match_fn(member)?;
+                    let (s, _) = s.match_str("()")?;
+                    s.finish(format!("{callee}()"))
+                })
+                .or_else(|s| s.match_str("f").map_inner(String::from))
+                .end_choice()
+        })
+    }
+    // member = call "." "x" | call
+    fn member(state: ParseState) -> ParseResult<String> {
+        state.left_recursive("member", |s| {
+            s.begin_choice()
+                .or_else(|s| {
+                    let (s, object) = s.match_fn(call)?;
+                    let (s, _) = s.match_str(".x")?;
+                    s.finish(format!("({object}).x"))
+                })
+                .or_else(call)
+                .end_choice()
+        })
+    }
+    let context = ParseContext::default().with_memo(64);
+    let state = ParseState::new("f.x().x()").with_context(&context);
+    let (rest, value) = call(state).as_result().unwrap();
+    assert_eq!(value, "((f).x()).x()");
+    assert!(rest.residual.is_empty());
+    // cached after growing
+    assert_eq!(call(state).unwrap(), value);
+    assert!(ParseState::new("f").match_fn(call).is_failure());
+}
+
+#[test]
+fn expression_pattern() {
+    use pex::{
+        helpers::{Associativity, ExpressionNode, ExpressionPattern, Fixity, Operator},
+        ParseResult, ParseState,
+    };
+    fn atom(state: ParseState) -> ParseResult<i64> {
+        let (state, digits) = state.match_str_if(|c| c.is_ascii_digit(), "NUMBER")?;
+        state.finish(digits.parse().unwrap())
+    }
+    fn eval(node: ExpressionNode<i64>) -> i64 {
+        match node {
+            ExpressionNode::Prefix { rhs, .. } => -rhs,
+            ExpressionNode::Infix { lhs, operator, rhs } => match operator.as_string().as_str() {
+                "-" => lhs - rhs,
+                "==" => (lhs == rhs) as i64,
+                _ => lhs * rhs,
+            },
+            ExpressionNode::Postfix { lhs, .. } => lhs * lhs,
+        }
+    }
+    let mut pattern = ExpressionPattern::default()
+        .with_operator(Operator::infix("==", 0, Associativity::None))
+        .with_operator(Operator::infix("-", 1, Associativity::Left))
+        .with_operator(Operator::infix("*", 2, Associativity::Left))
+        .with_operator(Operator::prefix("-", 3))
+        .with_operator(Operator::postfix("**", 4));
+    assert_eq!(pattern.parse(ParseState::new("10-2-3"), atom, eval).unwrap(), 5);
+    assert_eq!(pattern.parse(ParseState::new("-2*3**"), atom, eval).unwrap(), -18);
+    // missing right operand, stop before the operator
+    let (state, value) = pattern.parse(ParseState::new("1-2*"), atom, eval).as_result().unwrap();
+    assert_eq!((value, state.residual), (-1, "*"));
+    // non-associative
+    assert_eq!(pattern.parse(ParseState::new("1==1"), atom, eval).unwrap(), 1);
+    assert!(pattern.parse(ParseState::new("1==1==1"), atom, eval).is_failure());
+    // change the fixity at runtime
+    pattern.insert_operator(Operator::infix("-", 1, Associativity::Right));
+    assert_eq!(pattern.parse(ParseState::new("10-2-3"), atom, eval).unwrap(), 11);
+    assert!(pattern.remove_operator("**", Fixity::Postfix).is_some());
+    assert_eq!(pattern.get_operators().len(), 4);
+}
+
+#[test]
+fn byte_state() {
+    use pex::{ByteResult, ByteState, Endian, ParseState};
+    let state = ByteState::new(&[0x12, 0x34, 0x12, 0x34, 0xFF]);
+    let (state, big) = state.match_u16(Endian::Big).as_result().unwrap();
+    let (state, little) = state.match_u16(Endian::Little).as_result().unwrap();
+    assert_eq!((big, little), (0x1234, 0x3412));
+    assert_eq!(state.match_i8().unwrap(), -1);
+    assert!(state.match_u32(Endian::Big).is_failure());
+    // i64::MIN and u64::MAX
+    let min = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F];
+    assert_eq!(ByteState::new(&min).match_sleb128().unwrap(), i64::MIN);
+    let max = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+    assert_eq!(ByteState::new(&max).match_uleb128().unwrap(), u64::MAX);
+    assert!(ByteState::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02]).match_uleb128().is_failure());
+    // text errors propagate through `?`
+    fn utf8_name(state: ByteState) -> ByteResult<String> {
+        let (state, name) = state.match_length_prefixed(|s| s.match_uleb128())?;
+        let text = core::str::from_utf8(name).unwrap_or_default();
+        let (_, ident) = ParseState::new(text).match_str_if(|c| c.is_alphabetic(), "IDENT")?;
+        state.finish(ident.to_string())
+    }
+    assert_eq!(utf8_name(ByteState::new(b"\x03abc")).unwrap(), "abc");
+    assert!(utf8_name(ByteState::new(b"\x031bc")).is_failure());
+    assert!(utf8_name(ByteState::new(b"\x04abc")).is_failure());
+}
+
+#[test]
+fn streaming_input() {
+    use pex::{helpers::CommentBlock, ParseResult, ParseState, StreamDriver, StreamStatus};
+    fn words(state: ParseState) -> ParseResult<Vec<String>> {
+        state.match_repeats(|s| {
+            let (s, word) = s.match_str_if(|c| c.is_alphabetic(), "WORD")?;
+            let (s, _) = s.match_char(';')?;
+            s.finish(word.to_string())
+        })
+    }
+    let mut driver = StreamDriver::default();
+    driver.push_str("ab;c");
+    assert_eq!(driver.parse(words), StreamStatus::Incomplete);
+    driver.push_str("d;");
+    // the repeat may continue in the next chunk
+    assert_eq!(driver.parse(words), StreamStatus::Incomplete);
+    driver.finish();
+    assert_eq!(driver.parse(words), StreamStatus::Ready(vec!["ab".to_string(), "cd".to_string()]));
+    assert_eq!((driver.get_offset(), driver.get_buffer()), (6, ""));
+    // comment block needs the tail
+    let comment = CommentBlock::new("/*", "*/");
+    let partial = ParseState::new("/* open").with_partial(true);
+    assert!(comment(partial).is_incomplete());
+    assert!(comment(partial.with_partial(false)).is_failure());
+    assert!(!comment(partial.with_partial(false)).is_incomplete());
+    // the trivia cut off at the end of a chunk is not skipped as nothing
+    assert_eq!(partial.skip(|s| comment(s)).start_offset, 0);
+    assert!(partial.try_skip(|s| comment(s)).is_incomplete());
+    // choice stops at the incomplete branch
+    let state = ParseState::new("ab").with_partial(true);
+    let result = state.begin_choice().or_else(|s| s.match_str("abc")).or_else(|s| s.match_str("a")).end_choice();
+    assert!(result.is_incomplete());
+    // real mismatches still fail
+    let mut driver = StreamDriver::default();
+    driver.push_str("1;");
+    assert!(matches!(driver.parse(|s| s.match_str("a").map_inner(|_| ())), StreamStatus::Failed(_)));
+}
+
+#[test]
+fn incremental_reparse() {
+    use core::ops::Range;
+    use pex::{IncrementalParser, ParseResult, ParseState, PexIncremental, PexNode, TextEdit};
+    // item = "if" !ALNUM | WORD | "(" item* ")"
+    #[derive(Clone, Debug, PartialEq)]
+    enum Item {
+        Keyword(Range<usize>),
+        Word(String, Range<usize>),
+        Group(Vec<Item>, Range<usize>),
+    }
+    impl PexNode for Item {
+        fn parse(input: ParseState) -> ParseResult<Self> {
+            input.reuse("item", |s| {
+                let s = s.skip(|s| s.match_str_if(|c| c == ' ', "SPACE"));
+                let start = s.start_offset;
+                s.begin_choice()
+                    .or_else(|s| {
+                        let (s, _) = s.match_str("if")?;
+                        let (s, _) = s.match_negative(|s| s.match_char_if(|c| c.is_alphanumeric(), "ALNUM"), "ALNUM")?;
+                        s.finish(Item::Keyword(start..s.start_offset))
+                    })
+                    .or_else(|s| {
+                        let (s, word) = s.match_str_if(|c| c.is_alphanumeric(), "WORD")?;
+                        s.finish(Item::Word(word.to_string(), start..s.start_offset))
+                    })
+                    .or_else(|s| {
+                        let (s, _) = s.match_char('(')?;
+                        let (s, items) = s.match_repeats(Item::parse)?;
+                        let s = s.skip(|s| s.match_str_if(|c| c == ' ', "SPACE"));
+                        let (s, _) = s.match_char(')')?;
+                        s.finish(Item::Group(items, start..s.start_offset))
+                    })
+                    .end_choice()
+            })
+        }
+        fn get_range(&self) -> Range<usize> {
+            match self {
+                Item::Keyword(r) | Item::Word(_, r) | Item::Group(_, r) => r.clone(),
+            }
+        }
+    }
+    impl PexIncremental for Item {
+        fn shift_range(&mut self, delta: isize) {
+            let shift = |r: &mut Range<usize>| *r = r.start.wrapping_add_signed(delta)..r.end.wrapping_add_signed(delta);
+            match self {
+                Item::Keyword(r) | Item::Word(_, r) => shift(r),
+                Item::Group(items, r) => {
+                    shift(r);
+                    items.iter_mut().for_each(|i| i.shift_range(delta));
+                }
+            }
+        }
+    }
+    fn file(state: ParseState) -> ParseResult<Vec<Item>> {
+        let (state, items) = state.match_repeats(Item::parse)?;
+        let state = state.skip(|s| s.match_str_if(|c| c == ' ', "SPACE"));
+        let (state, _) = state.match_eof()?;
+        state.finish(items)
+    }
+    let mut parser = IncrementalParser::new("if (a (b if) c) (d e)").with_verify(true);
+    parser.parse(file).unwrap();
+    // the keyword depends on the next character
+    parser.edit(TextEdit::new(2..2, "f"));
+    assert!(matches!(parser.parse(file).unwrap()[0], Item::Word(..)));
+    let reused = parser.get_stats().reused;
+    assert!(reused > 0);
+    // a sequence of edits, every parse is verified against a fresh parse
+    let edits = [(4..5, "x y"), (0..0, "("), (1..1, "if"), (10..10, ")"), (5..9, ""), (0..1, ""), (3..3, " ((q))")];
+    for (range, text) in edits {
+        let _ = parser.parse(file);
+        let len = parser.get_text().len();
+        let range = range.start.min(len)..range.end.min(len);
+        parser.edit(TextEdit::new(range, text));
+        let _ = parser.parse(file);
+    }
+    assert!(parser.get_stats().reused > reused);
+}
+
+#[test]
+#[cfg(feature = "trace")]
+fn parse_trace() {
+    use pex::{ParseContext, ParseResult, ParseState, StopBecause};
+    fn key(state: ParseState) -> ParseResult<&str> {
+        state.match_rule("key", |s| {
+            s.begin_choice().or_else(|s| s.match_str("true")).or_else(|s| s.match_str("false")).end_choice()
+        })
+    }
+    let context = ParseContext::default().with_trace();
+    let state = ParseState::new("fals").with_context(&context);
+    assert!(state.match_optional(key).is_success());
+    let trace = context.take_trace().unwrap();
+    assert_eq!(trace.roots.len(), 1);
+    let key = &trace.roots[0].children[0];
+    assert_eq!(key.name, "key");
+    assert!(matches!(key.outcome, Err(StopBecause::MissingString { .. })));
+    let choice = &key.children[0];
+    assert_eq!(choice.name, "begin_choice");
+    assert_eq!(choice.children.iter().map(|c| c.name).collect::<Vec<_>>(), ["or_else", "or_else"]);
+    assert_eq!(
+        trace.to_text(),
+        "match_optional 0..0\n  key 0 failed: Missing string 'false'\n    begin_choice 0 failed: Missing string 'false'\n      or_else 0 failed: Missing string 'true'\n      or_else 0 failed: Missing string 'false'\n"
+    );
+    assert!(trace.to_json().contains(r#""name":"or_else","start":0,"end":null,"#));
+    assert_eq!(trace.to_dot().matches(" -> ").count(), 4);
+    // the trace restarts after taken
+    assert!(context.take_trace().unwrap().roots.is_empty());
+    // a choice dropped before `end_choice` leaves its branches without an open call
+    let state = ParseState::new("true").with_context(&context);
+    let _ = state.begin_choice().or_else(|s| s.match_str("true"));
+    assert!(state.match_fn(|s| s.match_str("true")).is_success());
+    let trace = context.take_trace().unwrap();
+    assert_eq!(
+        trace.to_text(),
+        "or_else 0..4
+match_fn 0..4
+"
+    );
+}
+
+#[test]
+fn runaway_guards() {
+    use pex::{BracketPattern, ParseContext, ParseResult, ParseState, StopBecause};
+    fn nested(state: ParseState) -> ParseResult<usize> {
+        state.match_rule("nested", |s| {
+            s.begin_choice()
+                .or_else(|s| {
+                    let (s, _) = s.match_char('[')?;
+                    let (s, depth) = s.match_fn(nested)?;
+                    let (s, _) = s.match_char(']')?;
+                    s.finish(depth + 1)
+                })
+                .or_else(|s| s.finish(0))
+                .end_choice()
+        })
+    }
+    let input = "[".repeat(100) + &"]".repeat(100);
+    let context = ParseContext::default().with_max_depth(1000);
+    assert_eq!(nested(ParseState::new(&input).with_context(&context)).unwrap(), 100);
+    assert_eq!(context.get_depth(), 0);
+    // the limit is not caught by the choice
+    let context = ParseContext::default().with_max_depth(64);
+    let result = nested(ParseState::new(&input).with_context(&context));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::TooDeep { limit: 64, .. })));
+    // fuel
+    let context = ParseContext::default().with_fuel(10);
+    let result = nested(ParseState::new(&input).with_context(&context));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::OutOfFuel { .. })));
+    assert_eq!(context.get_fuel(), Some(0));
+    let context = ParseContext::default().with_fuel(10_000);
+    assert!(nested(ParseState::new("[[]]").with_context(&context)).is_success());
+    context.cancel();
+    assert!(matches!(
+        nested(ParseState::new("[[]]").with_context(&context)),
+        ParseResult::Stop(StopBecause::OutOfFuel { position: 0 })
+    ));
+    // zero width iterations
+    let state = ParseState::new("aab");
+    let result = state.match_repeats(|s| s.match_optional(|s| s.match_char('a')));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::ZeroProgress { position: 2 })));
+    let result = state.match_repeat_m_n(0, usize::MAX, |s| s.match_optional(|s| s.match_char('a')));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::ZeroProgress { position: 2 })));
+    // a bounded repeat stops at the first empty iteration once the minimum is reached
+    let (rest, items) = state.match_repeat_m_n(0, 5, |s| s.match_optional(|s| s.match_char('a'))).as_result().unwrap();
+    assert_eq!((rest.start_offset, items), (2, vec![Some('a'), Some('a'), None]));
+    let (rest, items) =
+        ParseState::new("b").match_repeat_m_n(2, 3, |s| s.match_optional(|s| s.match_char('a'))).as_result().unwrap();
+    assert_eq!((rest.start_offset, items), (0, vec![None, None]));
+    let result = ParseState::new("b").match_optional(|s| s.match_repeats(|s| s.match_optional(|s| s.match_char('a'))));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::ZeroProgress { position: 0 })));
+    let pattern = BracketPattern::new("(", ")").with_delimiter("");
+    let result = pattern.consume(ParseState::new("(a)"), |s| s.finish(()), |s| s.match_optional(|s| s.match_char('a')));
+    assert!(matches!(result, ParseResult::Stop(StopBecause::ZeroProgress { position: 2 })));
+}
+
+#[test]
+fn case_folding() {
+    use pex::{helpers::CaseFolding, ParseState};
+    // the matched length counts the input, not the target
+    let state = ParseState::new("STRAßE ok");
+    let (rest, text) = state.match_str_casefold("strasse", CaseFolding::Full).as_result().unwrap();
+    assert_eq!(text, "STRAßE");
+    assert_eq!(rest.start_offset, 7);
+    let (rest, text) = ParseState::new("strasse").match_str_casefold("STRAẞE", CaseFolding::Full).as_result().unwrap();
+    assert_eq!((text, rest.start_offset), ("strasse", 7));
+    assert!(ParseState::new("straße").match_str_casefold("STRAẞE", CaseFolding::Simple).is_success());
+    // greek final sigma and iota subscript
+    assert!(ParseState::new("ΟΔΟΣ").match_str_casefold("οδος", CaseFolding::Simple).is_success());
+    assert!(ParseState::new("ὀδός").match_str_casefold("ὈΔΌΣ", CaseFolding::Full).is_success());
+    assert!(ParseState::new("ᾼ").match_str_casefold("ΑΙ", CaseFolding::Full).is_success());
+    assert!(ParseState::new("ᾼ").match_str_casefold("ΑΙ", CaseFolding::Simple).is_failure());
+    // dotted and dotless i
+    assert!(ParseState::new("İSTANBUL").match_str_casefold("istanbul", CaseFolding::Turkic).is_success());
+    assert!(ParseState::new("DİYARBAKIR").match_str_casefold("diyarbakır", CaseFolding::Turkic).is_success());
+    assert!(ParseState::new("DIYARBAKIR").match_str_casefold("diyarbakir", CaseFolding::Turkic).is_failure());
+    assert!(ParseState::new("İ").match_str_casefold("i\u{307}", CaseFolding::Full).is_success());
+    // partial input
+    assert!(ParseState::new("STRA").with_partial(true).match_str_casefold("straße", CaseFolding::Full).is_incomplete());
+    assert!(ParseState::new("STRO").with_partial(true).match_str_casefold("straße", CaseFolding::Full).is_failure());
+    assert!(!ParseState::new("STRO").with_partial(true).match_str_casefold("straße", CaseFolding::Full).is_incomplete());
+}
+
+#[test]
+fn keyword_set() {
+    use pex::{
+        helpers::{Keyword, KeywordSet, XID_CONTINUE, XID_START},
+        ParseContext, ParseResult, ParseState, StopBecause,
+    };
+    const KEYWORDS: KeywordSet = KeywordSet::new(&["let", "if", "match"]).with_soft(&["union", "async"]);
+    assert!(XID_START.contains('é') && XID_START.contains('变') && !XID_START.contains('1'));
+    assert!(XID_CONTINUE.contains('1') && XID_CONTINUE.contains('\u{301}') && !XID_CONTINUE.contains('-'));
+    // boundaries are unicode aware
+    assert!(KEYWORDS(ParseState::new("letté")).is_failure());
+    assert!(KEYWORDS(ParseState::new("let变量")).is_failure());
+    assert_eq!(KEYWORDS(ParseState::new("let-")).unwrap(), Keyword { text: "let", soft: false });
+    assert_eq!(KEYWORDS.match_identifier(ParseState::new("变量 = 1")).unwrap(), "变量");
+    assert_eq!(KEYWORDS.match_identifier(ParseState::new("_if")).unwrap(), "_if");
+    assert!(KEYWORDS.match_identifier(ParseState::new("1x")).is_failure());
+    // the word may continue in the next chunk
+    assert!(KEYWORDS(ParseState::new("le").with_partial(true)).is_incomplete());
+    assert!(KEYWORDS(ParseState::new("let").with_partial(true)).is_incomplete());
+    assert!(KEYWORDS(ParseState::new("let ").with_partial(true)).is_success());
+    // soft keywords are identifiers out of their context
+    fn binding(state: ParseState) -> ParseResult<(bool, &str)> {
+        let (state, _) = state.match_keyword("let")?;
+        let state = state.skip(|s| s.match_char(' '));
+        let (state, is_async) = state.match_optional(|s| {
+            let (s, k) = KEYWORDS(s)?;
+            match k.text {
+                "async" => s.match_char(' ').map_value(()),
+                _ => StopBecause::missing_string("async", s.start_offset)?,
+            }
+        })?;
+        let (state, name) = KEYWORDS.match_identifier(state)?;
+        state.finish((is_async.is_some(), name))
+    }
+    assert_eq!(binding(ParseState::new("let async union")).unwrap(), (true, "union"));
+    assert_eq!(binding(ParseState::new("let async")).unwrap(), (false, "async"));
+    let context = ParseContext::default();
+    let error = binding(ParseState::new("let match").with_context(&context)).as_result().unwrap_err();
+    assert_eq!(error, StopBecause::ReservedKeyword { keyword: "match", position: 4 });
+    assert_eq!(error.range(), 4..9);
+    assert_eq!(error.to_string(), "Reserved keyword `match`");
+    let error = KEYWORDS.match_identifier(ParseState::new("match").with_context(&context)).as_result().unwrap_err();
+    context.clear_furthest_failure();
+    assert_eq!(context.merge_error(error).to_string(), "expected not keyword `match` at 0");
+    // identifiers from other parsers
+    let (_, word) = ParseState::new("if").match_str_if(|c| c.is_alphabetic(), "WORD").as_result().unwrap();
+    assert!(KEYWORDS.check_identifier(word, 0).is_err());
+    assert!(KEYWORDS.is_reserved("if") && !KEYWORDS.is_reserved("union"));
+}
+
+#[test]
+fn literal_set() {
+    use pex::{LiteralSet, ParseContext, ParseResult, ParseState, StopBecause};
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Token {
+        Less,
+        LessEqual,
+        Shift,
+        ShiftAssign,
+        Arrow,
+        Spaceship,
+    }
+    let tokens = LiteralSet::new([
+        ("<", Token::Less),
+        ("<=", Token::LessEqual),
+        ("<<", Token::Shift),
+        ("<<=", Token::ShiftAssign),
+        ("<=>", Token::Spaceship),
+    ])
+    .with_literal("→", Token::Arrow);
+    let all = |text: &str| -> Vec<Token> {
+        let mut state = ParseState::new(text);
+        let mut out = vec![];
+        while !state.is_empty() {
+            let (next, (_, token)) = tokens.parse(state).as_result().unwrap();
+            out.push(*token);
+            state = next;
+        }
+        out
+    };
+    // the order of literals does not matter
+    assert_eq!(all("<<=<=><<<→"), [Token::ShiftAssign, Token::Spaceship, Token::Shift, Token::Less, Token::Arrow]);
+    assert_eq!(all("<=<"), [Token::LessEqual, Token::Less]);
+    // the matched text keeps its offset
+    let (_, (text, _)) = tokens.parse(ParseState::new("<<=").with_start_offset(10)).as_result().unwrap();
+    assert_eq!(text.as_range(), 10..13);
+    // partial input
+    assert!(tokens.parse(ParseState::new("<<").with_partial(true)).is_incomplete());
+    assert!(tokens.parse(ParseState::new("<<a").with_partial(true)).is_success());
+    assert!(tokens.parse(ParseState::new("→").with_partial(true)).is_success());
+    // every literal is expected
+    let context = ParseContext::default();
+    let state = ParseState::new("x").with_context(&context);
+    let result: ParseResult<&str> = state
+        .begin_choice()
+        .or_else(|s| tokens.parse(s).map_value("op"))
+        .or_else(|s| s.match_str("x=").map_value("x="))
+        .end_choice();
+    assert!(result.is_failure());
+    assert_eq!(
+        context.get_furthest_failure().unwrap().to_string(),
+        "expected one of `<`, `<=`, `<<`, `<<=`, `<=>`, `→`, `x=` at 0"
+    );
+    // without a context the error still names every literal
+    assert_eq!(
+        tokens.parse(ParseState::new("x")).as_result().unwrap_err(),
+        StopBecause::MustBe { message: "one of `<`, `<=`, `<<`, `<<=`, `<=>`, `→`", position: 0 }
+    );
+    // update and lookup
+    let mut tokens = tokens;
+    assert_eq!(tokens.insert("<", Token::Arrow), Some(Token::Less));
+    assert_eq!(tokens.get_value("<"), Some(&Token::Arrow));
+    assert_eq!(tokens.get_literals().count(), 6);
+    assert!(LiteralSet::<()>::default().parse(ParseState::new("a")).is_failure());
+}
+
+#[test]
+fn separated_pattern() {
+    use pex::{helpers::decimal_string, ParseResult, ParseState, SeparatedPattern, StopBecause};
+    fn spaces(state: ParseState) -> ParseResult<&str> {
+        state.match_str_if(|c| c == ' ', "SPACE")
+    }
+    fn word(state: ParseState) -> ParseResult<&str> {
+        state.match_str_if(|c| c.is_ascii_alphabetic(), "WORD")
+    }
+    // newline separated statements, the trivia does not eat newlines
+    let lines = SeparatedPattern::new("\n");
+    let (state, list) = lines.consume(ParseState::new("let  \nx \ny\n\nz"), spaces, word).as_result().unwrap();
+    assert_eq!(list.items, ["let", "x", "y"]);
+    assert_eq!(list.separators.iter().map(|s| s.start_offset()).collect::<Vec<_>>(), [5, 8, 10]);
+    assert_eq!(state.residual, "\nz");
+    // trailing policies
+    let allow = SeparatedPattern::new(",");
+    let require = allow.with_dangling(true);
+    let forbid = allow.with_dangling(false);
+    let rest = |p: SeparatedPattern, text| {
+        p.consume(ParseState::new(text), spaces, decimal_string)
+            .as_result()
+            .map(|(s, l)| (l.items.len(), l.separators.len(), s.residual))
+    };
+    assert_eq!(rest(allow, "1, 2 ,)"), Ok((2, 2, ")")));
+    assert_eq!(rest(allow, "1, 2)"), Ok((2, 1, ")")));
+    assert_eq!(rest(require, "1, 2 ,)"), Ok((2, 2, ")")));
+    assert!(rest(require, "1, 2)").is_err());
+    assert_eq!(rest(forbid, "1, 2 ,)"), Ok((2, 1, " ,)")));
+    assert_eq!(rest(forbid, "1, 2)"), Ok((2, 1, ")")));
+    assert_eq!(rest(allow, ")"), Ok((0, 0, ")")));
+    // the trivia before the first item is skipped, but kept if the list is empty
+    assert_eq!(rest(allow, "  1, 2)"), Ok((2, 1, ")")));
+    assert_eq!(rest(allow, "  )"), Ok((0, 0, "  )")));
+    // counts
+    let pair = allow.with_min(2).with_max(2).with_dangling(false);
+    assert_eq!(rest(pair, "1,2,3"), Ok((2, 1, ",3")));
+    assert_eq!(rest(pair, "1,"), Err(StopBecause::ExpectRepeats { min: 2, current: 1, position: 0 }));
+    assert!(rest(allow.with_min(1), "x").is_err());
+    // fatal errors pass through
+    let partial = allow.consume(ParseState::new("23, 2").with_partial(true), spaces, |s| s.match_str("23"));
+    assert!(partial.is_incomplete());
+}
+
+#[test]
+fn bracket_lossless() {
+    use pex::{
+        helpers::{decimal_string, CommentLine},
+        BracketPair, BracketPattern, ParseContext, ParseResult, ParseState, StringView,
+    };
+    fn trivia(state: ParseState) -> ParseResult<()> {
+        let (state, _) = state.match_repeats(|s| {
+            s.begin_choice()
+                .or_else(|s| s.match_str_if(|c| c.is_whitespace(), "SPACE"))
+                .or_else(|s| CommentLine::new("#")(s).map_value(""))
+                .end_choice()
+        })?;
+        state.finish(())
+    }
+    fn number(state: ParseState) -> ParseResult<StringView> {
+        let (rest, text) = decimal_string(state)?;
+        rest.finish(StringView::new(text, state.start_offset))
+    }
+    fn print(pair: &BracketPair<StringView>) -> String {
+        let mut views = vec![pair.lhs, pair.rhs];
+        views.extend(pair.body.iter().chain(&pair.delimiters).chain(&pair.trivia).chain(&pair.skipped).copied());
+        views.sort_by_key(|v| v.start_offset());
+        views.iter().map(|v| v.as_ref()).collect()
+    }
+    let pattern = BracketPattern::new("(", ")").with_lossless(true);
+    for text in ["()", "( )", "(1)", "( 1 , 2 ,  )", "(1 # one\n, 2#two\n)", "(1,2)"] {
+        let pair = pattern.consume(ParseState::new(text), trivia, number).unwrap();
+        assert_eq!(print(&pair), text);
+    }
+    // nothing is kept if not lossless
+    let pair = BracketPattern::new("(", ")").consume(ParseState::new("( 1 , 2 )"), trivia, number).unwrap();
+    assert_eq!((pair.body.len(), pair.delimiters.len(), pair.trivia.len()), (2, 0, 0));
+    // recovery
+    let pattern = pattern.with_recover(true);
+    let context = ParseContext::default().with_max_errors(8);
+    let state = ParseState::new("(1, f(2, 3) x, , 4 5, 6)").with_context(&context);
+    let (pair, errors) = pattern.consume(state, trivia, number).recovered(&context);
+    let pair = pair.unwrap();
+    assert_eq!(pair.body.iter().map(|v| v.as_ref()).collect::<Vec<_>>(), ["1", "6"]);
+    assert_eq!(errors.iter().map(|e| e.range().start).collect::<Vec<_>>(), [4, 15, 19]);
+    assert_eq!(pair.skipped.iter().map(|v| v.as_ref()).collect::<Vec<_>>(), ["f(2, 3) x", "4 5"]);
+    assert!(pair.trivia.iter().all(|v| v.as_ref().trim().is_empty()));
+    assert_eq!(print(&pair), "(1, f(2, 3) x, , 4 5, 6)");
+    // not in recovery mode
+    assert!(pattern.consume(ParseState::new("(1, x, 2)"), trivia, number).is_failure());
+}
+
+#[test]
+fn concrete_syntax_tree() {
+    use pex::{
+        cst::{GreenElement, NodeBuilder, SyntaxElement, SyntaxKind, SyntaxNode},
+        helpers::CommentLine,
+        ParseResult, ParseState,
+    };
+    const LIST: SyntaxKind = SyntaxKind(0);
+    const CALL: SyntaxKind = SyntaxKind(1);
+    const NAME: SyntaxKind = SyntaxKind(2);
+    const PUNCT: SyntaxKind = SyntaxKind(3);
+    const TRIVIA: SyntaxKind = SyntaxKind(4);
+    fn trivia<'i>(cst: &NodeBuilder, state: ParseState<'i>) -> ParseState<'i> {
+        cst.skip_trivia(TRIVIA, state, |s| {
+            s.match_repeats(|s| {
+                s.begin_choice()
+                    .or_else(|s| s.match_str_if(|c| c.is_whitespace(), "SPACE"))
+                    .or_else(|s| CommentLine::new("#")(s).map_value(""))
+                    .end_choice()
+            })
+        })
+    }
+    // call = name ( ) | name
+    fn item<'i>(cst: &NodeBuilder, state: ParseState<'i>) -> ParseResult<'i, ()> {
+        let name = |s: ParseState<'i>| cst.parse_token(NAME, s, |s| s.match_str_if(|c| c.is_ascii_alphabetic(), "NAME"));
+        state
+            .begin_choice()
+            .or_else(|s| {
+                cst.parse_node(CALL, s, |s| {
+                    let (s, _) = name(s)?;
+                    let (s, _) = cst.parse_token(PUNCT, s, |s| s.match_str("("))?;
+                    let (s, _) = cst.parse_token(PUNCT, s, |s| s.match_str(")"))?;
+                    s.finish(())
+                })
+            })
+            .or_else(|s| name(s).map_value(()))
+            .end_choice()
+    }
+    fn list<'i>(cst: &NodeBuilder, state: ParseState<'i>) -> ParseResult<'i, ()> {
+        cst.parse_node(LIST, state, |s| {
+            let (s, _) = cst.parse_token(PUNCT, s, |s| s.match_str("["))?;
+            let mut s = trivia(cst, s);
+            while let ParseResult::Pending(next, _) = item(cst, s) {
+                s = trivia(cst, next);
+                match cst.parse_token(PUNCT, s, |s| s.match_str(",")) {
+                    ParseResult::Pending(next, _) => s = trivia(cst, next),
+                    ParseResult::Stop(_) => break,
+                }
+            }
+            let (s, _) = cst.parse_token(PUNCT, s, |s| s.match_str("]"))?;
+            s.finish(())
+        })
+    }
+    let text = "[ a, f() # call\n, a, f() ]";
+    let cst = NodeBuilder::default();
+    list(&cst, ParseState::new(text)).unwrap();
+    let green = cst.finish().unwrap();
+    assert_eq!(green.to_string(), text);
+    assert_eq!(green.get_length(), text.len());
+    // equal tokens and small nodes are shared
+    let calls: Vec<_> = green.get_children().iter().filter(|c| c.get_kind() == CALL).collect();
+    match (calls[0], calls[1]) {
+        (GreenElement::Node(lhs), GreenElement::Node(rhs)) => assert!(std::rc::Rc::ptr_eq(lhs, rhs)),
+        _ => unreachable!(),
+    }
+    // the red tree knows the offsets and parents
+    let root = SyntaxNode::new_root(green);
+    assert_eq!(root.get_range(), 0..text.len());
+    let calls: Vec<_> = root.child_nodes().collect();
+    assert_eq!(calls.iter().map(|c| c.get_range()).collect::<Vec<_>>(), [5..8, 21..24]);
+    assert_eq!(calls[1].get_parent(), Some(&root));
+    let paren = root.token_at_offset(22).unwrap();
+    assert_eq!((paren.get_text(), paren.get_parent(), paren.get_index()), ("(", &calls[1], 1));
+    assert_eq!(paren.get_parent().ancestors().collect::<Vec<_>>(), std::slice::from_ref(&root));
+    let comment = root.token_at_offset(10).unwrap();
+    assert_eq!((comment.get_text(), comment.is_trivia()), (" # call\n", true));
+    let tokens = root.tokens();
+    assert_eq!(tokens.iter().map(|t| t.get_text()).collect::<String>(), text);
+    assert_eq!(tokens.iter().filter(|t| t.get_kind() == NAME).count(), 4);
+    assert!(matches!(root.get_child(0), Some(SyntaxElement::Token(t)) if t.get_text() == "["));
+    // failed branches leave nothing behind
+    let cst = NodeBuilder::default();
+    assert!(list(&cst, ParseState::new("[a, f(]")).is_failure());
+    assert!(cst.finish().is_none());
+}
+
+#[test]
+fn trailing_whitespace() {
+    use pex::{
+        helpers::{ascii_whitespace, make_from_str, whitespace},
+        ParseState, StopBecause,
+    };
+    // the trivia after the last token reaches the end of input
+    let number = |text: &str| -> Result<u32, StopBecause> {
+        make_from_str(ParseState::new(text), |state| {
+            let (state, digits) = state.skip(whitespace).match_str_if(|c| c.is_ascii_digit(), "DIGIT")?;
+            state.skip(whitespace).finish(digits.parse().unwrap())
+        })
+    };
+    assert_eq!(number(" 42 \n"), Ok(42));
+    assert_eq!(number("42"), Ok(42));
+    assert!(number("42 x").is_err());
+    let state = ParseState::new("a \t\n").advance(1);
+    assert!(whitespace(state).is_success());
+    assert!(ascii_whitespace(state).is_success());
+    assert!(state.skip(whitespace).match_eof().is_success());
+}
+
+#[test]
+fn diagnostic_renderer() {
+    use pex::{Diagnostic, DiagnosticRenderer, FurthestFailure, StopBecause};
+    let text = format!("fn main() {{\n\tlet a = 1;\n{}call(a, b{}\n}}", "x".repeat(100), " ".repeat(60));
+    let renderer = DiagnosticRenderer::new(&text, "main.rs").with_max_width(40);
+    let error = StopBecause::MissingString { message: ")", position: 24 + 109 };
+    let diagnostic = Diagnostic::from(error).with_label("expected `)`").with_secondary(10..11, "opened here");
+    assert_eq!(
+        renderer.render(&diagnostic),
+        "\
+error: Missing string ')'
+ --> main.rs:3:110
+  |
+1 | fn main() {
+  |           - opened here
+...
+3 | ...xxxxxxxcall(a, b                  ...
+  |                    ^ expected `)`
+"
+    );
+    // the tab is shown as 4 spaces
+    let diagnostic = Diagnostic::new("unused variable", 17..18).with_note("prefix it with `_`");
+    assert_eq!(
+        renderer.render(&diagnostic),
+        "error: unused variable\n --> main.rs:2:6\n  |\n2 |     let a = 1;\n  |         ^\n  |\n  = note: prefix it with `_`\n"
+    );
+    let mut failure = FurthestFailure::default();
+    failure.merge(StopBecause::MissingString { message: ")", position: text.len() });
+    failure.merge(StopBecause::MustBe { message: "IDENT", position: text.len() });
+    let reports = renderer.with_color(true).render_all(&[Diagnostic::from(&failure), diagnostic]);
+    assert!(reports.starts_with("\x1b[1;31merror\x1b[0m\x1b[1m: expected one of `)`, IDENT\x1b[0m\n"));
+    assert!(reports.contains("\x1b[1;34m4\x1b[0m \x1b[1;34m|\x1b[0m }\n  \x1b[1;34m|\x1b[0m  \x1b[1;31m^\x1b[0m\n\n"));
+}
+
+#[test]
+fn did_you_mean() {
+    use pex::{Diagnostic, DiagnosticRenderer, ParseContext, ParseResult, ParseState, Suggester};
+    // `function` | `return` | `while`
+    fn keyword(state: ParseState) -> ParseResult<&str> {
+        state
+            .begin_choice()
+            .or_else(|s| s.match_str("function"))
+            .or_else(|s| s.match_str("return"))
+            .or_else(|s| s.match_str("while"))
+            .end_choice()
+    }
+    let context = ParseContext::default();
+    let input = "retrun x";
+    let error = match keyword(ParseState::new(input).with_context(&context)) {
+        ParseResult::Pending(..) => unreachable!(),
+        ParseResult::Stop(e) => e,
+    };
+    let suggester = Suggester::default();
+    let failure = context.merge_error(error).with_suggestions(input, &suggester);
+    assert_eq!(failure.suggestions.len(), 1);
+    assert_eq!(failure.suggestions[0].text, "return");
+    assert_eq!(failure.suggestions[0].distance, 1);
+    let report = DiagnosticRenderer::new(input, "main.js").render(&Diagnostic::from(&failure));
+    assert!(report.ends_with("1 | retrun x\n  | ^^^^^^ did you mean `return`?\n"), "{}", report);
+    // the known identifiers, ranked by distance and limited
+    let suggester = Suggester::default().with_candidates(["length", "lens", "left", "lenght"]).with_limit(2);
+    assert_eq!(suggester.suggest_word("lengt"), vec!["length", "lenght"]);
+    assert_eq!(suggester.clone().with_max_distance(0).suggest_word("lengt"), Vec::<String>::new());
+    let failure = pex::FurthestFailure { position: 4, expected: vec![], suggestions: vec![] };
+    let failure = failure.with_suggestions("let lenth = 1", &suggester);
+    assert_eq!(failure.suggestions[0].range, 4..9);
+    // no suggestion for a symbol, it would only repeat the expected text
+    let context = ParseContext::default();
+    let error = match ParseState::new("-> x").with_context(&context).match_str("=>") {
+        ParseResult::Pending(..) => unreachable!(),
+        ParseResult::Stop(e) => e,
+    };
+    let failure = context.merge_error(error).with_suggestions("-> x", &Suggester::default());
+    assert!(failure.suggestions.is_empty());
+    assert_eq!(failure.to_string(), "expected `=>` at 0");
+}
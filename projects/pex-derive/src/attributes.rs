@@ -0,0 +1,126 @@
+use syn::{Attribute, Expr, Field, GenericArgument, LitInt, LitStr, PathArguments, Result, Type};
+
+/// The attributes on the struct or enum
+#[derive(Default)]
+pub struct ContainerAttributes {
+    /// The parser to skip between fields, such as whitespace and comments
+    pub trivia: Option<Expr>,
+    /// Implement `PexRule` to export the grammar
+    pub export: bool,
+}
+
+/// The attributes on a field
+#[derive(Default)]
+pub struct FieldAttributes {
+    /// Match the literal, and store the range
+    pub literal: Option<LitStr>,
+    /// The parser of the field or of the items
+    pub parser: Option<Expr>,
+    /// Parse `Option<T>`
+    pub optional: bool,
+    /// Parse `Vec<T>`
+    pub repeat: bool,
+    /// Parse `Vec<T>` separated by the delimiter
+    pub separator: Option<LitStr>,
+    /// The minimum count of repeated items
+    pub min: Option<LitInt>,
+    /// Capture the range of the whole node instead of parsing
+    pub span: bool,
+}
+
+impl ContainerAttributes {
+    pub fn new(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("pex")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("trivia") {
+                    out.trivia = Some(meta.value()?.parse()?);
+                    Ok(())
+                }
+                else if meta.path.is_ident("export") {
+                    out.export = true;
+                    Ok(())
+                }
+                else {
+                    Err(meta.error("unknown pex attribute, expected `trivia` or `export`"))
+                }
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+impl FieldAttributes {
+    pub fn new(field: &Field) -> Result<Self> {
+        let mut out = Self::default();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("pex")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("literal") {
+                    out.literal = Some(meta.value()?.parse()?);
+                }
+                else if meta.path.is_ident("parser") {
+                    out.parser = Some(meta.value()?.parse()?);
+                }
+                else if meta.path.is_ident("optional") {
+                    out.optional = true;
+                }
+                else if meta.path.is_ident("repeat") {
+                    out.repeat = true;
+                }
+                else if meta.path.is_ident("separator") {
+                    out.separator = Some(meta.value()?.parse()?);
+                }
+                else if meta.path.is_ident("min") {
+                    out.min = Some(meta.value()?.parse()?);
+                }
+                else if meta.path.is_ident("span") {
+                    out.span = true;
+                }
+                else {
+                    return Err(meta.error(
+                        "unknown pex attribute, expected one of `literal`, `parser`, `optional`, `repeat`, `separator`, `min`, `span`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        out.check(field)?;
+        Ok(out)
+    }
+    fn check(&self, field: &Field) -> Result<()> {
+        let modes = [self.literal.is_some(), self.optional, self.repeat, self.separator.is_some(), self.span];
+        if modes.iter().filter(|m| **m).count() > 1 {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`literal`, `optional`, `repeat`, `separator` and `span` can not be used together",
+            ));
+        }
+        if self.min.is_some() && !self.repeat && self.separator.is_none() {
+            return Err(syn::Error::new_spanned(field, "`min` only works with `repeat` or `separator`"));
+        }
+        if self.parser.is_some() && (self.literal.is_some() || self.span) {
+            return Err(syn::Error::new_spanned(field, "`parser` does not work with `literal` or `span`"));
+        }
+        Ok(())
+    }
+}
+
+/// Get `T` from `Option<T>` or `Vec<T>`
+pub fn inner_type<'t>(ty: &'t Type, wrapper: &str) -> Option<&'t Type> {
+    let Type::Path(path) = ty
+    else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments
+    else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
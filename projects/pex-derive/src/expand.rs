@@ -0,0 +1,335 @@
+use crate::attributes::{inner_type, ContainerAttributes, FieldAttributes};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+use syn::{Data, DeriveInput, Expr, Fields, Ident, Member, Result, Type};
+
+struct FieldInfo {
+    member: Member,
+    bind: Ident,
+    parser: Option<TokenStream>,
+    /// The node type parsed by `PexNode::parse`
+    item: Option<Type>,
+    attributes: FieldAttributes,
+}
+
+pub fn derive_pex_node(input: &DeriveInput) -> Result<TokenStream> {
+    let container = ContainerAttributes::new(&input.attrs)?;
+    let trivia = container.trivia.as_ref();
+    let mut rule = Rule::default();
+    let (parse, range) = match &input.data {
+        Data::Struct(data) => {
+            let fields = collect_fields(&data.fields)?;
+            let sequence = sequence(&fields, quote!(Self), trivia);
+            let (pattern, range) = range_of(&fields, quote!(Self), &input.ident)?;
+            if container.export {
+                rule.sequence(&fields, trivia.is_some())?;
+            }
+            (sequence, quote!(match self { #pattern => #range }))
+        }
+        Data::Enum(data) => {
+            let mut branches = Vec::with_capacity(data.variants.len());
+            let mut arms = Vec::with_capacity(data.variants.len());
+            for (index, variant) in data.variants.iter().enumerate() {
+                let fields = collect_fields(&variant.fields)?;
+                let ident = &variant.ident;
+                let sequence = sequence(&fields, quote!(Self::#ident), trivia);
+                branches.push(quote!(.or_else(|input| { #sequence })));
+                let (pattern, range) = range_of(&fields, quote!(Self::#ident), ident)?;
+                arms.push(quote!(#pattern => #range,));
+                if container.export {
+                    if index != 0 {
+                        rule.text(" | ");
+                    }
+                    rule.sequence(&fields, trivia.is_some())?;
+                }
+            }
+            let parse = quote!(input.begin_choice() #(#branches)* .end_choice());
+            (parse, quote!(match self { #(#arms)* }))
+        }
+        Data::Union(_) => return Err(syn::Error::new_spanned(&input.ident, "unions can not derive `PexNode`")),
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let from_str = match trivia {
+        // skip the trivia at the start and end
+        Some(trivia) => quote! {
+            ::pex::helpers::make_from_str(::pex::ParseState::new(s), |input| {
+                let (state, _) = input.try_skip(#trivia)?;
+                let (state, value) = state.match_fn(<Self as ::pex::PexNode>::parse)?;
+                state.try_skip(#trivia).map_value(value)
+            })
+        },
+        None => quote!(::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)),
+    };
+    let export = container.export.then(|| {
+        let rule_name = name.to_string();
+        let Rule { parts, children } = &rule;
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::pex::PexRule for #name #ty_generics #where_clause {
+                const NAME: &'static str = #rule_name;
+                fn register(registry: &mut ::pex::RuleRegistry) {
+                    if registry.insert(Self::NAME, &[#(#parts),*]) {
+                        #(<#children as ::pex::PexRule>::register(registry);)*
+                    }
+                }
+            }
+        }
+    });
+    Ok(quote! {
+        #export
+        #[automatically_derived]
+        impl #impl_generics ::pex::PexNode for #name #ty_generics #where_clause {
+            fn parse(input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+                #parse
+            }
+            fn get_range(&self) -> ::core::ops::Range<usize> {
+                #range
+            }
+        }
+        #[automatically_derived]
+        impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::pex::StopBecause;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                #from_str
+            }
+        }
+    })
+}
+
+fn collect_fields(fields: &Fields) -> Result<Vec<FieldInfo>> {
+    let mut out = Vec::with_capacity(fields.len());
+    for (index, field) in fields.iter().enumerate() {
+        let attributes = FieldAttributes::new(field)?;
+        let (member, bind) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), format_ident!("_pex_{}", ident)),
+            None => (Member::Unnamed(index.into()), format_ident!("_pex_{}", index)),
+        };
+        let mut item = None;
+        let parser = match &attributes.parser {
+            Some(parser) => Some(parser.to_token_stream()),
+            None if attributes.literal.is_some() || attributes.span => None,
+            None => {
+                let wrapper = match () {
+                    _ if attributes.optional => Some("Option"),
+                    _ if attributes.repeat || attributes.separator.is_some() => Some("Vec"),
+                    _ => None,
+                };
+                let ty = match wrapper {
+                    Some(wrapper) => inner_type(&field.ty, wrapper).ok_or_else(|| {
+                        syn::Error::new_spanned(&field.ty, format!("expected `{wrapper}<T>`, or set the `parser` of `T`"))
+                    })?,
+                    None => &field.ty,
+                };
+                item = Some(ty.clone());
+                Some(quote!(<#ty as ::pex::PexNode>::parse))
+            }
+        };
+        out.push(FieldInfo { member, bind, parser, item, attributes });
+    }
+    Ok(out)
+}
+
+/// Parse the fields in order, skip the trivia between them
+fn sequence(fields: &[FieldInfo], path: TokenStream, trivia: Option<&Expr>) -> TokenStream {
+    let has_span = fields.iter().any(|f| f.attributes.span);
+    let parsed: Vec<_> = fields.iter().filter(|f| !f.attributes.span).collect();
+    let mut steps = Vec::with_capacity(parsed.len() * 2 + 1);
+    if has_span {
+        // the span ends at the last consumed field, not the trivia after it
+        match parsed.is_empty() {
+            true => steps.push(quote!(let end = input.start_offset;)),
+            false => steps.push(quote!(let mut end = input.start_offset;)),
+        }
+    }
+    for (index, field) in parsed.iter().enumerate() {
+        if let (Some(trivia), true) = (trivia, index > 0) {
+            steps.push(quote!(let (state, _) = state.try_skip(#trivia)?;));
+        }
+        let bind = &field.bind;
+        let expression = field_expression(field, trivia);
+        match has_span {
+            true => steps.push(quote! {
+                let start = state.start_offset;
+                let (state, #bind) = #expression?;
+                if state.start_offset > start {
+                    end = state.start_offset;
+                }
+            }),
+            false => steps.push(quote!(let (state, #bind) = #expression?;)),
+        }
+    }
+    let inits = fields.iter().map(|field| {
+        let member = &field.member;
+        let bind = &field.bind;
+        match field.attributes.span {
+            true => quote!(#member: input.start_offset..end),
+            false => quote!(#member: #bind),
+        }
+    });
+    quote! {
+        let state = input;
+        #(#steps)*
+        state.finish(#path { #(#inits),* })
+    }
+}
+
+fn field_expression(field: &FieldInfo, trivia: Option<&Expr>) -> TokenStream {
+    let attributes = &field.attributes;
+    if let Some(literal) = &attributes.literal {
+        return quote! {
+            state.match_fn(|s| {
+                let (next, _) = s.match_str(#literal)?;
+                next.finish(s.start_offset..next.start_offset)
+            })
+        };
+    }
+    let parser = &field.parser;
+    let min = match &attributes.min {
+        Some(min) => min.to_token_stream(),
+        None => quote!(0),
+    };
+    if let Some(separator) = &attributes.separator {
+        let ignore = match trivia {
+            Some(trivia) => trivia.to_token_stream(),
+            None => quote!(|s: ::pex::ParseState| s.finish(())),
+        };
+        quote! {
+            ::pex::SeparatedPattern::new(#separator)
+                .with_min(#min)
+                .consume(state, #ignore, #parser)
+                .map_inner(|list| list.items)
+        }
+    }
+    else if attributes.repeat {
+        let skip = trivia.map(|trivia| quote!(let (s, _) = s.try_skip(#trivia)?;));
+        quote!(state.match_repeat_m_n(#min, usize::MAX, |s| { #skip s.match_fn(#parser) }))
+    }
+    else if attributes.optional {
+        quote!(state.match_optional(#parser))
+    }
+    else {
+        quote!(state.match_fn(#parser))
+    }
+}
+
+/// The range is the span field, or the range of the only field
+fn range_of(fields: &[FieldInfo], path: TokenStream, name: &Ident) -> Result<(TokenStream, TokenStream)> {
+    let parsed: Vec<_> = fields.iter().filter(|f| !f.attributes.span).collect();
+    let (field, range) = match fields.iter().find(|f| f.attributes.span) {
+        Some(span) => (span, quote!(::core::clone::Clone::clone(_pex_range))),
+        None => match parsed.as_slice() {
+            [single] if single.attributes.literal.is_some() => (*single, quote!(::core::clone::Clone::clone(_pex_range))),
+            [single] if !single.attributes.optional && !single.attributes.repeat && single.attributes.separator.is_none() => {
+                (*single, quote!(::pex::PexNode::get_range(_pex_range)))
+            }
+            _ => return Err(syn::Error::new_spanned(name, "add a `#[pex(span)]` field to capture the range")),
+        },
+    };
+    let member = &field.member;
+    Ok((quote!(#path { #member: _pex_range, .. }), range))
+}
+
+/// The rule in the ygg notation, joined from text and the names of the children
+#[derive(Default)]
+struct Rule {
+    parts: Vec<TokenStream>,
+    children: Vec<Type>,
+}
+
+impl Rule {
+    fn text(&mut self, text: &str) {
+        self.parts.push(quote!(#text));
+    }
+    /// The fields in order, `~` skips the trivia between them
+    fn sequence(&mut self, fields: &[FieldInfo], trivia: bool) -> Result<()> {
+        let parsed: Vec<_> = fields.iter().filter(|f| !f.attributes.span).collect();
+        if parsed.is_empty() {
+            self.text("\"\"");
+        }
+        for (index, field) in parsed.into_iter().enumerate() {
+            if index != 0 {
+                self.text(if trivia { " ~ " } else { " " });
+            }
+            self.field(field, trivia)?;
+        }
+        Ok(())
+    }
+    fn field(&mut self, field: &FieldInfo, trivia: bool) -> Result<()> {
+        let attributes = &field.attributes;
+        if let Some(literal) = &attributes.literal {
+            self.text(&format!("{:?}", literal.value()));
+            return Ok(());
+        }
+        let min = match &attributes.min {
+            Some(min) => min.base10_parse::<usize>()?,
+            None => 0,
+        };
+        if let Some(separator) = &attributes.separator {
+            // `item ("," item)* ","?`, the dangling delimiter is allowed
+            let separator = format!("{:?}", separator.value());
+            let repeat = match min {
+                0 | 1 => "*".to_string(),
+                min => format!("{{{},}}", min - 1),
+            };
+            let (next, dangling) = match trivia {
+                true => (format!(" (~ {} ~ ", separator), format!("){} (~ {})?", repeat, separator)),
+                false => (format!(" ({} ", separator), format!("){} {}?", repeat, separator)),
+            };
+            if min == 0 {
+                self.text("(");
+            }
+            self.item(field)?;
+            self.text(&next);
+            self.item(field)?;
+            self.text(&dangling);
+            if min == 0 {
+                self.text(")?");
+            }
+        }
+        else if attributes.repeat {
+            let repeat = match min {
+                0 => "*".to_string(),
+                1 => "+".to_string(),
+                min => format!("{{{},}}", min),
+            };
+            match trivia {
+                true => {
+                    self.text("(~ ");
+                    self.item(field)?;
+                    self.text(&format!("){}", repeat));
+                }
+                false => {
+                    self.item(field)?;
+                    self.text(&repeat);
+                }
+            }
+        }
+        else {
+            self.item(field)?;
+            if attributes.optional {
+                self.text("?");
+            }
+        }
+        Ok(())
+    }
+    /// The name of the node type, or of the parser function
+    fn item(&mut self, field: &FieldInfo) -> Result<()> {
+        match (&field.attributes.parser, &field.item) {
+            (Some(Expr::Path(path)), _) => match path.path.segments.last() {
+                Some(segment) => self.text(&segment.ident.to_string()),
+                None => return Err(syn::Error::new_spanned(path, "`export` needs the `parser` to be a function name")),
+            },
+            (Some(parser), _) => {
+                return Err(syn::Error::new_spanned(parser, "`export` needs the `parser` to be a function name"));
+            }
+            (None, Some(item)) => {
+                self.parts.push(quote!(<#item as ::pex::PexRule>::NAME));
+                self.children.push(item.clone());
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
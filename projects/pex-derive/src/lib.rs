@@ -0,0 +1,23 @@
+#![deny(missing_debug_implementations, missing_copy_implementations)]
+#![warn(missing_docs, rustdoc::missing_crate_level_docs)]
+#![doc = include_str!("../readme.md")]
+#![doc(html_logo_url = "https://avatars.githubusercontent.com/u/91894079")]
+#![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/91894079")]
+
+mod attributes;
+mod expand;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derive `PexNode` and `FromStr`, structs are parsed as sequences and enums as ordered choices.
+///
+/// See the [crate level documentation](crate) for the attributes.
+#[proc_macro_derive(PexNode, attributes(pex))]
+pub fn derive_pex_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand::derive_pex_node(&input) {
+        Ok(output) => output.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,154 @@
+use pex::{
+    helpers::{decimal_string, whitespace},
+    ParseResult, ParseState, PexNode as _, RuleRegistry, StopBecause,
+};
+use pex_derive::PexNode;
+use std::{ops::Range, str::FromStr};
+
+#[test]
+fn ready() {
+    println!("it works!")
+}
+
+fn number(state: ParseState) -> ParseResult<String> {
+    decimal_string(state).map_inner(String::from)
+}
+
+fn identifier(state: ParseState) -> ParseResult<String> {
+    state.match_str_if(|c| c.is_ascii_alphanumeric() || c == '_', "IDENTIFIER").map_inner(String::from)
+}
+
+#[derive(Debug, PexNode)]
+#[pex(export)]
+struct Number {
+    #[pex(parser = number)]
+    text: String,
+    #[pex(span)]
+    span: Range<usize>,
+}
+
+#[derive(Debug, PexNode)]
+#[pex(export)]
+enum Boolean {
+    True(#[pex(literal = "true")] Range<usize>),
+    False(#[pex(literal = "false")] Range<usize>),
+}
+
+#[derive(Debug, PexNode)]
+#[pex(trivia = whitespace, export)]
+enum Value {
+    Boolean(Boolean),
+    Number(Number),
+    Array(Box<Array>),
+    Path {
+        #[pex(parser = identifier)]
+        head: String,
+        #[pex(repeat)]
+        rest: Vec<Segment>,
+        #[pex(span)]
+        span: Range<usize>,
+    },
+}
+
+#[derive(Debug, PexNode)]
+#[pex(trivia = whitespace, export)]
+struct Segment {
+    #[pex(literal = ".")]
+    dot: Range<usize>,
+    #[pex(parser = identifier)]
+    name: String,
+    #[pex(span)]
+    span: Range<usize>,
+}
+
+#[derive(Debug, PexNode)]
+#[pex(trivia = whitespace, export)]
+struct Array {
+    #[pex(literal = "[")]
+    lhs: Range<usize>,
+    #[pex(separator = ",")]
+    items: Vec<Value>,
+    #[pex(literal = "]")]
+    rhs: Range<usize>,
+    #[pex(span)]
+    span: Range<usize>,
+}
+
+#[derive(Debug, PexNode)]
+#[pex(trivia = whitespace, export)]
+struct Binding {
+    #[pex(literal = "let")]
+    keyword: Range<usize>,
+    #[pex(parser = identifier)]
+    name: String,
+    #[pex(optional)]
+    annotation: Option<Annotation>,
+    #[pex(literal = "=")]
+    eq: Range<usize>,
+    #[pex(separator = "|", min = 1)]
+    values: Vec<Value>,
+    #[pex(span)]
+    span: Range<usize>,
+}
+
+#[derive(Debug, PexNode)]
+#[pex(trivia = whitespace, export)]
+struct Annotation(#[pex(literal = ":")] Range<usize>, #[pex(parser = identifier)] String, #[pex(span)] Range<usize>);
+
+#[test]
+fn sequence_and_choice() {
+    let value = Value::from_str("[1, true , a.b .c, [ ], 2.5,]").unwrap();
+    let Value::Array(array) = &value
+    else {
+        panic!("expected array, got {value:?}")
+    };
+    assert_eq!((array.lhs.clone(), array.rhs.clone(), value.get_range()), (0..1, 28..29, 0..29));
+    match array.items.as_slice() {
+        [Value::Number(one), Value::Boolean(Boolean::True(t)), Value::Path { head, rest, span }, Value::Array(empty), Value::Number(two)] =>
+        {
+            assert_eq!((one.text.as_str(), one.get_range()), ("1", 1..2));
+            assert_eq!(t.clone(), 4..8);
+            assert_eq!(head, "a");
+            assert_eq!(rest.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["b", "c"]);
+            assert_eq!((rest[1].dot.clone(), rest[1].get_range()), (15..16, 15..17));
+            assert_eq!(span.clone(), 11..17);
+            assert!(empty.items.is_empty());
+            assert_eq!((two.text.as_str(), two.span.clone()), ("2.5", 24..27));
+        }
+        items => panic!("unexpected items {items:?}"),
+    }
+    // the trivia around the text is skipped, the span does not cover the trailing trivia
+    let binding = Binding::from_str("  let x : int = 1 | [false]  ").unwrap();
+    assert_eq!((binding.keyword.clone(), binding.name.as_str(), binding.eq.clone()), (2..5, "x", 14..15));
+    assert_eq!(binding.get_range(), 2..27);
+    let annotation = binding.annotation.unwrap();
+    assert_eq!((annotation.0.clone(), annotation.1.as_str(), annotation.get_range()), (8..9, "int", 8..13));
+    assert_eq!(binding.values.len(), 2);
+    assert!(Binding::from_str("let x = 1").unwrap().annotation.is_none());
+}
+
+#[test]
+fn derive_errors() {
+    // at least one value
+    assert_eq!(Binding::from_str("let x =").unwrap_err().range().start, 7);
+    assert!(matches!(Value::from_str("[1 2]"), Err(StopBecause::MissingString { message: "]", position: 3 })));
+    assert!(matches!(Boolean::from_str("true "), Err(StopBecause::ExpectEOF { position: 4 })));
+    assert!(Boolean::parse(ParseState::new("falsy")).is_failure());
+}
+
+#[test]
+fn export_rules() {
+    let registry = RuleRegistry::default().with_node::<Binding>().with_rule("identifier", "[0-9A-Za-z_]+");
+    assert_eq!(
+        registry.to_string(),
+        r#"Binding = "let" ~ identifier ~ Annotation? ~ "=" ~ Value (~ "|" ~ Value)* (~ "|")?;
+Annotation = ":" ~ identifier;
+Value = Boolean | Number | Array | identifier ~ (~ Segment)*;
+Boolean = "true" | "false";
+Number = number;
+Array = "[" ~ (Value (~ "," ~ Value)* (~ ",")?)? ~ "]";
+Segment = "." ~ identifier;
+identifier = [0-9A-Za-z_]+;
+"#
+    );
+}
@@ -0,0 +1,313 @@
+use crate::{parser::parse_text, Grammar, GrammarError, GrammarExpr, GrammarRule};
+use pex::{ParseResult, ParseState, StopBecause};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The core rules of RFC 5234 appendix B.1, added when referenced but not defined
+const CORE_RULES: &str = r#"ALPHA = %x41-5A / %x61-7A
+BIT = "0" / "1"
+CHAR = %x01-7F
+CR = %x0D
+CRLF = CR LF
+CTL = %x00-1F / %x7F
+DIGIT = %x30-39
+DQUOTE = %x22
+HEXDIG = DIGIT / "A" / "B" / "C" / "D" / "E" / "F"
+HTAB = %x09
+LF = %x0A
+LWSP = *(WSP / CRLF WSP)
+OCTET = %x00-FF
+SP = %x20
+VCHAR = %x21-7E
+WSP = SP / HTAB
+"#;
+
+impl Grammar {
+    /// Convert an ABNF grammar of RFC 5234 and RFC 7405
+    ///
+    /// The rule names are case-insensitive, the references use the defined spelling,
+    /// `=/` adds alternatives to a rule, and the core rules like `ALPHA` and `CRLF` are added if referenced.
+    /// The alternatives are ordered and the repeats are greedy, as in every PEG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar =
+    ///     Grammar::from_abnf("time-hour = 2DIGIT ; 00-23\ntime-sep = %x3A / \"h\"\n").unwrap();
+    /// assert_eq!(
+    ///     grammar.to_string(),
+    ///     "time-hour = DIGIT{2};\ntime-sep = \":\" | i\"h\";\nDIGIT = [0-9];\n"
+    /// );
+    /// ```
+    pub fn from_abnf(text: &str) -> Result<Grammar, GrammarError> {
+        let mut grammar = Grammar::default();
+        let mut names = BTreeMap::new();
+        for (rule, incremental) in parse_text(text, rule_list)? {
+            match names.get(&rule.name.to_ascii_lowercase()) {
+                Some(index) if incremental => {
+                    let old: &mut GrammarRule = &mut grammar.rules[*index];
+                    let mut items = match std::mem::replace(&mut old.body, GrammarExpr::Any) {
+                        GrammarExpr::Choice(items) => items,
+                        body => vec![body],
+                    };
+                    match rule.body {
+                        GrammarExpr::Choice(more) => items.extend(more),
+                        body => items.push(body),
+                    }
+                    old.body = GrammarExpr::Choice(items);
+                }
+                None if incremental => {
+                    let message = format!("rule `{}` must be defined before `=/`", rule.name);
+                    Err(GrammarError::new(message, rule.range.clone()).with_source(text))?
+                }
+                // a duplicated rule is reported by the check
+                _ => {
+                    names.insert(rule.name.to_ascii_lowercase(), grammar.rules.len());
+                    grammar.rules.push(rule);
+                }
+            }
+        }
+        let core: Vec<GrammarRule> = match parse_text(CORE_RULES, rule_list) {
+            Ok(rules) => rules.into_iter().map(|(rule, _)| rule).collect(),
+            Err(_) => unreachable!("the core rules are valid"),
+        };
+        // add the referenced core rules, and the core rules they reference
+        let mut index = 0;
+        while index < grammar.rules.len() {
+            let mut missing = BTreeSet::new();
+            grammar.rules[index].body.walk(&mut |expr| {
+                if let GrammarExpr::Rule { name, .. } = expr {
+                    if !names.contains_key(&name.to_ascii_lowercase()) {
+                        missing.insert(name.to_ascii_lowercase());
+                    }
+                }
+            });
+            for name in missing {
+                if let Some(rule) = core.iter().find(|rule| rule.name.eq_ignore_ascii_case(&name)) {
+                    names.insert(name, grammar.rules.len());
+                    grammar.rules.push(GrammarRule { range: 0..0, ..rule.clone() });
+                }
+            }
+            index += 1;
+        }
+        let spelling: BTreeMap<String, String> =
+            grammar.rules.iter().map(|rule| (rule.name.to_ascii_lowercase(), rule.name.clone())).collect();
+        for rule in &mut grammar.rules {
+            rule.body.rename(&spelling);
+        }
+        Ok(grammar)
+    }
+}
+
+impl GrammarExpr {
+    /// Use the defined spelling of the case-insensitive references
+    fn rename(&mut self, spelling: &BTreeMap<String, String>) {
+        match self {
+            Self::Choice(items) | Self::Sequence(items) => items.iter_mut().for_each(|item| item.rename(spelling)),
+            Self::Optional(expr) | Self::Repeat { expr, .. } | Self::Positive(expr) | Self::Negative(expr) => {
+                expr.rename(spelling)
+            }
+            Self::Rule { name, .. } => {
+                if let Some(defined) = spelling.get(&name.to_ascii_lowercase()) {
+                    name.clone_from(defined)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The rules, and whether each rule is defined by `=/`
+fn rule_list(state: ParseState) -> ParseResult<Vec<(GrammarRule, bool)>> {
+    let (state, rules) = state.match_repeats(|s| s.skip(ignore).match_fn(rule))?;
+    let (state, _) = state.skip(ignore).match_eof()?;
+    state.finish(rules)
+}
+
+/// Skip whitespace, line breaks and `; comment`
+fn ignore(state: ParseState) -> ParseResult<()> {
+    let mut state = state;
+    loop {
+        let trimmed = state.residual.trim_start();
+        let mut offset = state.residual.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix(';') {
+            offset += 1 + rest.find('\n').unwrap_or(rest.len());
+        }
+        if offset == 0 {
+            return state.finish(());
+        }
+        state = state.advance(offset);
+    }
+}
+
+/// `name = elements` or `name =/ elements`
+fn rule(state: ParseState) -> ParseResult<(GrammarRule, bool)> {
+    let start = state.start_offset;
+    let (state, name) = rule_name(state)?;
+    let range = start..state.start_offset;
+    let (state, incremental) = state.skip(ignore).match_fn(defined_as)?;
+    let (state, body) = state.skip(ignore).match_fn(alternation)?;
+    state.finish((GrammarRule { name: name.to_string(), trivia: false, body, range }, incremental))
+}
+
+/// `=`, or `=/` for incremental alternatives
+fn defined_as(state: ParseState) -> ParseResult<bool> {
+    let (state, _) = state.match_char('=')?;
+    state.match_optional(|s| s.match_char('/')).map_inner(|slash| slash.is_some())
+}
+
+fn rule_name<'i>(state: ParseState<'i>) -> ParseResult<'i, &'i str> {
+    state.match_char_if(|c| c.is_ascii_alphabetic(), "RULENAME")?;
+    state.match_str_if(|c| c.is_ascii_alphanumeric() || c == '-', "RULENAME")
+}
+
+/// `a / b`
+fn alternation(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, first) = concatenation(state)?;
+    let (state, mut rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char('/')?;
+        s.skip(ignore).match_fn(concatenation)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(GrammarExpr::Choice(rest))
+        }
+    }
+}
+
+/// `a b`, stops before the name of the next rule
+fn concatenation(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, first) = repetition(state)?;
+    let (state, mut rest) = state.match_repeats(|s| {
+        let s = s.skip(ignore);
+        let (s, _) = s.match_negative(
+            |s| {
+                let (s, _) = rule_name(s)?;
+                s.skip(ignore).match_char('=')
+            },
+            "RULE",
+        )?;
+        repetition(s)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(GrammarExpr::Sequence(rest))
+        }
+    }
+}
+
+/// `n`, `m*n`, `*n`, `m*` or `*` before an element
+fn repetition(state: ParseState) -> ParseResult<GrammarExpr> {
+    let start = state.start_offset;
+    let (state, min) = state.match_optional(number)?;
+    let (state, star) = state.match_optional(|s| s.match_char('*'))?;
+    let (state, max) = match star {
+        Some(_) => state.match_optional(number)?,
+        None => (state, min),
+    };
+    let (state, element) = element(state)?;
+    if min.is_none() && star.is_none() {
+        return state.finish(element);
+    }
+    match (min.unwrap_or(0), max) {
+        (min, Some(max)) if max < min => {
+            StopBecause::custom_error("the maximum is less than the minimum", start, state.start_offset)?
+        }
+        (0, Some(1)) => state.finish(GrammarExpr::Optional(Box::new(element))),
+        (1, Some(1)) => state.finish(element),
+        (min, max) => state.finish(GrammarExpr::Repeat { expr: Box::new(element), min, max }),
+    }
+}
+
+fn number(state: ParseState) -> ParseResult<usize> {
+    let (next, digits) = state.match_str_if(|c| c.is_ascii_digit(), "DIGIT")?;
+    match digits.parse() {
+        Ok(n) => next.finish(n),
+        Err(_) => StopBecause::custom_error("the number is too large", state.start_offset, next.start_offset)?,
+    }
+}
+
+fn element(state: ParseState) -> ParseResult<GrammarExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, name) = rule_name(s)?;
+            s.finish(GrammarExpr::Rule { name: name.to_string(), range: start..s.start_offset })
+        })
+        .or_else(|s| {
+            let (s, _) = s.match_char('(')?;
+            let (s, expr) = s.skip(ignore).match_fn(alternation)?;
+            let (s, _) = s.skip(ignore).match_char(')')?;
+            s.finish(expr)
+        })
+        .or_else(|s| {
+            let (s, _) = s.match_char('[')?;
+            let (s, expr) = s.skip(ignore).match_fn(alternation)?;
+            let (s, _) = s.skip(ignore).match_char(']')?;
+            s.finish(GrammarExpr::Optional(Box::new(expr)))
+        })
+        .or_else(char_value)
+        .or_else(numeric_value)
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, _) = s.match_char('<')?;
+            let (s, _) = s.match_str_until(|c| c == '>' || c == '\n', "PROSE")?;
+            let (s, _) = s.match_char('>')?;
+            StopBecause::custom_error("prose values are not supported, define the rule instead", start, s.start_offset)?
+        })
+        .end_choice()
+}
+
+/// `"text"` ignoring the ascii case, `%s"text"` case sensitive, or `%i"text"`
+fn char_value(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, sensitive) = state.match_optional(|s| {
+        let (s, _) = s.match_char('%')?;
+        s.match_char_if(|c| matches!(c, 's' | 'S' | 'i' | 'I'), "s or i").map_inner(|c| c.eq_ignore_ascii_case(&'s'))
+    })?;
+    let (state, _) = state.match_char('"')?;
+    let (state, text) = state.match_optional(|s| s.match_str_until(|c| c == '"' || c == '\n', "STRING"))?;
+    let (state, _) = state.match_char('"')?;
+    let text = text.unwrap_or_default();
+    // the case only matters if there are letters
+    let insensitive = !sensitive.unwrap_or(false) && text.chars().any(|c| c.is_ascii_alphabetic());
+    state.finish(GrammarExpr::Literal { text: text.to_string(), insensitive })
+}
+
+/// `%x41`, `%x41-5A` or `%x0D.0A`, also `%d` in decimal and `%b` in binary
+fn numeric_value(state: ParseState) -> ParseResult<GrammarExpr> {
+    let start = state.start_offset;
+    let (state, _) = state.match_char('%')?;
+    let (state, radix) = state.match_char_if(|c| matches!(c, 'x' | 'X' | 'd' | 'D' | 'b' | 'B'), "x, d or b")?;
+    let radix = match radix.to_ascii_lowercase() {
+        'x' => 16,
+        'd' => 10,
+        _ => 2,
+    };
+    let (state, first) = code_point(state, radix)?;
+    if let ParseResult::Pending(state, _) = state.match_char('-') {
+        let (state, last) = code_point(state, radix)?;
+        if last < first {
+            StopBecause::custom_error("the character range is out of order", start, state.start_offset)?
+        }
+        return state.finish(GrammarExpr::Class { ranges: vec![(first, last)], negated: false });
+    }
+    let (state, rest) = state.match_repeats(|s| {
+        let (s, _) = s.match_char('.')?;
+        code_point(s, radix)
+    })?;
+    let text: String = std::iter::once(first).chain(rest).collect();
+    state.finish(GrammarExpr::literal(text))
+}
+
+fn code_point(state: ParseState, radix: u32) -> ParseResult<char> {
+    let (next, digits) = state.match_str_if(|c| c.is_digit(radix), "DIGIT")?;
+    match u32::from_str_radix(digits, radix).ok().and_then(char::from_u32) {
+        Some(c) => next.finish(c),
+        None => StopBecause::custom_error("invalid character value", state.start_offset, next.start_offset)?,
+    }
+}
@@ -0,0 +1,259 @@
+use pex::helpers::{CharactersTrie, XID_CONTINUE, XID_START};
+use std::{
+    fmt::{Display, Formatter, Write},
+    ops::Range,
+};
+
+/// A grammar in the ygg notation, a list of PEG rules
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Grammar {
+    /// The rules in the order of definition
+    pub rules: Vec<GrammarRule>,
+}
+
+/// A named rule, `@trivia? name = expression;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GrammarRule {
+    /// The name of the rule
+    pub name: String,
+    /// Whether the rule is skipped by `~`
+    pub trivia: bool,
+    /// The expression of the rule
+    pub body: GrammarExpr,
+    /// The range of the name in the grammar file
+    pub range: Range<usize>,
+}
+
+/// An expression of a rule
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GrammarExpr {
+    /// `a | b`, try each branch in order
+    Choice(Vec<GrammarExpr>),
+    /// `a b`, match all items in order
+    Sequence(Vec<GrammarExpr>),
+    /// `a?`
+    Optional(Box<GrammarExpr>),
+    /// `a*`, `a+` or `a{min,max}`
+    Repeat {
+        /// The repeated expression
+        expr: Box<GrammarExpr>,
+        /// The minimum count
+        min: usize,
+        /// The maximum count, `None` if unbounded
+        max: Option<usize>,
+    },
+    /// `&a`, match without consuming
+    Positive(Box<GrammarExpr>),
+    /// `!a`, fail if matched
+    Negative(Box<GrammarExpr>),
+    /// `"text"`, or `i"text"` ignoring the ascii case
+    Literal {
+        /// The text to match
+        text: String,
+        /// Whether to ignore the ascii case
+        insensitive: bool,
+    },
+    /// `[a-z_]` or `[^"]`
+    Class {
+        /// The inclusive character ranges
+        ranges: Vec<(char, char)>,
+        /// Whether to match characters out of the ranges
+        negated: bool,
+    },
+    /// `XID_START` or `XID_CONTINUE`, a unicode character set of pex
+    Set(CharacterSet),
+    /// `ANY`, any character
+    Any,
+    /// `$`, the end of input
+    Eof,
+    /// `~`, skip the trivia rule
+    Trivia,
+    /// A reference to another rule
+    Rule {
+        /// The name of the referenced rule
+        name: String,
+        /// The range of the reference in the grammar file
+        range: Range<usize>,
+    },
+}
+
+/// A unicode character set provided by `pex::helpers`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CharacterSet {
+    /// `XID_START`, the characters which can start an identifier
+    XidStart,
+    /// `XID_CONTINUE`, the characters which can continue an identifier
+    XidContinue,
+}
+
+impl Grammar {
+    /// Find a rule by name
+    pub fn get_rule(&self, name: &str) -> Option<&GrammarRule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+    /// Find the rule marked by `@trivia`
+    pub fn get_trivia(&self) -> Option<&GrammarRule> {
+        self.rules.iter().find(|rule| rule.trivia)
+    }
+}
+
+impl GrammarExpr {
+    /// Create a reference to the rule
+    pub fn rule(name: impl Into<String>) -> Self {
+        Self::Rule { name: name.into(), range: 0..0 }
+    }
+    /// Create a case sensitive literal
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self::Literal { text: text.into(), insensitive: false }
+    }
+    /// Visit every expression in pre-order
+    pub fn walk<'a>(&'a self, f: &mut impl FnMut(&'a GrammarExpr)) {
+        f(self);
+        match self {
+            Self::Choice(items) | Self::Sequence(items) => items.iter().for_each(|item| item.walk(f)),
+            Self::Optional(expr) | Self::Repeat { expr, .. } | Self::Positive(expr) | Self::Negative(expr) => expr.walk(f),
+            _ => {}
+        }
+    }
+    /// The binding power in the ygg notation, higher binds tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Choice(_) => 0,
+            Self::Sequence(_) => 1,
+            Self::Positive(_) | Self::Negative(_) => 2,
+            Self::Optional(_) | Self::Repeat { .. } => 3,
+            _ => 4,
+        }
+    }
+    fn fmt_operand(&self, f: &mut Formatter<'_>, min: u8) -> std::fmt::Result {
+        match self.precedence() < min {
+            true => write!(f, "({})", self),
+            false => write!(f, "{}", self),
+        }
+    }
+}
+
+impl CharacterSet {
+    /// The name in the grammar, also the name of the constant in `pex::helpers`
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::XidStart => "XID_START",
+            Self::XidContinue => "XID_CONTINUE",
+        }
+    }
+    /// The character trie of the set
+    pub fn get_trie(&self) -> CharactersTrie {
+        match self {
+            Self::XidStart => XID_START,
+            Self::XidContinue => XID_CONTINUE,
+        }
+    }
+}
+
+impl Display for Grammar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for rule in &self.rules {
+            writeln!(f, "{}", rule)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for GrammarRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.trivia {
+            f.write_str("@trivia ")?;
+        }
+        write!(f, "{} = {};", self.name, self.body)
+    }
+}
+
+impl Display for GrammarExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Choice(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        f.write_str(" | ")?;
+                    }
+                    item.fmt_operand(f, 1)?;
+                }
+                Ok(())
+            }
+            Self::Sequence(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        f.write_char(' ')?;
+                    }
+                    item.fmt_operand(f, 2)?;
+                }
+                Ok(())
+            }
+            Self::Optional(expr) => {
+                expr.fmt_operand(f, 4)?;
+                f.write_char('?')
+            }
+            Self::Repeat { expr, min, max } => {
+                expr.fmt_operand(f, 4)?;
+                match (min, max) {
+                    (0, None) => f.write_char('*'),
+                    (1, None) => f.write_char('+'),
+                    (min, None) => write!(f, "{{{},}}", min),
+                    (min, Some(max)) if min == max => write!(f, "{{{}}}", min),
+                    (min, Some(max)) => write!(f, "{{{},{}}}", min, max),
+                }
+            }
+            Self::Positive(expr) => {
+                f.write_char('&')?;
+                expr.fmt_operand(f, 3)
+            }
+            Self::Negative(expr) => {
+                f.write_char('!')?;
+                expr.fmt_operand(f, 3)
+            }
+            Self::Literal { text, insensitive } => {
+                if *insensitive {
+                    f.write_char('i')?;
+                }
+                f.write_char('"')?;
+                for c in text.chars() {
+                    write_escaped(f, c, '"')?;
+                }
+                f.write_char('"')
+            }
+            Self::Class { ranges, negated } => {
+                f.write_char('[')?;
+                if *negated {
+                    f.write_char('^')?;
+                }
+                for (start, end) in ranges {
+                    write_escaped(f, *start, ']')?;
+                    if start != end {
+                        f.write_char('-')?;
+                        write_escaped(f, *end, ']')?;
+                    }
+                }
+                f.write_char(']')
+            }
+            Self::Set(set) => f.write_str(set.get_name()),
+            Self::Any => f.write_str("ANY"),
+            Self::Eof => f.write_char('$'),
+            Self::Trivia => f.write_char('~'),
+            Self::Rule { name, .. } => f.write_str(name),
+        }
+    }
+}
+
+/// Write a character of a literal or a class, escape the quote and the special characters
+fn write_escaped(f: &mut Formatter<'_>, c: char, quote: char) -> std::fmt::Result {
+    match c {
+        '\\' => f.write_str("\\\\"),
+        '\n' => f.write_str("\\n"),
+        '\r' => f.write_str("\\r"),
+        '\t' => f.write_str("\\t"),
+        '-' | '^' if quote == ']' => write!(f, "\\{}", c),
+        c if c == quote => write!(f, "\\{}", c),
+        c if c.is_control() => write!(f, "\\u{{{:X}}}", c as u32),
+        c => f.write_char(c),
+    }
+}
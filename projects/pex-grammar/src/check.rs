@@ -0,0 +1,142 @@
+use crate::{Grammar, GrammarError, GrammarExpr};
+use std::{collections::BTreeMap, ops::Range};
+
+impl Grammar {
+    /// Check the rules, returns every duplicated or undefined rule, left recursion and repeat of empty matches
+    pub fn check(&self) -> Vec<GrammarError> {
+        let mut errors = vec![];
+        let mut names = BTreeMap::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if names.insert(rule.name.as_str(), index).is_some() {
+                errors.push(GrammarError::new(format!("rule `{}` is already defined", rule.name), rule.range.clone()));
+            }
+        }
+        if let Some(second) = self.rules.iter().filter(|rule| rule.trivia).nth(1) {
+            errors.push(GrammarError::new("only one rule can be marked by `@trivia`", second.range.clone()));
+        }
+        for rule in &self.rules {
+            rule.body.walk(&mut |expr| match expr {
+                GrammarExpr::Rule { name, range } if !names.contains_key(name.as_str()) => {
+                    errors.push(GrammarError::new(format!("undefined rule `{}`", name), range.clone()))
+                }
+                GrammarExpr::Trivia if rule.trivia => {
+                    errors.push(GrammarError::new("the trivia rule can not contain `~`", rule.range.clone()))
+                }
+                _ => {}
+            });
+        }
+        let nullable = self.nullable_rules();
+        for rule in &self.rules {
+            rule.body.walk(&mut |expr| {
+                if let GrammarExpr::Repeat { expr: inner, max: None, .. } = expr {
+                    if inner.is_nullable(&nullable) {
+                        let message = format!("`{}` in rule `{}` may repeat an empty match forever", expr, rule.name);
+                        errors.push(GrammarError::new(message, rule.range.clone()))
+                    }
+                }
+            });
+        }
+        self.check_left_recursion(&names, &nullable, &mut errors);
+        errors
+    }
+    /// Find the rules which can succeed without consuming any input
+    pub fn nullable_rules(&self) -> BTreeMap<&str, bool> {
+        let mut table: BTreeMap<&str, bool> = self.rules.iter().map(|rule| (rule.name.as_str(), false)).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for rule in &self.rules {
+                if !table[rule.name.as_str()] && rule.body.is_nullable(&table) {
+                    table.insert(rule.name.as_str(), true);
+                    changed = true;
+                }
+            }
+        }
+        table
+    }
+    fn check_left_recursion(
+        &self,
+        names: &BTreeMap<&str, usize>,
+        nullable: &BTreeMap<&str, bool>,
+        errors: &mut Vec<GrammarError>,
+    ) {
+        // the rules called at the start position of each rule
+        let edges: Vec<Vec<(usize, Range<usize>)>> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let mut calls = vec![];
+                rule.body.first_calls(nullable, &mut calls);
+                calls.into_iter().filter_map(|(name, range)| Some((*names.get(name)?, range))).collect()
+            })
+            .collect();
+        // 0: not visited, 1: on the stack, 2: finished
+        let mut marks = vec![0u8; self.rules.len()];
+        let mut stack = vec![];
+        for start in 0..self.rules.len() {
+            self.visit(start, &edges, &mut marks, &mut stack, errors);
+        }
+    }
+    fn visit(
+        &self,
+        index: usize,
+        edges: &[Vec<(usize, Range<usize>)>],
+        marks: &mut [u8],
+        stack: &mut Vec<usize>,
+        errors: &mut Vec<GrammarError>,
+    ) {
+        if marks[index] != 0 {
+            return;
+        }
+        marks[index] = 1;
+        stack.push(index);
+        for (callee, range) in &edges[index] {
+            match marks[*callee] {
+                0 => self.visit(*callee, edges, marks, stack, errors),
+                1 => {
+                    let start = stack.iter().position(|i| i == callee).unwrap_or_default();
+                    let path: Vec<&str> = stack[start..].iter().chain([callee]).map(|i| self.rules[*i].name.as_str()).collect();
+                    let message = format!("left recursion is not supported: {}", path.join(" -> "));
+                    errors.push(GrammarError::new(message, range.clone()))
+                }
+                _ => {}
+            }
+        }
+        stack.pop();
+        marks[index] = 2;
+    }
+}
+
+impl GrammarExpr {
+    /// Check if the expression can succeed without consuming any input
+    pub fn is_nullable(&self, rules: &BTreeMap<&str, bool>) -> bool {
+        match self {
+            Self::Choice(items) => items.iter().any(|item| item.is_nullable(rules)),
+            Self::Sequence(items) => items.iter().all(|item| item.is_nullable(rules)),
+            Self::Repeat { expr, min, .. } => *min == 0 || expr.is_nullable(rules),
+            Self::Optional(_) | Self::Positive(_) | Self::Negative(_) | Self::Eof | Self::Trivia => true,
+            Self::Literal { text, .. } => text.is_empty(),
+            Self::Class { .. } | Self::Set(_) | Self::Any => false,
+            Self::Rule { name, .. } => rules.get(name.as_str()).copied().unwrap_or(false),
+        }
+    }
+    /// Collect the rules that may be called at the start position
+    fn first_calls<'a>(&'a self, rules: &BTreeMap<&str, bool>, calls: &mut Vec<(&'a str, Range<usize>)>) {
+        match self {
+            Self::Choice(items) => items.iter().for_each(|item| item.first_calls(rules, calls)),
+            Self::Sequence(items) => {
+                for item in items {
+                    item.first_calls(rules, calls);
+                    if !item.is_nullable(rules) {
+                        break;
+                    }
+                }
+            }
+            Self::Optional(expr) | Self::Repeat { expr, .. } | Self::Positive(expr) | Self::Negative(expr) => {
+                expr.first_calls(rules, calls)
+            }
+            Self::Rule { name, range } => calls.push((name, range.clone())),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use crate::{Grammar, GrammarError, GrammarExpr, GrammarRule};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+/// How many times an expression captures each rule, the maximum is `None` if unbounded
+type Counts<'a> = BTreeMap<&'a str, (usize, Option<usize>)>;
+
+/// The multiplicity of a field
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum FieldKind {
+    One,
+    Optional,
+    Many,
+}
+
+struct RustWriter<'a> {
+    grammar: &'a Grammar,
+    /// The fields of the struct rules, enum rules are not in the map
+    fields: BTreeMap<&'a str, Vec<(&'a str, FieldKind)>>,
+    /// The fields of rule types which contain their own type
+    boxed: BTreeSet<(&'a str, &'a str)>,
+    out: String,
+}
+
+impl Grammar {
+    /// Check the grammar and generate the rust code of the parser
+    ///
+    /// Every rule becomes a type implementing `PexNode` and `FromStr`:
+    /// a choice of rules becomes an enum, other rules become structs with the `span` of the match,
+    /// and a field for each rule it captures, which is `T`, `Option<T>` or `Vec<T>` by how many times it is matched.
+    /// The rules that capture nothing keep the matched `text`.
+    pub fn to_rust(&self) -> Result<String, Vec<GrammarError>> {
+        let mut errors = self.check();
+        let mut types = BTreeMap::new();
+        let mut fields = BTreeMap::new();
+        for rule in &self.rules {
+            if !rule.name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                let message = format!("rule `{}` is not a valid type name, generate functions instead", rule.name);
+                errors.push(GrammarError::new(message, rule.range.clone()));
+            }
+            let name = type_name(&rule.name);
+            if name == "Self" || name == "AnyNode" {
+                errors.push(GrammarError::new(
+                    format!("rule `{}` is reserved by the generated code", rule.name),
+                    rule.range.clone(),
+                ));
+            }
+            if let Some(old) = types.insert(name, &rule.name).filter(|old| **old != rule.name) {
+                let message = format!("rule `{}` has the same type name as `{}`", rule.name, old);
+                errors.push(GrammarError::new(message, rule.range.clone()));
+            }
+            if let Some(old) = fields.insert(snake_name(&rule.name), &rule.name).filter(|old| **old != rule.name) {
+                let message = format!("rule `{}` has the same field name as `{}`", rule.name, old);
+                errors.push(GrammarError::new(message, rule.range.clone()));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut writer = RustWriter::new(self);
+        writer.write_grammar();
+        Ok(writer.out)
+    }
+}
+
+impl<'a> RustWriter<'a> {
+    fn new(grammar: &'a Grammar) -> Self {
+        let mut fields = BTreeMap::new();
+        for rule in &grammar.rules {
+            if enum_variants(rule).is_none() {
+                let counts = rule.body.counts();
+                fields.insert(rule.name.as_str(), counts.into_iter().map(|(name, count)| (name, field_kind(count))).collect());
+            }
+        }
+        let mut out = Self { grammar, fields, boxed: BTreeSet::new(), out: String::new() };
+        out.find_boxed();
+        out
+    }
+    /// The rules contained by value, through a field of `T` or `Option<T>`, or an enum variant
+    fn contained(&self, name: &str) -> Vec<&'a str> {
+        match self.fields.get(name) {
+            Some(fields) => fields.iter().filter(|(_, kind)| *kind != FieldKind::Many).map(|(name, _)| *name).collect(),
+            None => self.grammar.get_rule(name).and_then(enum_variants).unwrap_or_default(),
+        }
+    }
+    /// Box the fields whose type contains the owner, so that every type has a finite size
+    fn find_boxed(&mut self) {
+        for rule in &self.grammar.rules {
+            for child in self.contained(&rule.name) {
+                let mut seen = BTreeSet::new();
+                let mut stack = vec![child];
+                while let Some(name) = stack.pop() {
+                    if name == rule.name {
+                        self.boxed.insert((rule.name.as_str(), child));
+                        break;
+                    }
+                    if seen.insert(name) {
+                        stack.extend(self.contained(name));
+                    }
+                }
+            }
+        }
+    }
+    fn write_grammar(&mut self) {
+        self.out.push_str("// This file is generated by pex-grammar, do not edit it by hand.\n\n");
+        // the nodes captured by the struct rules
+        let captured: BTreeSet<&str> = self.fields.values().flatten().map(|(name, _)| *name).collect();
+        self.out.push_str("#[allow(clippy::large_enum_variant)]\nenum AnyNode {\n");
+        for rule in self.grammar.rules.iter().filter(|rule| captured.contains(rule.name.as_str())) {
+            let name = type_name(&rule.name);
+            writeln!(self.out, "    {}({}),", name, name).unwrap();
+        }
+        self.out.push_str("}\n");
+        for rule in &self.grammar.rules {
+            self.out.push('\n');
+            match enum_variants(rule) {
+                Some(variants) => self.write_enum(rule, &variants),
+                None => self.write_struct(rule),
+            }
+            let name = type_name(&rule.name);
+            writeln!(
+                self.out,
+                "\nimpl ::core::str::FromStr for {name} {{
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {{
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }}
+}}"
+            )
+            .unwrap();
+        }
+    }
+    fn write_enum(&mut self, rule: &GrammarRule, variants: &[&str]) {
+        let name = type_name(&rule.name);
+        writeln!(self.out, "/// `{}`\n#[derive(Clone, Debug, Eq, PartialEq)]\npub enum {} {{", rule, name).unwrap();
+        for variant in variants {
+            let ty = self.field_type(&rule.name, variant);
+            writeln!(self.out, "    {}({}),", type_name(variant), ty).unwrap();
+        }
+        writeln!(
+            self.out,
+            "}}\n\nimpl ::pex::PexNode for {name} {{\n    fn parse(input: ::pex::ParseState) -> ::pex::ParseResult<Self> {{"
+        )
+        .unwrap();
+        self.out.push_str("        input\n            .begin_choice()\n");
+        for variant in variants {
+            let ty = type_name(variant);
+            let wrap = match self.boxed.contains(&(rule.name.as_str(), *variant)) {
+                true => format!("|node| Self::{}(::std::boxed::Box::new(node))", ty),
+                false => format!("Self::{}", ty),
+            };
+            writeln!(self.out, "            .or_else(|s| s.match_fn(<{} as ::pex::PexNode>::parse).map_inner({}))", ty, wrap)
+                .unwrap();
+        }
+        self.out.push_str("            .end_choice()\n    }\n");
+        self.out.push_str("    fn get_range(&self) -> ::core::ops::Range<usize> {\n        match self {\n");
+        for variant in variants {
+            writeln!(self.out, "            Self::{}(node) => ::pex::PexNode::get_range(node),", type_name(variant)).unwrap();
+        }
+        self.out.push_str("        }\n    }\n}\n");
+    }
+    fn write_struct(&mut self, rule: &GrammarRule) {
+        let name = type_name(&rule.name);
+        let fields = self.fields[rule.name.as_str()].clone();
+        writeln!(self.out, "/// `{}`\n#[derive(Clone, Debug, Eq, PartialEq)]\npub struct {} {{", rule, name).unwrap();
+        for (child, _) in &fields {
+            writeln!(self.out, "    pub {}: {},", field_name(child), self.field_type(&rule.name, child)).unwrap();
+        }
+        if fields.is_empty() {
+            self.out.push_str("    pub text: ::std::string::String,\n");
+        }
+        self.out.push_str("    pub span: ::core::ops::Range<usize>,\n}\n\n");
+        writeln!(self.out, "impl ::pex::PexNode for {name} {{").unwrap();
+        if fields.len() == 1 {
+            self.out.push_str("    #[allow(clippy::single_match)]\n");
+        }
+        // the locals are prefixed, so that they are not shadowed by the fields named after the rules
+        self.out.push_str("    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {\n");
+        let body = format!("{}_body", snake_name(&rule.name));
+        match fields.is_empty() {
+            true => {
+                writeln!(self.out, "        let (_pex_state, _) = {}(_pex_input)?;", body).unwrap();
+                self.out.push_str(
+                    "        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();\n",
+                );
+                self.out.push_str(
+                    "        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })\n",
+                );
+            }
+            false => {
+                writeln!(self.out, "        let (_pex_state, _pex_nodes) = {}(_pex_input)?;", body).unwrap();
+                for (child, kind) in &fields {
+                    let init = match kind {
+                        FieldKind::Many => "::std::vec::Vec::new()",
+                        _ => "::core::option::Option::None",
+                    };
+                    writeln!(self.out, "        let mut {} = {};", field_name(child), init).unwrap();
+                }
+                self.out.push_str("        for _pex_node in _pex_nodes {\n            match _pex_node {\n");
+                for (child, kind) in &fields {
+                    let field = field_name(child);
+                    let store = match kind {
+                        FieldKind::Many => format!("{}.push(_pex_node)", field),
+                        _ => format!("{} = ::core::option::Option::Some(_pex_node)", field),
+                    };
+                    writeln!(self.out, "                AnyNode::{}(_pex_node) => {},", type_name(child), store).unwrap();
+                }
+                self.out.push_str(
+                    "                #[allow(unreachable_patterns)]\n                _ => {}\n            }\n        }\n",
+                );
+                self.out.push_str("        _pex_state.finish(Self {\n");
+                for (child, kind) in &fields {
+                    let field = field_name(child);
+                    let boxed = self.boxed.contains(&(rule.name.as_str(), *child));
+                    let value = match (kind, boxed) {
+                        (FieldKind::One, false) => format!("{}.expect(\"matched by the grammar\")", field),
+                        (FieldKind::One, true) => {
+                            format!("::std::boxed::Box::new({}.expect(\"matched by the grammar\"))", field)
+                        }
+                        (FieldKind::Optional, true) => format!("{}.map(::std::boxed::Box::new)", field),
+                        _ => field.clone(),
+                    };
+                    match value == field {
+                        true => writeln!(self.out, "            {},", field).unwrap(),
+                        false => writeln!(self.out, "            {}: {},", field, value).unwrap(),
+                    }
+                }
+                self.out.push_str("            span: _pex_input.start_offset.._pex_state.start_offset,\n        })\n");
+            }
+        }
+        self.out
+            .push_str("    }\n    fn get_range(&self) -> ::core::ops::Range<usize> {\n        self.span.clone()\n    }\n}\n\n");
+        let mut class = false;
+        rule.body.walk(&mut |expr| class |= matches!(expr, GrammarExpr::Class { .. }));
+        if class {
+            self.out.push_str("#[allow(clippy::manual_is_ascii_check)]\n");
+        }
+        writeln!(self.out, "fn {}(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {{", body).unwrap();
+        let code = match &rule.body {
+            GrammarExpr::Sequence(items) => self.sequence_statements(items, true),
+            expr => self.expression(expr),
+        };
+        writeln!(self.out, "    {}\n}}", indent(&code)).unwrap();
+    }
+    fn field_type(&self, owner: &str, child: &str) -> String {
+        let ty = type_name(child);
+        let ty = match self.boxed.contains(&(owner, child)) {
+            true => format!("::std::boxed::Box<{}>", ty),
+            false => ty,
+        };
+        match self.fields.get(owner).and_then(|fields| fields.iter().find(|(name, _)| *name == child)) {
+            Some((_, FieldKind::Optional)) => format!("::core::option::Option<{}>", ty),
+            Some((_, FieldKind::Many)) => format!("::std::vec::Vec<{}>", type_name(child)),
+            _ => ty,
+        }
+    }
+    /// An expression of `ParseResult<Vec<AnyNode>>` with the state `s`
+    fn expression(&self, expr: &GrammarExpr) -> String {
+        if expr.counts().is_empty() {
+            return format!("{}.map_value(::std::vec::Vec::new())", self.matcher(expr));
+        }
+        match expr {
+            GrammarExpr::Choice(items) => {
+                let mut out = String::from("s.begin_choice()");
+                for item in items {
+                    write!(out, "\n    .or_else(|s| {})", indent(&self.expression(item))).unwrap();
+                }
+                out.push_str("\n    .end_choice()");
+                out
+            }
+            GrammarExpr::Sequence(items) => {
+                format!("s.match_fn(|s| {{\n    {}\n}})", indent(&self.sequence_statements(items, true)))
+            }
+            GrammarExpr::Optional(inner) => {
+                format!("s.match_optional(|s| {}).map_inner(|nodes| nodes.unwrap_or_default())", self.expression(inner))
+            }
+            GrammarExpr::Repeat { expr: inner, min, max } => {
+                format!(
+                    "{}.map_inner(|nodes| nodes.into_iter().flatten().collect::<::std::vec::Vec<AnyNode>>())",
+                    repeat(*min, *max, &self.expression(inner))
+                )
+            }
+            GrammarExpr::Rule { name, .. } => {
+                let ty = type_name(name);
+                format!("s.match_fn(<{} as ::pex::PexNode>::parse).map_inner(|node| ::std::vec![AnyNode::{}(node)])", ty, ty)
+            }
+            _ => unreachable!("only rules are captured"),
+        }
+    }
+    /// An expression which captures no rule, the value is dropped
+    fn matcher(&self, expr: &GrammarExpr) -> String {
+        match expr {
+            GrammarExpr::Choice(items) => {
+                let mut out = String::from("s.begin_choice()");
+                for item in items {
+                    write!(out, "\n    .or_else(|s| {}.map_value(()))", indent(&self.matcher(item))).unwrap();
+                }
+                out.push_str("\n    .end_choice()");
+                out
+            }
+            GrammarExpr::Sequence(items) => {
+                format!("s.match_fn(|s| {{\n    {}\n}})", indent(&self.sequence_statements(items, false)))
+            }
+            GrammarExpr::Optional(inner) => format!("s.match_optional(|s| {})", self.matcher(inner)),
+            GrammarExpr::Repeat { expr: inner, min, max } => repeat(*min, *max, &self.matcher(inner)),
+            GrammarExpr::Positive(inner) => format!("s.match_positive(|s| {}, {:?})", self.matcher(inner), inner.to_string()),
+            GrammarExpr::Negative(inner) => format!("s.match_negative(|s| {}, {:?})", self.matcher(inner), inner.to_string()),
+            GrammarExpr::Literal { text, insensitive: false } => format!("s.match_str({:?})", text),
+            GrammarExpr::Literal { text, insensitive: true } => format!("s.match_str_insensitive({:?})", text),
+            GrammarExpr::Class { ranges, negated } => {
+                let mut patterns = String::new();
+                for (index, (start, end)) in ranges.iter().enumerate() {
+                    if index != 0 {
+                        patterns.push_str(" | ");
+                    }
+                    match start == end {
+                        true => write!(patterns, "{:?}", start).unwrap(),
+                        false => write!(patterns, "{:?}..={:?}", start, end).unwrap(),
+                    }
+                }
+                let not = if *negated { "!" } else { "" };
+                format!("s.match_char_if(|c| {}matches!(c, {}), {:?})", not, patterns, expr.to_string())
+            }
+            GrammarExpr::Set(set) => format!("s.match_fn(::pex::helpers::{})", set.get_name()),
+            GrammarExpr::Any => "s.match_char_any()".to_string(),
+            GrammarExpr::Eof => "s.match_eof()".to_string(),
+            GrammarExpr::Trivia => format!("s.try_skip({})", self.trivia()),
+            // the matched node is dropped in lookaheads
+            GrammarExpr::Rule { name, .. } => format!("s.match_fn(<{} as ::pex::PexNode>::parse)", type_name(name)),
+        }
+    }
+    /// The parser skipped by `~`
+    fn trivia(&self) -> String {
+        match self.grammar.get_trivia() {
+            Some(trivia) => format!("<{} as ::pex::PexNode>::parse", type_name(&trivia.name)),
+            None => "::pex::helpers::whitespace".to_string(),
+        }
+    }
+    /// Match the items in order, collect the captured nodes, or finish with `()` if nothing is captured and `nodes` is false
+    fn sequence_statements(&self, items: &[GrammarExpr], nodes: bool) -> String {
+        let capture = items.iter().any(|item| !item.counts().is_empty());
+        let mut out = String::new();
+        if capture {
+            out.push_str("let mut nodes = ::std::vec::Vec::new();\n");
+        }
+        for item in items {
+            match item {
+                GrammarExpr::Trivia => writeln!(out, "let (s, _) = s.try_skip({})?;", self.trivia()).unwrap(),
+                GrammarExpr::Rule { name, .. } => {
+                    let ty = type_name(name);
+                    writeln!(out, "let (s, node) = s.match_fn(<{} as ::pex::PexNode>::parse)?;", ty).unwrap();
+                    writeln!(out, "nodes.push(AnyNode::{}(node));", ty).unwrap();
+                }
+                item if item.counts().is_empty() => writeln!(out, "let (s, _) = {}?;", self.matcher(item)).unwrap(),
+                item => {
+                    writeln!(out, "let (s, more) = {}?;", self.expression(item)).unwrap();
+                    out.push_str("nodes.extend(more);\n");
+                }
+            }
+        }
+        match (capture, nodes) {
+            (true, _) => out.push_str("s.finish(nodes)"),
+            (false, true) => out.push_str("s.finish(::std::vec::Vec::new())"),
+            (false, false) => out.push_str("s.finish(())"),
+        }
+        out
+    }
+}
+
+impl GrammarExpr {
+    fn counts(&self) -> Counts<'_> {
+        match self {
+            Self::Rule { name, .. } => Counts::from([(name.as_str(), (1, Some(1)))]),
+            Self::Sequence(items) => {
+                let mut out = Counts::new();
+                for item in items {
+                    for (name, (min, max)) in item.counts() {
+                        let (old_min, old_max) = out.entry(name).or_insert((0, Some(0)));
+                        *old_min += min;
+                        *old_max = old_max.zip(max).map(|(a, b)| a + b);
+                    }
+                }
+                out
+            }
+            Self::Choice(items) => {
+                let branches: Vec<Counts> = items.iter().map(|item| item.counts()).collect();
+                let names: BTreeSet<&str> = branches.iter().flat_map(|branch| branch.keys().copied()).collect();
+                let mut out = Counts::new();
+                for name in names {
+                    let counts = branches.iter().map(|branch| branch.get(name).copied().unwrap_or((0, Some(0))));
+                    let min = counts.clone().map(|(min, _)| min).min().unwrap_or(0);
+                    let max = counts.map(|(_, max)| max).try_fold(0, |acc, max| max.map(|max| acc.max(max)));
+                    out.insert(name, (min, max));
+                }
+                out
+            }
+            Self::Optional(inner) => inner.counts().into_iter().map(|(name, (_, max))| (name, (0, max))).collect(),
+            Self::Repeat { expr, min, max } => expr
+                .counts()
+                .into_iter()
+                .map(|(name, (inner_min, inner_max))| (name, (inner_min * min, inner_max.zip(*max).map(|(a, b)| a * b))))
+                .collect(),
+            _ => Counts::new(),
+        }
+    }
+}
+
+fn field_kind(count: (usize, Option<usize>)) -> FieldKind {
+    match count {
+        (1, Some(1)) => FieldKind::One,
+        (0, Some(1)) => FieldKind::Optional,
+        _ => FieldKind::Many,
+    }
+}
+
+/// The variants if the rule is a choice of distinct rules
+fn enum_variants(rule: &GrammarRule) -> Option<Vec<&str>> {
+    let GrammarExpr::Choice(items) = &rule.body
+    else {
+        return None;
+    };
+    let mut variants = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            GrammarExpr::Rule { name, .. } if !variants.contains(&name.as_str()) => variants.push(name.as_str()),
+            _ => return None,
+        }
+    }
+    Some(variants)
+}
+
+fn repeat(min: usize, max: Option<usize>, item: &str) -> String {
+    match (min, max) {
+        (0, None) => format!("s.match_repeats(|s| {})", item),
+        (min, None) => format!("s.match_repeat_m_n({}, usize::MAX, |s| {})", min, item),
+        (min, Some(max)) => format!("s.match_repeat_m_n({}, {}, |s| {})", min, max, item),
+    }
+}
+
+/// Indent every line except the first one
+pub(crate) fn indent(code: &str) -> String {
+    code.replace('\n', "\n    ")
+}
+
+/// `json_value` to `JsonValue`, and `ASCII_DIGIT` to `AsciiDigit`
+fn type_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for part in name.split('_').filter(|part| !part.is_empty()) {
+        let upper = !part.chars().any(|c| c.is_ascii_lowercase());
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            match upper {
+                true => out.extend(chars.map(|c| c.to_ascii_lowercase())),
+                false => out.extend(chars),
+            }
+        }
+    }
+    match out.is_empty() {
+        true => "Underscore".to_string(),
+        false => out,
+    }
+}
+
+/// `JsonValue` to `json_value`
+pub(crate) fn snake_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && lower {
+            out.push('_');
+        }
+        lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        out.push(c.to_ascii_lowercase());
+    }
+    out
+}
+
+/// The strict and reserved keywords of rust
+pub(crate) const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern",
+    "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override",
+    "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// The field name of a rule, avoid the keywords and the `span` field
+fn field_name(name: &str) -> String {
+    let name = snake_name(name);
+    match name.as_str() {
+        "self" | "super" | "crate" | "span" => format!("{}_", name),
+        s if KEYWORDS.contains(&s) => format!("r#{}", name),
+        _ => name,
+    }
+}
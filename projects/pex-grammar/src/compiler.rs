@@ -0,0 +1,94 @@
+use crate::{Grammar, GrammarError};
+use std::path::{Path, PathBuf};
+
+/// Compile a grammar file to rust code, used in `build.rs`.
+///
+/// ```no_run
+/// # use pex_grammar::GrammarCompiler;
+/// // build.rs
+/// if let Err(errors) = GrammarCompiler::new("grammars/json.ygg").build() {
+///     errors.iter().for_each(|e| println!("cargo:warning={}", e));
+///     panic!("invalid grammar");
+/// }
+/// // src/lib.rs
+/// // mod json { include!(concat!(env!("OUT_DIR"), "/json.rs")); }
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrammarCompiler {
+    input: PathBuf,
+    output: Option<PathBuf>,
+}
+
+impl GrammarCompiler {
+    /// Compile the grammar file
+    pub fn new(input: impl AsRef<Path>) -> Self {
+        Self { input: input.as_ref().to_path_buf(), output: None }
+    }
+    /// Write the code to the file, instead of `$OUT_DIR/<grammar name>.rs`
+    pub fn with_output(mut self, output: impl AsRef<Path>) -> Self {
+        self.output = Some(output.as_ref().to_path_buf());
+        self
+    }
+    /// Read, check and compile the grammar, returns the path of the generated code
+    ///
+    /// The errors are located in the grammar file, cargo is told to rerun the build script if the grammar changed.
+    pub fn build(&self) -> Result<PathBuf, Vec<GrammarError>> {
+        println!("cargo:rerun-if-changed={}", self.input.display());
+        let io_error = |e: std::io::Error| vec![GrammarError::new(e.to_string(), 0..0).with_file(&self.input)];
+        let text = std::fs::read_to_string(&self.input).map_err(io_error)?;
+        let code = self.compile(&text)?;
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            None => {
+                let out_dir = std::env::var_os("OUT_DIR")
+                    .ok_or_else(|| vec![GrammarError::new("`OUT_DIR` is not set, call it in the build script", 0..0)])?;
+                let name = self.input.file_stem().unwrap_or_default();
+                Path::new(&out_dir).join(name).with_extension("rs")
+            }
+        };
+        std::fs::write(&output, code).map_err(io_error)?;
+        Ok(output)
+    }
+    /// Compile the text of the grammar file, the notation is chosen by the extension
+    ///
+    /// A `.pest` file is converted first, and the constructs of pest which can not be translated are errors.
+    /// A `.abnf` or `.ebnf` file is compiled to a parser function for each rule, see [Grammar::to_rust_functions].
+    pub fn compile(&self, text: &str) -> Result<String, Vec<GrammarError>> {
+        let (grammar, _) = read_grammar(&self.input, text, false)?;
+        let code = match compiles_to_functions(&self.input) {
+            true => grammar.to_rust_functions(),
+            false => grammar.to_rust(),
+        };
+        code.map_err(|errors| errors.into_iter().map(|e| e.with_source(text).with_file(&self.input)).collect())
+    }
+}
+
+/// Read the grammar in the notation of the extension, the errors are located in the file
+///
+/// The constructs of pest which can not be translated are errors,
+/// or warnings returned with the grammar if `pest_warnings` is set.
+pub fn read_grammar(input: &Path, text: &str, pest_warnings: bool) -> Result<(Grammar, Vec<GrammarError>), Vec<GrammarError>> {
+    let locate = |e: GrammarError| e.with_source(text).with_file(input);
+    let mut warnings = vec![];
+    let grammar = match input.extension().and_then(|extension| extension.to_str()).unwrap_or_default() {
+        "pest" => {
+            let import = Grammar::from_pest(text).map_err(|e| vec![locate(e)])?;
+            let unsupported = import.unsupported.into_iter().map(locate).collect();
+            match pest_warnings {
+                true => warnings = unsupported,
+                false if !unsupported.is_empty() => return Err(unsupported),
+                false => {}
+            }
+            import.grammar
+        }
+        "abnf" => Grammar::from_abnf(text).map_err(|e| vec![locate(e)])?,
+        "ebnf" => Grammar::from_ebnf(text).map_err(|e| vec![locate(e)])?,
+        _ => text.parse().map_err(|e| vec![locate(e)])?,
+    };
+    Ok((grammar, warnings))
+}
+
+/// Whether the notation of the extension is compiled to a parser function for each rule
+pub(crate) fn compiles_to_functions(input: &Path) -> bool {
+    matches!(input.extension().and_then(|extension| extension.to_str()), Some("abnf" | "ebnf"))
+}
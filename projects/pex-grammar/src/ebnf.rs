@@ -0,0 +1,191 @@
+use crate::{parser::parse_text, Grammar, GrammarError, GrammarExpr, GrammarRule};
+use pex::{ParseResult, ParseState, StopBecause};
+
+impl Grammar {
+    /// Convert an ISO 14977 EBNF grammar
+    ///
+    /// The meta identifiers may contain spaces, `a - b` becomes `!b a`,
+    /// the definitions are ordered and the repeats are greedy, as in every PEG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar = Grammar::from_ebnf(
+    ///     "(* a comment *) signed integer = ['-'], digit, {digit};\ndigit = '0' | '1' | '2';",
+    /// )
+    /// .unwrap();
+    /// assert_eq!(grammar.rules[0].to_string(), "signed integer = \"-\"? digit digit*;");
+    /// ```
+    pub fn from_ebnf(text: &str) -> Result<Grammar, GrammarError> {
+        let rules = parse_text(text, syntax)?;
+        Ok(Grammar { rules })
+    }
+}
+
+fn syntax(state: ParseState) -> ParseResult<Vec<GrammarRule>> {
+    let (state, rules) = state.match_repeats(|s| s.skip(ignore).match_fn(rule))?;
+    let (state, _) = state.skip(ignore).match_eof()?;
+    state.finish(rules)
+}
+
+/// Skip whitespace and `(* comment *)`
+fn ignore(state: ParseState) -> ParseResult<()> {
+    let mut state = state;
+    loop {
+        let trimmed = state.residual.trim_start();
+        let mut offset = state.residual.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix("(*") {
+            match rest.find("*)") {
+                Some(end) => offset += end + 4,
+                None => StopBecause::missing_string("*)", state.end_offset())?,
+            }
+        }
+        if offset == 0 {
+            return state.finish(());
+        }
+        state = state.advance(offset);
+    }
+}
+
+/// `name = definitions ;`, or ended by `.`
+fn rule(state: ParseState) -> ParseResult<GrammarRule> {
+    let start = state.start_offset;
+    let (state, name) = meta_identifier(state)?;
+    let range = start..state.start_offset;
+    let (state, _) = state.skip(ignore).match_char('=')?;
+    let (state, body) = state.skip(ignore).match_fn(definitions)?;
+    let (state, _) = state.skip(ignore).match_char_if(|c| c == ';' || c == '.', "`;`")?;
+    state.finish(GrammarRule { name, trivia: false, body, range })
+}
+
+/// `letter or digit`, the words are joined by a space
+fn meta_identifier(state: ParseState) -> ParseResult<String> {
+    state.match_char_if(|c| c.is_ascii_alphabetic(), "META_IDENTIFIER")?;
+    let (state, first) = word(state)?;
+    let (state, rest) = state.match_repeats(|s| {
+        let (s, _) = s.match_str_if(|c| c == ' ' || c == '\t', "SPACE")?;
+        word(s)
+    })?;
+    let mut name = first.to_string();
+    for word in rest {
+        name.push(' ');
+        name.push_str(word);
+    }
+    state.finish(name)
+}
+
+fn word<'i>(state: ParseState<'i>) -> ParseResult<'i, &'i str> {
+    state.match_str_if(|c| c.is_ascii_alphanumeric() || c == '_', "META_IDENTIFIER")
+}
+
+/// `a | b`, also separated by `/` or `!`
+fn definitions(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, first) = single_definition(state)?;
+    let (state, mut rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char_if(|c| matches!(c, '|' | '/' | '!'), "`|`")?;
+        s.skip(ignore).match_fn(single_definition)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(GrammarExpr::Choice(rest))
+        }
+    }
+}
+
+/// `a, b`, or the empty sequence
+fn single_definition(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, first) = match state.match_fn(term) {
+        ParseResult::Pending(state, first) => (state, first),
+        ParseResult::Stop(_) => return state.finish(GrammarExpr::literal("")),
+    };
+    let (state, mut rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char(',')?;
+        s.skip(ignore).match_fn(term)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(GrammarExpr::Sequence(rest))
+        }
+    }
+}
+
+/// `a - b`, match `a` if `b` does not match
+fn term(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, item) = factor(state)?;
+    let (state, exception) = state.match_optional(|s| {
+        let (s, _) = s.skip(ignore).match_char('-')?;
+        s.skip(ignore).match_fn(factor)
+    })?;
+    match exception {
+        Some(exception) => state.finish(GrammarExpr::Sequence(vec![GrammarExpr::Negative(Box::new(exception)), item])),
+        None => state.finish(item),
+    }
+}
+
+/// `3 * a`
+fn factor(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, count) = state.match_optional(|s| {
+        let (s, count) = number(s)?;
+        let (s, _) = s.skip(ignore).match_char('*')?;
+        s.skip(ignore).finish(count)
+    })?;
+    let (state, primary) = primary(state)?;
+    match count {
+        Some(1) | None => state.finish(primary),
+        Some(count) => state.finish(GrammarExpr::Repeat { expr: Box::new(primary), min: count, max: Some(count) }),
+    }
+}
+
+fn number(state: ParseState) -> ParseResult<usize> {
+    let (next, digits) = state.match_str_if(|c| c.is_ascii_digit(), "INTEGER")?;
+    match digits.parse() {
+        Ok(n) => next.finish(n),
+        Err(_) => StopBecause::custom_error("the number is too large", state.start_offset, next.start_offset)?,
+    }
+}
+
+fn primary(state: ParseState) -> ParseResult<GrammarExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let (s, expr) = bracket(s, '[', ']')?;
+            s.finish(GrammarExpr::Optional(Box::new(expr)))
+        })
+        .or_else(|s| {
+            let (s, expr) = bracket(s, '{', '}')?;
+            s.finish(GrammarExpr::Repeat { expr: Box::new(expr), min: 0, max: None })
+        })
+        .or_else(|s| bracket(s, '(', ')'))
+        .or_else(|s| {
+            let (s, quote) = s.match_char_if(|c| c == '"' || c == '\'', "TERMINAL")?;
+            let (s, text) = s.match_str_until(|c| c == quote || c == '\n', "TERMINAL")?;
+            let (s, _) = s.match_char(quote)?;
+            s.finish(GrammarExpr::literal(text))
+        })
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, name) = meta_identifier(s)?;
+            s.finish(GrammarExpr::Rule { name, range: start..s.start_offset })
+        })
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, _) = s.match_char('?')?;
+            let (s, _) = s.match_optional(|s| s.match_str_until(|c| c == '?', "SPECIAL"))?;
+            let (s, _) = s.match_char('?')?;
+            StopBecause::custom_error("special sequences are not supported, define the rule instead", start, s.start_offset)?
+        })
+        .end_choice()
+}
+
+/// `[a]`, `{a}` or `(a)`
+fn bracket(state: ParseState, open: char, close: char) -> ParseResult<GrammarExpr> {
+    let (state, _) = state.match_char(open)?;
+    let (state, expr) = state.skip(ignore).match_fn(definitions)?;
+    let (state, _) = state.skip(ignore).match_char(close)?;
+    state.finish(expr)
+}
@@ -0,0 +1,53 @@
+use pex::{ColumnMode, SourceIndex, SourcePosition};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+/// An error in the grammar, such as a syntax error, an undefined rule or a left recursion
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GrammarError {
+    /// The description of the error
+    pub message: String,
+    /// The byte range in the grammar file
+    pub range: Range<usize>,
+    /// The grammar file, if read from a file
+    pub file: Option<PathBuf>,
+    /// The line and column of the range start, if the source is known
+    pub position: Option<SourcePosition>,
+}
+
+impl GrammarError {
+    /// Create an error at the range
+    pub fn new(message: impl Into<String>, range: Range<usize>) -> Self {
+        Self { message: message.into(), range, file: None, position: None }
+    }
+    /// Resolve the line and column in the grammar text
+    pub fn with_source(mut self, text: &str) -> Self {
+        let index = SourceIndex::new(text);
+        self.position = Some(index.get_position(self.range.start, ColumnMode::Characters));
+        self
+    }
+    /// Set the grammar file
+    pub fn with_file(mut self, file: impl AsRef<Path>) -> Self {
+        self.file = Some(file.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl Error for GrammarError {}
+
+impl Display for GrammarError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        match self.position {
+            Some(position) => write!(f, "{}:{}: {}", position.line, position.column, self.message),
+            None if self.file.is_some() => write!(f, " {}", self.message),
+            None => write!(f, "{} at {}", self.message, self.range.start),
+        }
+    }
+}
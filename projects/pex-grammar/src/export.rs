@@ -0,0 +1,500 @@
+use crate::{
+    compiler::{compiles_to_functions, read_grammar},
+    Grammar, GrammarError, GrammarExpr, GrammarRule,
+};
+use pex::RuleRegistry;
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// Export a grammar file or a [RuleRegistry] as W3C EBNF, railroad diagrams and a Markdown reference.
+///
+/// ```no_run
+/// # use pex::RuleRegistry;
+/// # use pex_grammar::GrammarExporter;
+/// // writes docs/json/json.ebnf, docs/json/json.md and an svg for each rule
+/// let files =
+///     GrammarExporter::new("grammars/json.ygg").with_output("docs/json").export().unwrap();
+/// // writes docs/digits/digits.ebnf, docs/digits/digits.md and digits.svg
+/// let registry = RuleRegistry::default().with_rule("digits", "[0-9]+");
+/// let files = GrammarExporter::new("docs/digits").export_registry(&registry).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrammarExporter {
+    input: PathBuf,
+    output: Option<PathBuf>,
+}
+
+impl GrammarExporter {
+    /// Export the grammar file, in any notation accepted by [GrammarCompiler](crate::GrammarCompiler)
+    pub fn new(input: impl AsRef<Path>) -> Self {
+        Self { input: input.as_ref().to_path_buf(), output: None }
+    }
+    /// Write the files to the directory, instead of `<grammar directory>/<grammar name>`
+    pub fn with_output(mut self, output: impl AsRef<Path>) -> Self {
+        self.output = Some(output.as_ref().to_path_buf());
+        self
+    }
+    /// Read and check the grammar, write `<grammar name>.ebnf`, `<grammar name>.md` and `<rule name>.svg`
+    ///
+    /// Returns the paths of the written files.
+    pub fn export(&self) -> Result<Vec<PathBuf>, Vec<GrammarError>> {
+        let io_error = |e: std::io::Error| vec![GrammarError::new(e.to_string(), 0..0).with_file(&self.input)];
+        let text = std::fs::read_to_string(&self.input).map_err(io_error)?;
+        let (grammar, _) = read_grammar(&self.input, &text, false)?;
+        let errors = grammar.check();
+        if !errors.is_empty() {
+            return Err(errors.into_iter().map(|e| e.with_source(&text).with_file(&self.input)).collect());
+        }
+        match compiles_to_functions(&self.input) {
+            true => self.write(&grammar, Grammar::to_markdown_functions),
+            false => self.write(&grammar, Grammar::to_markdown),
+        }
+    }
+    /// Export the rules of the registry, the name of the files is the name of the input
+    ///
+    /// The rules are not checked, so the parser functions without a registered rule are kept as references.
+    pub fn export_registry(&self, registry: &RuleRegistry) -> Result<Vec<PathBuf>, Vec<GrammarError>> {
+        let grammar =
+            Grammar::from_registry(registry).map_err(|e| vec![e.with_source(&registry.to_string()).with_file(&self.input)])?;
+        self.write(&grammar, Grammar::to_markdown)
+    }
+    fn write(&self, grammar: &Grammar, markdown: fn(&Grammar, &str) -> String) -> Result<Vec<PathBuf>, Vec<GrammarError>> {
+        let io_error = |e: std::io::Error| vec![GrammarError::new(e.to_string(), 0..0).with_file(&self.input)];
+        let name = self.input.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            None => self.input.with_file_name(&name),
+        };
+        std::fs::create_dir_all(&output).map_err(io_error)?;
+        let page = markdown(grammar, &name);
+        let mut files = vec![
+            (output.join(&name).with_extension("ebnf"), grammar.to_ebnf()),
+            (output.join(&name).with_extension("md"), page),
+        ];
+        for rule in &grammar.rules {
+            files.push((output.join(diagram_file(&rule.name)), rule.to_railroad()));
+        }
+        for (path, content) in &files {
+            std::fs::write(path, content).map_err(io_error)?;
+        }
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+}
+
+impl Grammar {
+    /// Read the rules collected by a [RuleRegistry], from the derived nodes and the rules written by hand
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex::RuleRegistry;
+    /// # use pex_grammar::Grammar;
+    /// let registry = RuleRegistry::default().with_rule("digits", "[0-9]+");
+    /// let grammar = Grammar::from_registry(&registry).unwrap();
+    /// assert_eq!(grammar.to_ebnf(), "digits ::= [0-9]+\n");
+    /// ```
+    pub fn from_registry(registry: &RuleRegistry) -> Result<Self, GrammarError> {
+        registry.to_string().parse()
+    }
+    /// Export the rules in the W3C EBNF notation of the XML specification
+    ///
+    /// Repeats with bounds are expanded, `i"text"` becomes classes like `[aA]`, and `!b a` becomes `(a - b)`.
+    /// The other lookaheads, `$` and `~` have no equivalent and are kept as comments,
+    /// unless `~` skips a rule marked by `@trivia`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar: Grammar = "number = \"-\"? [0-9]{1,3}; null = i\"null\";".parse().unwrap();
+    /// assert_eq!(
+    ///     grammar.to_ebnf(),
+    ///     "number ::= '-'? [0-9] ([0-9] [0-9]?)?\nnull ::= [nN] [uU] [lL] [lL]\n"
+    /// );
+    /// ```
+    pub fn to_ebnf(&self) -> String {
+        self.rules.iter().map(|rule| self.ebnf_rule(rule)).collect()
+    }
+    /// A rule in W3C EBNF, the branches of a long choice are put on separate lines
+    fn ebnf_rule(&self, rule: &GrammarRule) -> String {
+        let head = format!("{} ::= ", ebnf_name(&rule.name));
+        let line = format!("{}{}\n", head, self.ebnf(&rule.body, 0));
+        match &rule.body {
+            GrammarExpr::Choice(items) if line.trim_end().chars().count() > 80 => {
+                let indent = " ".repeat(head.chars().count() - 2);
+                let mut out = String::new();
+                for (index, item) in items.iter().enumerate() {
+                    match index {
+                        0 => write!(out, "{}{}", head, self.ebnf(item, 1)).unwrap(),
+                        _ => write!(out, "\n{}| {}", indent, self.ebnf(item, 1)).unwrap(),
+                    }
+                }
+                out.push('\n');
+                out
+            }
+            _ => line,
+        }
+    }
+    /// The expression in W3C EBNF, wrapped in parentheses if it binds looser than `min`
+    fn ebnf(&self, expr: &GrammarExpr, min: u8) -> String {
+        let (text, precedence) = match expr {
+            GrammarExpr::Choice(items) => {
+                let items: Vec<String> = items.iter().map(|item| self.ebnf(item, 1)).collect();
+                (items.join(" | "), 0)
+            }
+            GrammarExpr::Sequence(items) => {
+                let mut parts = vec![];
+                let mut iter = items.iter().peekable();
+                while let Some(item) = iter.next() {
+                    match (item, iter.peek()) {
+                        (GrammarExpr::Negative(except), Some(next)) if !matches!(next, GrammarExpr::Trivia) => {
+                            parts.push(format!("({} - {})", self.ebnf(next, 3), self.ebnf(except, 3)));
+                            iter.next();
+                        }
+                        _ => parts.push(self.ebnf(item, 1)),
+                    }
+                }
+                (parts.join(" "), 1)
+            }
+            GrammarExpr::Optional(inner) => (format!("{}?", self.ebnf(inner, 3)), 2),
+            GrammarExpr::Repeat { expr: inner, min: count, max } => {
+                let item = self.ebnf(inner, 3);
+                let mut parts = vec![item.clone(); count.saturating_sub(1)];
+                match max {
+                    None if *count == 0 => parts.push(format!("{}*", item)),
+                    None => parts.push(format!("{}+", item)),
+                    Some(max) => {
+                        if *count != 0 {
+                            parts.push(item.clone());
+                        }
+                        if let Some(optional) = optional_repeat(&item, max.saturating_sub(*count)) {
+                            parts.push(optional);
+                        }
+                    }
+                }
+                match parts.len() {
+                    0 => ("/* nothing */".to_string(), 3),
+                    1 => (parts.remove(0), 2),
+                    _ => (parts.join(" "), 1),
+                }
+            }
+            GrammarExpr::Positive(_) | GrammarExpr::Negative(_) => (format!("/* {} */", expr), 3),
+            GrammarExpr::Literal { text, insensitive } => {
+                let mut parts = vec![];
+                let mut run = String::new();
+                for c in text.chars() {
+                    match c {
+                        c if *insensitive && c.is_ascii_alphabetic() => {
+                            parts.extend(ebnf_string(&std::mem::take(&mut run)));
+                            parts.push(format!("[{}{}]", c.to_ascii_lowercase(), c.to_ascii_uppercase()));
+                        }
+                        c if c.is_control() => {
+                            parts.extend(ebnf_string(&std::mem::take(&mut run)));
+                            parts.push(format!("#x{:X}", c as u32));
+                        }
+                        c => run.push(c),
+                    }
+                }
+                parts.extend(ebnf_string(&run));
+                match parts.len() {
+                    0 => ("''".to_string(), 3),
+                    1 => (parts.remove(0), 3),
+                    _ => (parts.join(" "), 1),
+                }
+            }
+            GrammarExpr::Class { ranges, negated } => {
+                let mut out = String::from("[");
+                if *negated {
+                    out.push('^');
+                }
+                for (start, end) in ranges {
+                    out.push_str(&ebnf_char(*start));
+                    if start != end {
+                        out.push('-');
+                        out.push_str(&ebnf_char(*end));
+                    }
+                }
+                out.push(']');
+                (out, 3)
+            }
+            GrammarExpr::Set(set) => (set.get_name().to_string(), 3),
+            GrammarExpr::Any => ("[#x0-#x10FFFF]".to_string(), 3),
+            GrammarExpr::Eof => ("/* end of input */".to_string(), 3),
+            GrammarExpr::Trivia => match self.get_trivia() {
+                Some(trivia) => (format!("{}?", ebnf_name(&trivia.name)), 2),
+                None => ("/* whitespace */".to_string(), 3),
+            },
+            GrammarExpr::Rule { name, .. } => (ebnf_name(name), 3),
+        };
+        match precedence < min {
+            true => format!("({})", text),
+            false => text,
+        }
+    }
+    /// Export a reference page with a section for each rule
+    ///
+    /// A section links the railroad diagram `<rule name>.svg` written by [GrammarExporter],
+    /// shows the rule in W3C EBNF, the pex errors raised by the rule itself, and the rules it uses and is used by.
+    /// The errors are the ones of the parser generated by [Grammar::to_rust], see [GrammarRule::get_messages].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar: Grammar = "bool = \"true\" | \"false\";".parse().unwrap();
+    /// let page = grammar.to_markdown("bool");
+    /// assert!(page.contains("## bool\n\n![bool](bool.svg)\n"));
+    /// assert!(page.contains("| `MissingString` | `false` |"));
+    /// ```
+    pub fn to_markdown(&self, title: &str) -> String {
+        self.markdown(title, GrammarRule::get_messages)
+    }
+    /// Export a reference page like [Grammar::to_markdown], with the errors of the functions generated by
+    /// [Grammar::to_rust_functions], see [GrammarRule::get_function_messages].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar = Grammar::from_abnf("sign = \"+\" / \"-\"\n").unwrap();
+    /// let page = grammar.to_markdown_functions("sign");
+    /// assert!(page.contains("| `MustBe` | `sign` |\n| `MissingCharacterRange` | `+` |"));
+    /// ```
+    pub fn to_markdown_functions(&self, title: &str) -> String {
+        self.markdown(title, GrammarRule::get_function_messages)
+    }
+    fn markdown(&self, title: &str, get_messages: fn(&GrammarRule) -> Vec<(&'static str, String)>) -> String {
+        let mut out = format!("# {}\n\n", title);
+        for rule in &self.rules {
+            writeln!(out, "- [{}](#{})", rule.name, anchor(&rule.name)).unwrap();
+        }
+        for rule in &self.rules {
+            write!(out, "\n## {}\n\n![{}]({})\n\n", rule.name, rule.name, diagram_file(&rule.name)).unwrap();
+            if rule.trivia {
+                out.push_str("Skipped by `~` between the items of the other rules.\n\n");
+            }
+            write!(out, "```ebnf\n{}```\n", self.ebnf_rule(rule)).unwrap();
+            let messages = get_messages(rule);
+            if !messages.is_empty() {
+                out.push_str("\n| Error | Message |\n|-------|---------|\n");
+                for (error, message) in messages {
+                    writeln!(out, "| `{}` | {} |", error, markdown_code(&message)).unwrap();
+                }
+            }
+            let uses = self.uses(rule);
+            if !uses.is_empty() {
+                write!(out, "\nUses: {}\n", self.rule_links(uses.iter().copied())).unwrap();
+            }
+            let used_by: Vec<&str> = self
+                .rules
+                .iter()
+                .filter(|other| self.uses(other).contains(rule.name.as_str()))
+                .map(|other| other.name.as_str())
+                .collect();
+            if !used_by.is_empty() {
+                write!(out, "\nUsed by: {}\n", self.rule_links(used_by.into_iter())).unwrap();
+            }
+        }
+        out
+    }
+    /// The links to the sections of the rules, the rules which are not defined are shown as code
+    fn rule_links<'a>(&self, names: impl Iterator<Item = &'a str>) -> String {
+        let link = |name: &str| match self.get_rule(name) {
+            Some(_) => format!("[{}](#{})", name, anchor(name)),
+            None => format!("`{}`", name),
+        };
+        names.map(link).collect::<Vec<_>>().join(", ")
+    }
+    /// The rules referenced by the rule, and the trivia rule if skipped
+    fn uses<'a>(&'a self, rule: &'a GrammarRule) -> BTreeSet<&'a str> {
+        let mut uses = BTreeSet::new();
+        rule.body.walk(&mut |expr| match expr {
+            GrammarExpr::Rule { name, .. } => {
+                uses.insert(name.as_str());
+            }
+            GrammarExpr::Trivia => {
+                if let Some(trivia) = self.get_trivia() {
+                    uses.insert(trivia.name.as_str());
+                }
+            }
+            _ => {}
+        });
+        uses
+    }
+}
+
+impl GrammarRule {
+    /// The `StopBecause` variants and messages raised by the rule itself, the referenced rules are not included
+    ///
+    /// The messages are the ones of the parser generated by [Grammar::to_rust] and of the interpreter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar: Grammar = "name = !\"_\" [a-z_]+;".parse().unwrap();
+    /// let messages = grammar.rules[0].get_messages();
+    /// assert_eq!(
+    ///     messages,
+    ///     vec![
+    ///         ("ShouldNotBe", "\"_\"".to_string()),
+    ///         ("ExpectRepeats", "at least 1".to_string()),
+    ///         ("MustBe", "[a-z_]".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn get_messages(&self) -> Vec<(&'static str, String)> {
+        let mut messages = vec![];
+        collect_messages(&self.body, false, &mut messages);
+        messages
+    }
+    /// The `StopBecause` variants and messages raised by the function generated by [Grammar::to_rust_functions]
+    ///
+    /// A failure at the start of the function expects the rule name,
+    /// and the single characters and the ranges of classes expect a `MissingCharacterRange`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar = Grammar::from_abnf("hex = \"0x\" 1*(DIGIT / %x61-66)\n").unwrap();
+    /// let messages = grammar.rules[0].get_function_messages();
+    /// assert_eq!(
+    ///     messages,
+    ///     vec![
+    ///         ("MustBe", "hex".to_string()),
+    ///         ("MissingString", "0x".to_string()),
+    ///         ("ExpectRepeats", "at least 1".to_string()),
+    ///         ("MissingCharacterRange", "'a'..='f'".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn get_function_messages(&self) -> Vec<(&'static str, String)> {
+        let mut messages = vec![("MustBe", self.name.clone())];
+        collect_messages(&self.body, true, &mut messages);
+        messages
+    }
+}
+
+/// Collect the errors of the expression, `functions` for the code of [Grammar::to_rust_functions]
+fn collect_messages(expr: &GrammarExpr, functions: bool, messages: &mut Vec<(&'static str, String)>) {
+    let message = match expr {
+        GrammarExpr::Choice(items) | GrammarExpr::Sequence(items) => {
+            items.iter().for_each(|item| collect_messages(item, functions, messages));
+            return;
+        }
+        GrammarExpr::Optional(inner) => return collect_messages(inner, functions, messages),
+        GrammarExpr::Repeat { expr: inner, min, .. } => {
+            if *min != 0 {
+                push_message(messages, ("ExpectRepeats", format!("at least {}", min)));
+            }
+            return collect_messages(inner, functions, messages);
+        }
+        GrammarExpr::Literal { text, insensitive: false } if functions && text.chars().count() == 1 => {
+            ("MissingCharacterRange", text.clone())
+        }
+        GrammarExpr::Class { ranges, negated: false } if functions => {
+            for (start, end) in ranges {
+                let range = match start == end {
+                    true => start.to_string(),
+                    false => format!("'{}'..='{}'", start, end),
+                };
+                push_message(messages, ("MissingCharacterRange", range));
+            }
+            return;
+        }
+        GrammarExpr::Positive(inner) => ("MustBe", inner.to_string()),
+        GrammarExpr::Negative(inner) => ("ShouldNotBe", inner.to_string()),
+        GrammarExpr::Literal { text, .. } => ("MissingString", text.clone()),
+        GrammarExpr::Class { .. } => ("MustBe", expr.to_string()),
+        GrammarExpr::Set(set) => ("MustBe", set.get_name().to_string()),
+        GrammarExpr::Any => ("MustBe", "ANY".to_string()),
+        GrammarExpr::Eof => ("ExpectEOF", "end of input".to_string()),
+        GrammarExpr::Trivia | GrammarExpr::Rule { .. } => return,
+    };
+    push_message(messages, message);
+}
+
+fn push_message(messages: &mut Vec<(&'static str, String)>, message: (&'static str, String)) {
+    if !messages.contains(&message) {
+        messages.push(message);
+    }
+}
+
+/// `(a (a a?)?)?`, match the item at most `count` times
+fn optional_repeat(item: &str, count: usize) -> Option<String> {
+    match count {
+        0 => None,
+        1 => Some(format!("{}?", item)),
+        _ => Some(format!("({} {})?", item, optional_repeat(item, count - 1).unwrap_or_default())),
+    }
+}
+
+/// A quoted string, split at the quotes if it contains both
+fn ebnf_string(text: &str) -> Vec<String> {
+    if text.is_empty() {
+        return vec![];
+    }
+    if !text.contains('\'') {
+        return vec![format!("'{}'", text)];
+    }
+    if !text.contains('"') {
+        return vec![format!("\"{}\"", text)];
+    }
+    let mut parts = vec![];
+    for (index, part) in text.split('"').enumerate() {
+        if index != 0 {
+            parts.push("'\"'".to_string());
+        }
+        parts.extend(ebnf_string(part));
+    }
+    parts
+}
+
+/// A character in a class, `#xN` if it is special
+fn ebnf_char(c: char) -> String {
+    match c {
+        c if c.is_control() || c.is_whitespace() || matches!(c, '[' | ']' | '^' | '-' | '#' | '\\') => {
+            format!("#x{:X}", c as u32)
+        }
+        c => c.to_string(),
+    }
+}
+
+/// The spaces of ISO EBNF names are not allowed in W3C EBNF
+fn ebnf_name(name: &str) -> String {
+    name.replace(' ', "_")
+}
+
+/// The file of the railroad diagram of the rule
+fn diagram_file(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            }
+            else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.svg", name)
+}
+
+/// The anchor of a heading on GitHub
+fn anchor(name: &str) -> String {
+    name.to_lowercase().replace(' ', "-")
+}
+
+/// An inline code in a table cell
+fn markdown_code(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+    match text.contains('`') {
+        true => format!("`` {} ``", text),
+        false => format!("`{}`", text),
+    }
+}
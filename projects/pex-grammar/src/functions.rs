@@ -0,0 +1,178 @@
+use crate::{
+    codegen::{indent, snake_name, KEYWORDS},
+    Grammar, GrammarError, GrammarExpr,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+impl Grammar {
+    /// Check the grammar and generate a parser function for each rule
+    ///
+    /// Every rule becomes `pub fn rule_name<'i>(input: ParseState<'i>) -> ParseResult<'i, &'i str>` returning the matched text,
+    /// a failure at the start of the rule expects the original rule name.
+    /// Ranges of characters use `match_char_range`, repeats use `match_repeat_m_n`, and choices use `begin_choice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pex_grammar::Grammar;
+    /// let grammar = Grammar::from_abnf("date-fullyear = 4DIGIT\n").unwrap();
+    /// let code = grammar.to_rust_functions().unwrap();
+    /// assert!(code
+    ///     .contains("pub fn date_fullyear<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {"));
+    /// assert!(code.contains(
+    ///     "match_named(input, \"date-fullyear\", |s| s.match_repeat_m_n(4, 4, |s| digit(s)))"
+    /// ));
+    /// ```
+    pub fn to_rust_functions(&self) -> Result<String, Vec<GrammarError>> {
+        let mut errors = self.check();
+        let mut names = BTreeMap::new();
+        for rule in &self.rules {
+            if let Some(old) = names.insert(function_name(&rule.name), &rule.name).filter(|old| **old != rule.name) {
+                let message = format!("rule `{}` has the same function name as `{}`", rule.name, old);
+                errors.push(GrammarError::new(message, rule.range.clone()));
+            }
+        }
+        if let Some(rule) = self.rules.iter().find(|rule| function_name(&rule.name) == "match_named") {
+            errors
+                .push(GrammarError::new(format!("rule `{}` is reserved by the generated code", rule.name), rule.range.clone()));
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let mut out = String::from("// This file is generated by pex-grammar, do not edit it by hand.\n\n");
+        out.push_str(MATCH_NAMED);
+        for rule in &self.rules {
+            writeln!(
+                out,
+                "\n/// `{}`\npub fn {}<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {{\n    match_named(input, {:?}, |s| {})\n}}",
+                rule,
+                function_name(&rule.name),
+                rule.name,
+                indent(&self.function_body(&rule.body))
+            )
+            .unwrap();
+        }
+        Ok(out)
+    }
+    /// An expression of `ParseResult<T>` with the state `s`, the value is dropped
+    fn function_body(&self, expr: &GrammarExpr) -> String {
+        match expr {
+            GrammarExpr::Choice(items) => {
+                let mut out = String::from("s.begin_choice()");
+                for item in items {
+                    write!(out, "\n    .or_else(|s| {}.map_value(()))", indent(&self.function_body(item))).unwrap();
+                }
+                out.push_str("\n    .end_choice()");
+                out
+            }
+            GrammarExpr::Sequence(items) => {
+                let mut out = String::from("s.match_fn(|s| {\n");
+                for item in items {
+                    match item {
+                        GrammarExpr::Trivia => {
+                            writeln!(out, "    let (s, _) = s.try_skip({})?;", self.trivia_function()).unwrap()
+                        }
+                        item => writeln!(out, "    let (s, _) = {}?;", indent(&self.function_body(item))).unwrap(),
+                    }
+                }
+                out.push_str("    s.finish(())\n})");
+                out
+            }
+            GrammarExpr::Optional(inner) => format!("s.match_optional(|s| {})", self.function_body(inner)),
+            GrammarExpr::Repeat { expr: inner, min, max } => {
+                let max = max.map_or("usize::MAX".to_string(), |max| max.to_string());
+                format!("s.match_repeat_m_n({}, {}, |s| {})", min, max, self.function_body(inner))
+            }
+            GrammarExpr::Positive(inner) => {
+                format!("s.match_positive(|s| {}, {:?})", self.function_body(inner), inner.to_string())
+            }
+            GrammarExpr::Negative(inner) => {
+                format!("s.match_negative(|s| {}, {:?})", self.function_body(inner), inner.to_string())
+            }
+            GrammarExpr::Literal { text, insensitive: false } if text.chars().count() == 1 => {
+                format!("s.match_char({:?})", text.chars().next().unwrap_or_default())
+            }
+            GrammarExpr::Literal { text, insensitive: false } => format!("s.match_str({:?})", text),
+            GrammarExpr::Literal { text, insensitive: true } => format!("s.match_str_insensitive({:?})", text),
+            GrammarExpr::Class { ranges, negated: false } => {
+                let range = |(start, end): &(char, char)| match start == end {
+                    true => format!("s.match_char({:?})", start),
+                    false => format!("s.match_char_range({:?}, {:?})", start, end),
+                };
+                match ranges.as_slice() {
+                    [single] => range(single),
+                    _ => {
+                        let mut out = String::from("s.begin_choice()");
+                        for item in ranges {
+                            write!(out, "\n    .or_else(|s| {})", range(item)).unwrap();
+                        }
+                        out.push_str("\n    .end_choice()");
+                        out
+                    }
+                }
+            }
+            GrammarExpr::Class { ranges, negated: true } => {
+                let patterns: Vec<String> = ranges
+                    .iter()
+                    .map(|(start, end)| match start == end {
+                        true => format!("{:?}", start),
+                        false => format!("{:?}..={:?}", start, end),
+                    })
+                    .collect();
+                format!("s.match_char_if(|c| !matches!(c, {}), {:?})", patterns.join(" | "), expr.to_string())
+            }
+            GrammarExpr::Set(set) => format!("s.match_fn(::pex::helpers::{})", set.get_name()),
+            GrammarExpr::Any => "s.match_char_any()".to_string(),
+            GrammarExpr::Eof => "s.match_eof()".to_string(),
+            GrammarExpr::Trivia => format!("s.try_skip({})", self.trivia_function()),
+            GrammarExpr::Rule { name, .. } => format!("{}(s)", function_name(name)),
+        }
+    }
+    /// The function skipped by `~`
+    fn trivia_function(&self) -> String {
+        match self.get_trivia() {
+            Some(trivia) => function_name(&trivia.name),
+            None => "::pex::helpers::whitespace".to_string(),
+        }
+    }
+}
+
+/// The helper shared by the generated functions
+const MATCH_NAMED: &str =
+    "/// Match a rule and return the matched text, a failure at the start of the rule expects the rule name
+fn match_named<'i, T>(
+    input: ::pex::ParseState<'i>,
+    name: &'static str,
+    parse: impl FnOnce(::pex::ParseState<'i>) -> ::pex::ParseResult<'i, T>,
+) -> ::pex::ParseResult<'i, &'i str> {
+    match input.match_rule(name, parse) {
+        ::pex::ParseResult::Pending(state, _) => state.finish(&input.residual[..state.start_offset - input.start_offset]),
+        ::pex::ParseResult::Stop(error) if error.is_fatal() || error.range().start > input.start_offset => {
+            ::pex::ParseResult::Stop(error)
+        }
+        ::pex::ParseResult::Stop(_) => ::pex::StopBecause::must_be(name, input.start_offset)?,
+    }
+}
+";
+
+/// `date-fullyear` to `date_fullyear`, and `type` to `r#type`
+fn function_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c
+            }
+            else {
+                '_'
+            }
+        })
+        .collect();
+    let name = snake_name(&name);
+    match name.as_str() {
+        "self" | "super" | "crate" => format!("{}_", name),
+        s if KEYWORDS.contains(&s) => format!("r#{}", name),
+        s if s.starts_with(|c: char| c.is_ascii_digit()) => format!("_{}", name),
+        _ => name,
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{Grammar, GrammarError, GrammarExpr};
+use pex::{helpers::CharactersTrie, ParseResult, ParseState, StopBecause};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    ops::Range,
+};
+
+/// Run a grammar at runtime without generating code, for prototyping.
+///
+/// The literals, rule names and messages are leaked because the errors of pex only hold static strings.
+/// Each [GrammarInterpreter::new] leaks the distinct texts of the grammar once, no more than the size of the grammar,
+/// so keep or clone the interpreter, and create it again only when the grammar changes.
+///
+/// # Examples
+///
+/// ```
+/// # use pex::ParseState;
+/// # use pex_grammar::{Grammar, GrammarInterpreter};
+/// let grammar: Grammar =
+///     "list = '[' ~ (item (~ ',' ~ item)*)? ~ ']'; item = [0-9]+;".parse().unwrap();
+/// let interpreter = GrammarInterpreter::new(&grammar).unwrap();
+/// let tree = interpreter.parse_text("list", "[1, 23]").unwrap();
+/// assert_eq!(tree.to_text("[1, 23]"), "list 0..7\n  item 1..2 \"1\"\n  item 4..6 \"23\"\n");
+/// assert!(interpreter.parse_text("list", "[1,]").is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrammarInterpreter {
+    rules: Vec<RuleNode>,
+    names: BTreeMap<String, usize>,
+    trivia: Option<usize>,
+}
+
+/// A node of the parse tree, built for every matched rule
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseTree {
+    /// The name of the rule
+    pub rule: &'static str,
+    /// The matched range of the input
+    pub span: Range<usize>,
+    /// The rules matched inside
+    pub children: Vec<ParseTree>,
+}
+
+#[derive(Clone, Debug)]
+struct RuleNode {
+    name: &'static str,
+    body: Node,
+}
+
+/// A grammar expression with the static strings and the resolved rules
+#[derive(Clone, Debug)]
+enum Node {
+    Choice(Vec<Node>),
+    Sequence(Vec<Node>),
+    Optional(Box<Node>),
+    Repeat { node: Box<Node>, min: usize, max: usize },
+    Positive(Box<Node>, &'static str),
+    Negative(Box<Node>, &'static str),
+    Literal(&'static str),
+    Insensitive(&'static str),
+    Class { ranges: Vec<(char, char)>, negated: bool, message: &'static str },
+    Set(CharactersTrie),
+    Any,
+    Eof,
+    Trivia,
+    Rule(usize),
+}
+
+impl GrammarInterpreter {
+    /// Check the grammar and prepare the rules
+    pub fn new(grammar: &Grammar) -> Result<Self, Vec<GrammarError>> {
+        let errors = grammar.check();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let names: BTreeMap<String, usize> =
+            grammar.rules.iter().enumerate().map(|(index, rule)| (rule.name.clone(), index)).collect();
+        let mut interner = Interner::default();
+        let rules = grammar
+            .rules
+            .iter()
+            .map(|rule| RuleNode { name: interner.intern(&rule.name), body: interner.lower(&rule.body, &names) })
+            .collect();
+        let trivia = grammar.rules.iter().position(|rule| rule.trivia);
+        Ok(Self { rules, names, trivia })
+    }
+    /// Check if the rule is defined
+    pub fn has_rule(&self, rule: &str) -> bool {
+        self.names.contains_key(rule)
+    }
+    /// Parse the rule at the state
+    pub fn parse<'i>(&self, rule: &str, input: ParseState<'i>) -> ParseResult<'i, ParseTree> {
+        match self.names.get(rule) {
+            Some(index) => self.rule(*index, input),
+            None => StopBecause::custom_error("undefined rule", input.start_offset, input.start_offset)?,
+        }
+    }
+    /// Parse the whole text by the rule
+    pub fn parse_text(&self, rule: &str, text: &str) -> Result<ParseTree, StopBecause> {
+        pex::helpers::make_from_str(ParseState::new(text), |s| self.parse(rule, s))
+    }
+    fn rule<'i>(&self, index: usize, input: ParseState<'i>) -> ParseResult<'i, ParseTree> {
+        let rule = &self.rules[index];
+        let (state, children) = input.match_rule(rule.name, |s| self.node(&rule.body, s))?;
+        state.finish(ParseTree { rule: rule.name, span: input.start_offset..state.start_offset, children })
+    }
+    /// Match the node, returns the trees of the matched rules
+    fn node<'i>(&self, node: &Node, state: ParseState<'i>) -> ParseResult<'i, Vec<ParseTree>> {
+        match node {
+            Node::Choice(items) => {
+                let mut choice = state.begin_choice();
+                for item in items {
+                    choice = choice.or_else(|s| self.node(item, s));
+                }
+                choice.end_choice()
+            }
+            Node::Sequence(items) => {
+                let mut state = state;
+                let mut trees = vec![];
+                for item in items {
+                    let (next, more) = self.node(item, state)?;
+                    trees.extend(more);
+                    state = next;
+                }
+                state.finish(trees)
+            }
+            Node::Optional(node) => state.match_optional(|s| self.node(node, s)).map_inner(|trees| trees.unwrap_or_default()),
+            Node::Repeat { node, min, max } => state
+                .match_repeat_m_n(*min, *max, |s| self.node(node, s))
+                .map_inner(|trees| trees.into_iter().flatten().collect()),
+            Node::Positive(node, message) => state.match_positive(|s| self.node(node, s), message).map_value(vec![]),
+            Node::Negative(node, message) => state.match_negative(|s| self.node(node, s), message).map_value(vec![]),
+            Node::Literal(text) => state.match_str(text).map_value(vec![]),
+            Node::Insensitive(text) => state.match_str_insensitive(text).map_value(vec![]),
+            Node::Class { ranges, negated, message } => {
+                let inside = |c: char| ranges.iter().any(|(start, end)| (*start..=*end).contains(&c));
+                state.match_char_if(|c| inside(c) != *negated, message).map_value(vec![])
+            }
+            Node::Set(trie) => state.match_fn(*trie).map_value(vec![]),
+            Node::Any => state.match_char_any().map_value(vec![]),
+            Node::Eof => state.match_eof().map_value(vec![]),
+            Node::Trivia => match self.trivia {
+                Some(index) => state.try_skip(|s| self.rule(index, s)).map_value(vec![]),
+                None => state.try_skip(pex::helpers::whitespace).map_value(vec![]),
+            },
+            Node::Rule(index) => self.rule(*index, state).map_inner(|tree| vec![tree]),
+        }
+    }
+}
+
+impl ParseTree {
+    /// Print the tree with indents, the leaves show the matched text
+    pub fn to_text(&self, input: &str) -> String {
+        let mut out = String::new();
+        self.write_text(input, &mut out, 0);
+        out
+    }
+    fn write_text(&self, input: &str, out: &mut String, depth: usize) {
+        let _ = write!(out, "{:indent$}{} {}..{}", "", self.rule, self.span.start, self.span.end, indent = depth * 2);
+        match input.get(self.span.clone()) {
+            Some(text) if self.children.is_empty() => {
+                let _ = writeln!(out, " {:?}", text);
+            }
+            _ => out.push('\n'),
+        }
+        for child in &self.children {
+            child.write_text(input, out, depth + 1);
+        }
+    }
+}
+
+/// The texts leaked while loading one grammar, a text used many times is leaked once
+#[derive(Default)]
+struct Interner {
+    texts: BTreeSet<&'static str>,
+}
+
+impl Interner {
+    fn intern(&mut self, text: &str) -> &'static str {
+        match self.texts.get(text) {
+            Some(text) => text,
+            None => {
+                let text: &'static str = Box::leak(text.to_string().into_boxed_str());
+                self.texts.insert(text);
+                text
+            }
+        }
+    }
+    fn lower(&mut self, expr: &GrammarExpr, names: &BTreeMap<String, usize>) -> Node {
+        match expr {
+            GrammarExpr::Choice(items) => Node::Choice(items.iter().map(|item| self.lower(item, names)).collect()),
+            GrammarExpr::Sequence(items) => Node::Sequence(items.iter().map(|item| self.lower(item, names)).collect()),
+            GrammarExpr::Optional(inner) => Node::Optional(Box::new(self.lower(inner, names))),
+            GrammarExpr::Repeat { expr, min, max } => {
+                Node::Repeat { node: Box::new(self.lower(expr, names)), min: *min, max: max.unwrap_or(usize::MAX) }
+            }
+            GrammarExpr::Positive(inner) => Node::Positive(Box::new(self.lower(inner, names)), self.intern(&inner.to_string())),
+            GrammarExpr::Negative(inner) => Node::Negative(Box::new(self.lower(inner, names)), self.intern(&inner.to_string())),
+            GrammarExpr::Literal { text, insensitive: false } => Node::Literal(self.intern(text)),
+            GrammarExpr::Literal { text, insensitive: true } => Node::Insensitive(self.intern(text)),
+            GrammarExpr::Class { ranges, negated } => {
+                Node::Class { ranges: ranges.clone(), negated: *negated, message: self.intern(&expr.to_string()) }
+            }
+            GrammarExpr::Set(set) => Node::Set(set.get_trie()),
+            GrammarExpr::Any => Node::Any,
+            GrammarExpr::Eof => Node::Eof,
+            GrammarExpr::Trivia => Node::Trivia,
+            // the names are checked before
+            GrammarExpr::Rule { name, .. } => Node::Rule(names[name]),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+#![deny(missing_debug_implementations, missing_copy_implementations)]
+#![warn(missing_docs, rustdoc::missing_crate_level_docs)]
+#![doc = include_str!("../readme.md")]
+#![doc(html_logo_url = "https://avatars.githubusercontent.com/u/91894079")]
+#![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/91894079")]
+
+mod abnf;
+mod ast;
+mod check;
+mod codegen;
+mod compiler;
+mod ebnf;
+mod error;
+mod export;
+mod functions;
+mod interpreter;
+mod parser;
+mod pest;
+mod railroad;
+
+pub use crate::{
+    ast::{CharacterSet, Grammar, GrammarExpr, GrammarRule},
+    compiler::{read_grammar, GrammarCompiler},
+    error::GrammarError,
+    export::GrammarExporter,
+    interpreter::{GrammarInterpreter, ParseTree},
+    pest::PestImport,
+};
@@ -0,0 +1,154 @@
+//! The `pex` command, run a grammar file over an input file without generating code.
+use pex::{Diagnostic, DiagnosticRenderer, ParseContext, ParseResult, ParseState, Suggester};
+use pex_grammar::{read_grammar, GrammarError, GrammarExporter, GrammarInterpreter};
+use std::{io::IsTerminal, path::Path, process::ExitCode};
+
+const USAGE: &str = "usage: pex <GRAMMAR> <INPUT> [--rule NAME] [--trace]
+       pex <GRAMMAR> --export DIR
+
+Parse the input by the grammar in the ygg notation, print the parse tree or the errors.
+A `.pest`, `.abnf` or `.ebnf` grammar is converted first, the constructs of pest which can not be translated are warned.
+
+    --rule NAME    the rule to start with, the first rule not marked by `@trivia` by default
+    --trace        print every rule call with its offsets before the result
+    --export DIR   write the grammar as W3C EBNF, a Markdown reference and a railroad svg for each rule
+";
+
+#[derive(Debug, Default)]
+struct Options {
+    grammar: String,
+    input: String,
+    rule: Option<String>,
+    trace: bool,
+    export: Option<String>,
+    help: bool,
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1)) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut files = vec![];
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                options.help = true;
+                return Ok(options);
+            }
+            "--trace" => options.trace = true,
+            "--rule" => options.rule = Some(args.next().ok_or("missing the name after `--rule`")?),
+            "--export" => options.export = Some(args.next().ok_or("missing the directory after `--export`")?),
+            flag if flag.starts_with("--") => return Err(format!("unknown flag `{}`\n\n{}", flag, USAGE)),
+            _ => files.push(arg),
+        }
+    }
+    match <[String; 2]>::try_from(files) {
+        Ok([grammar, input]) if options.export.is_none() => {
+            options.grammar = grammar;
+            options.input = input;
+            Ok(options)
+        }
+        Ok(_) => Err(USAGE.to_string()),
+        Err(files) => match <[String; 1]>::try_from(files) {
+            Ok([grammar]) if options.export.is_some() => {
+                options.grammar = grammar;
+                Ok(options)
+            }
+            _ => Err(USAGE.to_string()),
+        },
+    }
+}
+
+fn run(args: impl Iterator<Item = String>) -> Result<ExitCode, String> {
+    let options = parse_options(args)?;
+    if options.help {
+        print!("{}", USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(output) = &options.export {
+        let files = GrammarExporter::new(&options.grammar)
+            .with_output(output)
+            .export()
+            .map_err(|errors| errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))?;
+        files.iter().for_each(|file| println!("{}", file.display()));
+        return Ok(ExitCode::SUCCESS);
+    }
+    let read = |path: &str| std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e));
+    let text = read(&options.grammar)?;
+    let located = |e: GrammarError| e.with_source(&text).with_file(&options.grammar).to_string();
+    let (grammar, warnings) = read_grammar(Path::new(&options.grammar), &text, true)
+        .map_err(|errors| errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))?;
+    warnings.iter().for_each(|e| eprintln!("warning: {}", e));
+    let interpreter =
+        GrammarInterpreter::new(&grammar).map_err(|errors| errors.into_iter().map(located).collect::<Vec<_>>().join("\n"))?;
+    let rule = match options.rule {
+        Some(rule) if interpreter.has_rule(&rule) => rule,
+        Some(rule) => return Err(format!("undefined rule `{}`", rule)),
+        None => grammar.rules.iter().find(|rule| !rule.trivia).ok_or("the grammar has no rule")?.name.clone(),
+    };
+    let input = read(&options.input)?;
+    let context = match options.trace {
+        true => ParseContext::default().with_trace(),
+        false => ParseContext::default(),
+    };
+    let result = match interpreter.parse(&rule, ParseState::new(&input).with_context(&context)) {
+        ParseResult::Pending(state, tree) => state.match_eof().map_value(tree),
+        ParseResult::Stop(error) => ParseResult::Stop(error),
+    };
+    if let Some(trace) = context.take_trace() {
+        print!("{}", trace.to_text());
+    }
+    match result {
+        ParseResult::Pending(_, tree) => {
+            print!("{}", tree.to_text(&input));
+            Ok(ExitCode::SUCCESS)
+        }
+        ParseResult::Stop(error) => {
+            let failure = context.merge_error(error).with_suggestions(&input, &Suggester::default());
+            let renderer = DiagnosticRenderer::new(&input, &options.input).with_color(std::io::stderr().is_terminal());
+            eprint!("{}", renderer.render(&Diagnostic::from(&failure)));
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Result<Options, String> {
+        parse_options(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_flags() {
+        let parsed = options(&["json.ygg", "--trace", "input.json", "--rule", "value"]).unwrap();
+        assert_eq!((parsed.grammar.as_str(), parsed.input.as_str()), ("json.ygg", "input.json"));
+        assert_eq!((parsed.rule.as_deref(), parsed.trace, parsed.help), (Some("value"), true, false));
+        let parsed = options(&["--export", "docs", "json.ygg"]).unwrap();
+        assert_eq!((parsed.grammar.as_str(), parsed.export.as_deref()), ("json.ygg", Some("docs")));
+        // the help wins over the other arguments
+        assert!(options(&["--help"]).unwrap().help);
+        assert!(options(&["json.ygg", "-h", "--unknown"]).unwrap().help);
+    }
+
+    #[test]
+    fn reject_arguments() {
+        let error = |args: &[&str]| options(args).map(|_| ()).unwrap_err();
+        assert_eq!(error(&[]), USAGE);
+        assert_eq!(error(&["json.ygg"]), USAGE);
+        assert_eq!(error(&["json.ygg", "input.json", "extra.json"]), USAGE);
+        assert_eq!(error(&["json.ygg", "input.json", "--export", "docs"]), USAGE);
+        assert_eq!(error(&["json.ygg", "--export"]), "missing the directory after `--export`");
+        assert_eq!(error(&["json.ygg", "input.json", "--rule"]), "missing the name after `--rule`");
+        assert!(error(&["json.ygg", "input.json", "--color"]).starts_with("unknown flag `--color`\n\nusage: pex"));
+    }
+}
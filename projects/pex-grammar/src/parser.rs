@@ -0,0 +1,292 @@
+use crate::{CharacterSet, Grammar, GrammarError, GrammarExpr, GrammarRule};
+use pex::{ParseContext, ParseResult, ParseState, StopBecause};
+use std::str::FromStr;
+
+impl FromStr for Grammar {
+    type Err = GrammarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_text(s, grammar)
+    }
+}
+
+/// Run the parser over the text, a custom error takes priority over the furthest failure
+pub(crate) fn parse_text<T>(
+    s: &str,
+    parser: impl for<'i> FnOnce(ParseState<'i>) -> ParseResult<'i, T>,
+) -> Result<T, GrammarError> {
+    let context = ParseContext::default();
+    let state = ParseState::new(s).with_context(&context);
+    match parser(state) {
+        ParseResult::Pending(_, value) => Ok(value),
+        ParseResult::Stop(error) => {
+            let failure = context.merge_error(error);
+            let custom = failure.expected.iter().find_map(|error| match error {
+                StopBecause::Custom(custom) => Some(custom),
+                _ => None,
+            });
+            let error = match custom {
+                Some(custom) => GrammarError::new(custom.message, custom.start..custom.end),
+                // the position is shown as line and column instead
+                None => GrammarError::new(failure.get_expected_message(), failure.position..failure.position),
+            };
+            Err(error.with_source(s))
+        }
+    }
+}
+
+fn grammar(state: ParseState) -> ParseResult<Grammar> {
+    let (state, rules) = state.match_repeats(|s| s.skip(ignore).match_fn(rule))?;
+    let (state, _) = state.skip(ignore).match_eof()?;
+    state.finish(Grammar { rules })
+}
+
+/// Skip whitespace, `// line comment` and `/* block comment */`
+pub(crate) fn ignore(state: ParseState) -> ParseResult<()> {
+    let mut state = state;
+    loop {
+        let trimmed = state.residual.trim_start();
+        let mut offset = state.residual.len() - trimmed.len();
+        if let Some(rest) = trimmed.strip_prefix("//") {
+            offset += 2 + rest.find('\n').unwrap_or(rest.len());
+        }
+        else if let Some(rest) = trimmed.strip_prefix("/*") {
+            match rest.find("*/") {
+                Some(end) => offset += end + 4,
+                None => StopBecause::missing_string("*/", state.end_offset())?,
+            }
+        }
+        if offset == 0 {
+            return state.finish(());
+        }
+        state = state.advance(offset);
+    }
+}
+
+fn rule(state: ParseState) -> ParseResult<GrammarRule> {
+    let (state, trivia) = state.match_optional(|s| {
+        let (s, _) = s.match_str("@trivia")?;
+        s.skip(ignore).finish(())
+    })?;
+    let start = state.start_offset;
+    let (state, name) = identifier(state)?;
+    let range = start..state.start_offset;
+    let (state, _) = state.skip(ignore).match_char('=')?;
+    let (state, body) = state.skip(ignore).match_fn(choice)?;
+    let (state, _) = state.skip(ignore).match_char(';')?;
+    state.finish(GrammarRule { name: name.to_string(), trivia: trivia.is_some(), body, range })
+}
+
+pub(crate) fn identifier<'i>(state: ParseState<'i>) -> ParseResult<'i, &'i str> {
+    state.match_char_if(|c| c.is_ascii_alphabetic() || c == '_', "IDENTIFIER")?;
+    state.match_str_if(|c| c.is_ascii_alphanumeric() || c == '_', "IDENTIFIER")
+}
+
+/// `a | b`, a leading `|` is allowed
+fn choice(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, _) = state.match_optional(|s| s.match_char('|'))?;
+    let (state, first) = state.skip(ignore).match_fn(sequence)?;
+    let (state, rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char('|')?;
+        s.skip(ignore).match_fn(sequence)
+    })?;
+    if rest.is_empty() {
+        return state.finish(first);
+    }
+    let mut items = Vec::with_capacity(rest.len() + 1);
+    for item in std::iter::once(first).chain(rest) {
+        match item {
+            GrammarExpr::Choice(inner) => items.extend(inner),
+            item => items.push(item),
+        }
+    }
+    state.finish(GrammarExpr::Choice(items))
+}
+
+fn sequence(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, first) = prefix(state)?;
+    let (state, rest) = state.match_repeats(|s| s.skip(ignore).match_fn(prefix))?;
+    if rest.is_empty() {
+        return state.finish(first);
+    }
+    let mut items = Vec::with_capacity(rest.len() + 1);
+    for item in std::iter::once(first).chain(rest) {
+        match item {
+            GrammarExpr::Sequence(inner) => items.extend(inner),
+            item => items.push(item),
+        }
+    }
+    state.finish(GrammarExpr::Sequence(items))
+}
+
+/// `&a` or `!a`
+fn prefix(state: ParseState) -> ParseResult<GrammarExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let (s, _) = s.match_char('&')?;
+            s.skip(ignore).match_fn(prefix).map_inner(|e| GrammarExpr::Positive(Box::new(e)))
+        })
+        .or_else(|s| {
+            let (s, _) = s.match_char('!')?;
+            s.skip(ignore).match_fn(prefix).map_inner(|e| GrammarExpr::Negative(Box::new(e)))
+        })
+        .or_else(suffix)
+        .end_choice()
+}
+
+/// `a?`, `a*`, `a+` or `a{m,n}`
+fn suffix(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, mut expr) = primary(state)?;
+    let (state, operators) = state.match_repeats(suffix_operator)?;
+    for (min, max) in operators {
+        expr = match (min, max) {
+            (0, Some(1)) => GrammarExpr::Optional(Box::new(expr)),
+            (min, max) => GrammarExpr::Repeat { expr: Box::new(expr), min, max },
+        }
+    }
+    state.finish(expr)
+}
+
+pub(crate) fn suffix_operator(state: ParseState) -> ParseResult<(usize, Option<usize>)> {
+    state
+        .begin_choice()
+        .or_else(|s| s.match_char('?').map_value((0, Some(1))))
+        .or_else(|s| s.match_char('*').map_value((0, None)))
+        .or_else(|s| s.match_char('+').map_value((1, None)))
+        .or_else(bounds)
+        .end_choice()
+}
+
+/// `{n}`, `{m,}`, `{,n}` or `{m,n}`
+fn bounds(state: ParseState) -> ParseResult<(usize, Option<usize>)> {
+    let start = state.start_offset;
+    let (state, _) = state.match_char('{')?;
+    let (state, min) = state.skip(ignore).match_optional(number)?;
+    let (state, comma) = state.skip(ignore).match_optional(|s| s.match_char(','))?;
+    let (state, max) = match comma {
+        Some(_) => state.skip(ignore).match_optional(number)?,
+        None if min.is_some() => (state, min),
+        None => StopBecause::must_be("NUMBER", state.start_offset)?,
+    };
+    let (state, _) = state.skip(ignore).match_char('}')?;
+    let min = min.unwrap_or(0);
+    if max.is_some_and(|max| max < min) {
+        StopBecause::custom_error("the maximum is less than the minimum", start, state.start_offset)?
+    }
+    state.finish((min, max))
+}
+
+fn number(state: ParseState) -> ParseResult<usize> {
+    let (next, digits) = state.match_str_if(|c| c.is_ascii_digit(), "NUMBER")?;
+    match digits.parse() {
+        Ok(n) => next.finish(n),
+        Err(_) => StopBecause::custom_error("the number is too large", state.start_offset, next.start_offset)?,
+    }
+}
+
+fn primary(state: ParseState) -> ParseResult<GrammarExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let (s, _) = s.match_char('(')?;
+            let (s, expr) = s.skip(ignore).match_fn(choice)?;
+            let (s, _) = s.skip(ignore).match_char(')')?;
+            s.finish(expr)
+        })
+        .or_else(literal)
+        .or_else(class)
+        .or_else(|s| s.match_char('$').map_value(GrammarExpr::Eof))
+        .or_else(|s| s.match_char('~').map_value(GrammarExpr::Trivia))
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, name) = identifier(s)?;
+            match name {
+                "ANY" => s.finish(GrammarExpr::Any),
+                "XID_START" => s.finish(GrammarExpr::Set(CharacterSet::XidStart)),
+                "XID_CONTINUE" => s.finish(GrammarExpr::Set(CharacterSet::XidContinue)),
+                _ => s.finish(GrammarExpr::Rule { name: name.to_string(), range: start..s.start_offset }),
+            }
+        })
+        .end_choice()
+}
+
+/// `"text"`, `'text'`, or `i"text"` ignoring the ascii case
+fn literal(state: ParseState) -> ParseResult<GrammarExpr> {
+    let (state, insensitive) = state.match_optional(|s| s.match_char('i'))?;
+    let (mut state, quote) = state.match_char_if(|c| c == '"' || c == '\'', "STRING")?;
+    let mut text = String::new();
+    loop {
+        match state.residual.chars().next() {
+            None | Some('\n') => StopBecause::missing_character(quote, state.start_offset)?,
+            Some(c) if c == quote => {
+                return state.advance(c).finish(GrammarExpr::Literal { text, insensitive: insensitive.is_some() });
+            }
+            Some('\\') => {
+                let (next, c) = escape(state)?;
+                text.push(c);
+                state = next;
+            }
+            Some(c) => {
+                text.push(c);
+                state = state.advance(c);
+            }
+        }
+    }
+}
+
+/// `[a-z_]`, or `[^"]` for the characters out of the ranges
+fn class(state: ParseState) -> ParseResult<GrammarExpr> {
+    let start = state.start_offset;
+    let (state, _) = state.match_char('[')?;
+    let (mut state, negated) = state.match_optional(|s| s.match_char('^'))?;
+    let mut ranges = vec![];
+    while !state.residual.starts_with(']') {
+        let (next, first) = class_char(state)?;
+        let (next, last) = match next.residual.strip_prefix('-') {
+            Some(rest) if !rest.starts_with(']') => class_char(next.advance('-'))?,
+            _ => (next, first),
+        };
+        if last < first {
+            StopBecause::custom_error("the character range is out of order", state.start_offset, next.start_offset)?
+        }
+        ranges.push((first, last));
+        state = next;
+    }
+    let state = state.advance(']');
+    if ranges.is_empty() {
+        StopBecause::custom_error("the character class is empty", start, state.start_offset)?
+    }
+    state.finish(GrammarExpr::Class { ranges, negated: negated.is_some() })
+}
+
+fn class_char(state: ParseState) -> ParseResult<char> {
+    match state.residual.chars().next() {
+        None | Some('\n') => StopBecause::missing_character(']', state.start_offset)?,
+        Some('\\') => escape(state),
+        Some(c) => state.advance(c).finish(c),
+    }
+}
+
+/// `\n`, `\r`, `\t`, `\0`, `\u{FFFF}`, or the escaped character itself
+pub(crate) fn escape(state: ParseState) -> ParseResult<char> {
+    let start = state.start_offset;
+    let (state, _) = state.match_char('\\')?;
+    let (state, c) = state.match_char_any()?;
+    match c {
+        'n' => state.finish('\n'),
+        'r' => state.finish('\r'),
+        't' => state.finish('\t'),
+        '0' => state.finish('\0'),
+        'u' => {
+            let (state, _) = state.match_char('{')?;
+            let (state, hex) = state.match_str_if(|c| c.is_ascii_hexdigit(), "HEX")?;
+            let (state, _) = state.match_char('}')?;
+            match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                Some(c) => state.finish(c),
+                None => StopBecause::custom_error("invalid unicode escape", start, state.start_offset)?,
+            }
+        }
+        c => state.finish(c),
+    }
+}
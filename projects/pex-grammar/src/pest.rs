@@ -0,0 +1,525 @@
+use crate::{
+    parser::{escape, identifier, ignore, parse_text, suffix_operator},
+    CharacterSet, Grammar, GrammarError, GrammarExpr, GrammarRule,
+};
+use pex::{ParseResult, ParseState, StopBecause};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+/// A pest grammar converted to the ygg notation
+///
+/// # Examples
+///
+/// ```
+/// # use pex_grammar::Grammar;
+/// let import = Grammar::from_pest(
+///     "list = { \"[\" ~ (int ~ (\",\" ~ int)*)? ~ \"]\" }
+/// int = @{ ASCII_DIGIT+ }
+/// WHITESPACE = _{ \" \" }",
+/// )
+/// .unwrap();
+/// assert!(import.unsupported.is_empty());
+/// assert_eq!(
+///     import.grammar.rules[0].to_string(),
+///     "list = \"[\" ~ (int ~ (\",\" ~ int (~ \",\" ~ int)*)?)? ~ \"]\";"
+/// );
+/// assert_eq!(import.grammar.rules[3].to_string(), "@trivia trivia = WHITESPACE*;");
+/// assert!(import.grammar.to_rust().is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PestImport {
+    /// The converted grammar
+    pub grammar: Grammar,
+    /// The constructs which can not be translated, at their ranges in the pest grammar
+    pub unsupported: Vec<GrammarError>,
+}
+
+#[derive(Clone, Debug)]
+struct PestRule {
+    name: String,
+    /// `_`, `@`, `$` or `!`
+    modifier: Option<char>,
+    body: PestExpr,
+    range: Range<usize>,
+}
+
+#[derive(Clone, Debug)]
+enum PestExpr {
+    Choice(Vec<PestExpr>),
+    Sequence(Vec<PestExpr>),
+    Optional(Box<PestExpr>),
+    Repeat {
+        expr: Box<PestExpr>,
+        min: usize,
+        max: Option<usize>,
+    },
+    Positive(Box<PestExpr>),
+    Negative(Box<PestExpr>),
+    Literal {
+        text: String,
+        insensitive: bool,
+    },
+    Range(char, char),
+    /// A rule or a builtin, `PUSH(a)` has the argument
+    Ident {
+        name: String,
+        argument: Option<Box<PestExpr>>,
+        range: Range<usize>,
+    },
+    /// `#tag = a`
+    Tag {
+        tag: String,
+        expr: Box<PestExpr>,
+        range: Range<usize>,
+    },
+}
+
+struct PestConverter<'a> {
+    names: BTreeSet<&'a str>,
+    /// The name of the generated trivia rule, if `WHITESPACE` or `COMMENT` is defined
+    trivia: Option<String>,
+    unsupported: Vec<GrammarError>,
+}
+
+impl Grammar {
+    /// Convert a pest grammar, fails only on syntax errors
+    ///
+    /// The implicit whitespace of pest becomes `~` between the items and repeats of non-atomic rules,
+    /// skipping a generated `@trivia` rule of `WHITESPACE` and `COMMENT`.
+    /// The builtin rules become literals, classes and character sets,
+    /// the stack operations, the tags and other builtins are listed in `unsupported`.
+    pub fn from_pest(text: &str) -> Result<PestImport, GrammarError> {
+        let rules = parse_text(text, grammar)?;
+        let mut converter =
+            PestConverter { names: rules.iter().map(|rule| rule.name.as_str()).collect(), trivia: None, unsupported: vec![] };
+        let skipped: Vec<GrammarExpr> = ["WHITESPACE", "COMMENT"]
+            .iter()
+            .filter(|name| converter.names.contains(*name))
+            .map(|name| GrammarExpr::rule(*name))
+            .collect();
+        if !skipped.is_empty() {
+            let mut name = "trivia".to_string();
+            while converter.names.contains(name.as_str()) {
+                name.push('_');
+            }
+            converter.trivia = Some(name);
+        }
+        let atomic = converter.atomicity(&rules);
+        let mut grammar = Grammar::default();
+        for (rule, atomic) in rules.iter().zip(atomic) {
+            let body = match &rule.body {
+                // the rule always starts at the start of input when parsed from a string
+                PestExpr::Sequence(items) if items.first().is_some_and(|item| item.is_ident("SOI")) => {
+                    let items: Vec<GrammarExpr> = items[1..].iter().map(|item| converter.convert(item, atomic)).collect();
+                    converter.join(items, atomic)
+                }
+                body => converter.convert(body, atomic),
+            };
+            grammar.rules.push(GrammarRule { name: rule.name.clone(), trivia: false, body, range: rule.range.clone() });
+        }
+        if let Some(name) = converter.trivia {
+            let body = match <[GrammarExpr; 1]>::try_from(skipped) {
+                Ok([rule]) => rule,
+                Err(both) => GrammarExpr::Choice(both),
+            };
+            let body = GrammarExpr::Repeat { expr: Box::new(body), min: 0, max: None };
+            grammar.rules.push(GrammarRule { name, trivia: true, body, range: 0..0 });
+        }
+        Ok(PestImport { grammar, unsupported: converter.unsupported })
+    }
+}
+
+impl<'a> PestConverter<'a> {
+    /// Find the atomicity of each rule, a rule without its own modifier inherits it from the callers
+    ///
+    /// A rule called both inside and outside atomic rules is listed, and converted as non-atomic.
+    fn atomicity(&mut self, rules: &'a [PestRule]) -> Vec<bool> {
+        const NON_ATOMIC: u8 = 1;
+        const ATOMIC: u8 = 2;
+        let index: BTreeMap<&str, usize> = rules.iter().enumerate().map(|(i, rule)| (rule.name.as_str(), i)).collect();
+        let own: Vec<Option<u8>> = rules
+            .iter()
+            .map(|rule| match rule.modifier {
+                Some('@' | '$') => Some(ATOMIC),
+                Some('!') => Some(NON_ATOMIC),
+                // pest skips them inside an atomic state
+                _ if rule.name == "WHITESPACE" || rule.name == "COMMENT" => Some(ATOMIC),
+                _ => None,
+            })
+            .collect();
+        let calls: Vec<Vec<usize>> = rules
+            .iter()
+            .map(|rule| {
+                let mut calls = vec![];
+                rule.body.calls(&mut calls);
+                calls.into_iter().filter_map(|name| index.get(name).copied()).collect()
+            })
+            .collect();
+        let called: BTreeSet<usize> = calls.iter().flatten().copied().collect();
+        let mut contexts = vec![0u8; rules.len()];
+        let mut stack = vec![];
+        for (i, own) in own.iter().enumerate() {
+            match own {
+                Some(context) => contexts[i] = *context,
+                // the entry rules are non-atomic
+                None if !called.contains(&i) => contexts[i] = NON_ATOMIC,
+                None => continue,
+            }
+            stack.push(i);
+        }
+        loop {
+            while let Some(caller) = stack.pop() {
+                for callee in &calls[caller] {
+                    let merged = contexts[*callee] | contexts[caller];
+                    if own[*callee].is_none() && merged != contexts[*callee] {
+                        contexts[*callee] = merged;
+                        stack.push(*callee);
+                    }
+                }
+            }
+            // the cycles which are not called from the outside
+            match contexts.iter().position(|context| *context == 0) {
+                Some(i) => {
+                    contexts[i] = NON_ATOMIC;
+                    stack.push(i);
+                }
+                None => break,
+            }
+        }
+        for (rule, context) in rules.iter().zip(&contexts) {
+            if *context == NON_ATOMIC | ATOMIC {
+                let message = format!("rule `{}` is called inside and outside atomic rules, it skips whitespace", rule.name);
+                self.unsupported.push(GrammarError::new(message, rule.range.clone()));
+            }
+        }
+        contexts.into_iter().map(|context| context == ATOMIC).collect()
+    }
+    fn convert(&mut self, expr: &PestExpr, atomic: bool) -> GrammarExpr {
+        match expr {
+            PestExpr::Choice(items) => GrammarExpr::Choice(items.iter().map(|item| self.convert(item, atomic)).collect()),
+            PestExpr::Sequence(items) => {
+                let items = items.iter().map(|item| self.convert(item, atomic)).collect();
+                self.join(items, atomic)
+            }
+            PestExpr::Optional(inner) => GrammarExpr::Optional(Box::new(self.convert(inner, atomic))),
+            PestExpr::Repeat { expr: inner, min, max } => {
+                let item = self.convert(inner, atomic);
+                self.repeat(item, *min, *max, atomic)
+            }
+            PestExpr::Positive(inner) => GrammarExpr::Positive(Box::new(self.convert(inner, atomic))),
+            PestExpr::Negative(inner) => GrammarExpr::Negative(Box::new(self.convert(inner, atomic))),
+            PestExpr::Literal { text, insensitive } => GrammarExpr::Literal { text: text.clone(), insensitive: *insensitive },
+            PestExpr::Range(start, end) => GrammarExpr::Class { ranges: vec![(*start, *end)], negated: false },
+            PestExpr::Tag { tag, expr, range } => {
+                self.unsupported.push(GrammarError::new(format!("the tag `#{}` is dropped", tag), range.clone()));
+                self.convert(expr, atomic)
+            }
+            PestExpr::Ident { name, argument: Some(argument), range } if name == "PUSH" => {
+                let message = "the stack operation `PUSH` is not supported, only its expression is matched";
+                self.unsupported.push(GrammarError::new(message, range.clone()));
+                self.convert(argument, atomic)
+            }
+            PestExpr::Ident { name, argument: None, range } if self.names.contains(name.as_str()) => {
+                GrammarExpr::Rule { name: name.clone(), range: range.clone() }
+            }
+            PestExpr::Ident { name, argument, range } => match builtin(name).filter(|_| argument.is_none()) {
+                Some(expr) => expr,
+                None => {
+                    let message = match name.as_str() {
+                        "SOI" => "`SOI` is only supported at the start of a rule".to_string(),
+                        "PUSH" | "POP" | "POP_ALL" | "PEEK" | "PEEK_ALL" | "DROP" => {
+                            format!("the stack operation `{}` is not supported", name)
+                        }
+                        _ if name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') => {
+                            format!("the builtin rule `{}` is not supported", name)
+                        }
+                        // an undefined rule, reported by the check
+                        _ => return GrammarExpr::Rule { name: name.clone(), range: range.clone() },
+                    };
+                    self.unsupported.push(GrammarError::new(message, range.clone()));
+                    GrammarExpr::Rule { name: name.clone(), range: range.clone() }
+                }
+            },
+        }
+    }
+    /// `a ~ b`, skip the trivia between the items of non-atomic rules
+    fn join(&self, items: Vec<GrammarExpr>, atomic: bool) -> GrammarExpr {
+        let mut out = Vec::with_capacity(items.len() * 2);
+        for item in items {
+            if !out.is_empty() && !atomic && self.trivia.is_some() {
+                out.push(GrammarExpr::Trivia);
+            }
+            out.push(item);
+        }
+        flatten(out)
+    }
+    /// `a{m,n}`, skip the trivia between the matches of non-atomic rules, as `a (~ a){m-1,n-1}`
+    fn repeat(&self, item: GrammarExpr, min: usize, max: Option<usize>, atomic: bool) -> GrammarExpr {
+        if atomic || self.trivia.is_none() || max == Some(0) {
+            return GrammarExpr::Repeat { expr: Box::new(item), min, max };
+        }
+        let rest = flatten(vec![GrammarExpr::Trivia, item.clone()]);
+        let rest = match (min.saturating_sub(1), max.map(|max| max - 1)) {
+            (_, Some(0)) => None,
+            (0, Some(1)) => Some(GrammarExpr::Optional(Box::new(rest))),
+            (min, max) => Some(GrammarExpr::Repeat { expr: Box::new(rest), min, max }),
+        };
+        let once = match rest {
+            Some(rest) => flatten(vec![item, rest]),
+            None => item,
+        };
+        match min {
+            0 => GrammarExpr::Optional(Box::new(once)),
+            _ => once,
+        }
+    }
+}
+
+impl PestExpr {
+    fn is_ident(&self, builtin: &str) -> bool {
+        matches!(self, Self::Ident { name, argument: None, .. } if name == builtin)
+    }
+    /// Collect the names of the called rules and builtins
+    fn calls<'a>(&'a self, calls: &mut Vec<&'a str>) {
+        match self {
+            Self::Choice(items) | Self::Sequence(items) => items.iter().for_each(|item| item.calls(calls)),
+            Self::Optional(expr) | Self::Repeat { expr, .. } | Self::Positive(expr) | Self::Negative(expr) => expr.calls(calls),
+            Self::Tag { expr, .. } => expr.calls(calls),
+            Self::Ident { name, argument, .. } => {
+                calls.push(name);
+                if let Some(argument) = argument {
+                    argument.calls(calls)
+                }
+            }
+            Self::Literal { .. } | Self::Range(..) => {}
+        }
+    }
+}
+
+/// Merge the nested sequences, `(a ~ b) ~ c` is the same as `a ~ b ~ c`
+fn flatten(items: Vec<GrammarExpr>) -> GrammarExpr {
+    let mut out = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            GrammarExpr::Sequence(inner) => out.extend(inner),
+            item => out.push(item),
+        }
+    }
+    match out.len() {
+        1 => out.remove(0),
+        _ => GrammarExpr::Sequence(out),
+    }
+}
+
+/// The builtin rules of pest which have an equivalent expression
+fn builtin(name: &str) -> Option<GrammarExpr> {
+    let class = |ranges: &[(char, char)]| GrammarExpr::Class { ranges: ranges.to_vec(), negated: false };
+    let expr = match name {
+        "ANY" => GrammarExpr::Any,
+        "EOI" => GrammarExpr::Eof,
+        "NEWLINE" => {
+            GrammarExpr::Choice(vec![GrammarExpr::literal("\n"), GrammarExpr::literal("\r\n"), GrammarExpr::literal("\r")])
+        }
+        "ASCII_DIGIT" => class(&[('0', '9')]),
+        "ASCII_NONZERO_DIGIT" => class(&[('1', '9')]),
+        "ASCII_BIN_DIGIT" => class(&[('0', '1')]),
+        "ASCII_OCT_DIGIT" => class(&[('0', '7')]),
+        "ASCII_HEX_DIGIT" => class(&[('0', '9'), ('a', 'f'), ('A', 'F')]),
+        "ASCII_ALPHA_LOWER" => class(&[('a', 'z')]),
+        "ASCII_ALPHA_UPPER" => class(&[('A', 'Z')]),
+        "ASCII_ALPHA" => class(&[('a', 'z'), ('A', 'Z')]),
+        "ASCII_ALPHANUMERIC" => class(&[('a', 'z'), ('A', 'Z'), ('0', '9')]),
+        "ASCII" => class(&[('\0', '\u{7F}')]),
+        "XID_START" => GrammarExpr::Set(CharacterSet::XidStart),
+        "XID_CONTINUE" => GrammarExpr::Set(CharacterSet::XidContinue),
+        _ => return None,
+    };
+    Some(expr)
+}
+
+fn grammar(state: ParseState) -> ParseResult<Vec<PestRule>> {
+    let (state, rules) = state.match_repeats(|s| s.skip(ignore).match_fn(rule))?;
+    let (state, _) = state.skip(ignore).match_eof()?;
+    state.finish(rules)
+}
+
+/// `name = _{ expression }`
+fn rule(state: ParseState) -> ParseResult<PestRule> {
+    let start = state.start_offset;
+    let (state, name) = identifier(state)?;
+    let range = start..state.start_offset;
+    let (state, _) = state.skip(ignore).match_char('=')?;
+    let (state, modifier) = state.skip(ignore).match_optional(|s| s.match_char_if(|c| "_@$!".contains(c), "MODIFIER"))?;
+    let (state, _) = state.skip(ignore).match_char('{')?;
+    let (state, body) = state.skip(ignore).match_fn(choice)?;
+    let (state, _) = state.skip(ignore).match_char('}')?;
+    state.finish(PestRule { name: name.to_string(), modifier, body, range })
+}
+
+/// `a | b`, a leading `|` is allowed
+fn choice(state: ParseState) -> ParseResult<PestExpr> {
+    let (state, _) = state.match_optional(|s| s.match_char('|'))?;
+    let (state, first) = state.skip(ignore).match_fn(sequence)?;
+    let (state, mut rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char('|')?;
+        s.skip(ignore).match_fn(sequence)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(PestExpr::Choice(rest))
+        }
+    }
+}
+
+/// `a ~ b`
+fn sequence(state: ParseState) -> ParseResult<PestExpr> {
+    let (state, first) = prefix(state)?;
+    let (state, mut rest) = state.match_repeats(|s| {
+        let (s, _) = s.skip(ignore).match_char('~')?;
+        s.skip(ignore).match_fn(prefix)
+    })?;
+    match rest.is_empty() {
+        true => state.finish(first),
+        false => {
+            rest.insert(0, first);
+            state.finish(PestExpr::Sequence(rest))
+        }
+    }
+}
+
+/// `&a` or `!a`
+fn prefix(state: ParseState) -> ParseResult<PestExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let (s, _) = s.match_char('&')?;
+            s.skip(ignore).match_fn(prefix).map_inner(|e| PestExpr::Positive(Box::new(e)))
+        })
+        .or_else(|s| {
+            let (s, _) = s.match_char('!')?;
+            s.skip(ignore).match_fn(prefix).map_inner(|e| PestExpr::Negative(Box::new(e)))
+        })
+        .or_else(suffix)
+        .end_choice()
+}
+
+/// `a?`, `a*`, `a+` or `a{m,n}`
+fn suffix(state: ParseState) -> ParseResult<PestExpr> {
+    let (state, mut expr) = primary(state)?;
+    let (state, operators) = state.match_repeats(|s| s.skip(ignore).match_fn(suffix_operator))?;
+    for (min, max) in operators {
+        expr = match (min, max) {
+            (0, Some(1)) => PestExpr::Optional(Box::new(expr)),
+            (min, max) => PestExpr::Repeat { expr: Box::new(expr), min, max },
+        }
+    }
+    state.finish(expr)
+}
+
+fn primary(state: ParseState) -> ParseResult<PestExpr> {
+    state
+        .begin_choice()
+        .or_else(|s| {
+            let (s, _) = s.match_char('(')?;
+            let (s, expr) = s.skip(ignore).match_fn(choice)?;
+            let (s, _) = s.skip(ignore).match_char(')')?;
+            s.finish(expr)
+        })
+        .or_else(|s| {
+            let (s, insensitive) = s.match_optional(|s| s.match_char('^'))?;
+            let (s, text) = string(s, '"')?;
+            s.finish(PestExpr::Literal { text, insensitive: insensitive.is_some() })
+        })
+        .or_else(character)
+        .or_else(tag)
+        .or_else(|s| {
+            let start = s.start_offset;
+            let (s, name) = identifier(s)?;
+            // `PUSH(a)` or `PEEK[0..1]`
+            let (s, argument) = s.match_optional(|s| {
+                let (s, _) = s.match_char('(')?;
+                let (s, expr) = s.skip(ignore).match_fn(choice)?;
+                let (s, _) = s.skip(ignore).match_char(')')?;
+                s.finish(Box::new(expr))
+            })?;
+            let (s, _) = s.match_optional(|s| {
+                let (s, _) = s.match_char('[')?;
+                let (s, _) = s.match_optional(|s| s.match_str_if(|c| c != ']', "SLICE"))?;
+                s.match_char(']')
+            })?;
+            s.finish(PestExpr::Ident { name: name.to_string(), argument, range: start..s.start_offset })
+        })
+        .end_choice()
+}
+
+/// `'a'`, or `'a'..'z'` for the characters in the range
+fn character(state: ParseState) -> ParseResult<PestExpr> {
+    let (next, first) = char_literal(state)?;
+    let range = next.match_fn(|s| {
+        let (s, _) = s.skip(ignore).match_str("..")?;
+        s.skip(ignore).match_fn(char_literal)
+    });
+    match range {
+        ParseResult::Pending(end, last) if last < first => {
+            StopBecause::custom_error("the character range is out of order", state.start_offset, end.start_offset)?
+        }
+        ParseResult::Pending(end, last) => end.finish(PestExpr::Range(first, last)),
+        ParseResult::Stop(_) => next.finish(PestExpr::Literal { text: first.to_string(), insensitive: false }),
+    }
+}
+
+fn char_literal(state: ParseState) -> ParseResult<char> {
+    let (next, text) = string(state, '\'')?;
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => next.finish(c),
+        _ => StopBecause::custom_error("a character literal must have one character", state.start_offset, next.start_offset)?,
+    }
+}
+
+/// `#tag = a`
+fn tag(state: ParseState) -> ParseResult<PestExpr> {
+    let start = state.start_offset;
+    let (state, _) = state.match_char('#')?;
+    let (state, tag) = identifier(state)?;
+    let range = start..state.start_offset;
+    let (state, _) = state.skip(ignore).match_char('=')?;
+    let (state, expr) = state.skip(ignore).match_fn(suffix)?;
+    state.finish(PestExpr::Tag { tag: tag.to_string(), expr: Box::new(expr), range })
+}
+
+/// The text between the quotes, pest also escapes bytes by `\x7F`
+fn string(state: ParseState, quote: char) -> ParseResult<String> {
+    let (mut state, _) = state.match_char(quote)?;
+    let mut text = String::new();
+    loop {
+        match state.residual.chars().next() {
+            None => StopBecause::missing_character(quote, state.start_offset)?,
+            Some(c) if c == quote => return state.advance(c).finish(text),
+            Some('\\') if state.residual.starts_with("\\x") => {
+                // exactly two hex digits, the following digits are literal
+                let (next, high) = state.advance("\\x").match_char_if(|c| c.is_ascii_hexdigit(), "HEX")?;
+                let (next, low) = next.match_char_if(|c| c.is_ascii_hexdigit(), "HEX")?;
+                let byte = high.to_digit(16).unwrap_or(0) * 16 + low.to_digit(16).unwrap_or(0);
+                match u8::try_from(byte).ok().filter(u8::is_ascii) {
+                    Some(byte) => text.push(byte as char),
+                    None => StopBecause::custom_error("invalid byte escape", state.start_offset, next.start_offset)?,
+                }
+                state = next;
+            }
+            Some('\\') => {
+                let (next, c) = escape(state)?;
+                text.push(c);
+                state = next;
+            }
+            Some(c) => {
+                text.push(c);
+                state = state.advance(c);
+            }
+        }
+    }
+}
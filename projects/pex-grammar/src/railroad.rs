@@ -0,0 +1,254 @@
+use crate::{GrammarExpr, GrammarRule};
+use std::fmt::Write;
+
+/// The radius of the arcs
+const R: i32 = 10;
+/// The space between the items
+const GAP: i32 = 10;
+/// The half height of a box
+const BOX: i32 = 11;
+/// The width of a character in the monospace font
+const CHAR: i32 = 9;
+
+/// A railroad diagram, the line enters at the left and leaves at the right of the baseline
+#[derive(Clone, Debug)]
+enum Diagram {
+    /// A literal or a class in a rounded box
+    Terminal(String),
+    /// A rule reference in a square box
+    NonTerminal(String),
+    /// A note on the line, such as a lookahead
+    Comment(String),
+    Skip,
+    Sequence(Vec<Diagram>),
+    /// The first branch is on the line, the others are below
+    Choice(Vec<Diagram>),
+    /// Match the item one or more times, the loop goes back below the item
+    Loop(Box<Diagram>),
+}
+
+impl GrammarRule {
+    /// Render the railroad diagram of the rule as a standalone SVG
+    ///
+    /// The diagram is drawn locally without scripts or external styles, the trivia skipped by `~` is not drawn.
+    pub fn to_railroad(&self) -> String {
+        let diagram = Diagram::from(&self.body);
+        let pad = 20;
+        let title = 24;
+        let width = diagram.width() + 2 * pad + 2 * GAP;
+        let height = title + diagram.up() + diagram.down() + 2 * pad;
+        let y = pad + title + diagram.up();
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">"
+        )
+        .unwrap();
+        out.push_str(STYLE);
+        writeln!(out, "<text class=\"title\" x=\"{}\" y=\"{}\">{}</text>", pad, pad + 8, escape(&self.name)).unwrap();
+        writeln!(out, "<path d=\"M{} {} v{} m0 {} h{}\"/>", pad, y - 8, 16, -8, GAP).unwrap();
+        diagram.render(pad + GAP, y, &mut out);
+        let end = pad + GAP + diagram.width();
+        writeln!(out, "<path d=\"M{} {} h{} m0 {} v{}\"/>", end, y, GAP, -8, 16).unwrap();
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+const STYLE: &str = "<style>
+path { fill: none; stroke: #333; stroke-width: 2; }
+rect { fill: #f4f8ff; stroke: #333; stroke-width: 2; }
+text { font: 14px monospace; text-anchor: middle; fill: #111; }
+text.title { font-weight: bold; text-anchor: start; }
+text.comment { font-style: italic; fill: #666; }
+</style>
+";
+
+impl From<&GrammarExpr> for Diagram {
+    fn from(expr: &GrammarExpr) -> Self {
+        match expr {
+            GrammarExpr::Choice(items) => Self::Choice(items.iter().map(Self::from).collect()),
+            GrammarExpr::Sequence(items) => {
+                let items: Vec<Diagram> =
+                    items.iter().filter(|item| !matches!(item, GrammarExpr::Trivia)).map(Self::from).collect();
+                match items.len() {
+                    0 => Self::Skip,
+                    1 => items.into_iter().next().unwrap_or(Self::Skip),
+                    _ => Self::Sequence(items),
+                }
+            }
+            GrammarExpr::Optional(inner) => Self::Choice(vec![Self::Skip, Self::from(inner.as_ref())]),
+            GrammarExpr::Repeat { expr: inner, min, max } => {
+                let repeat = Self::Loop(Box::new(Self::from(inner.as_ref())));
+                let repeat = match (min, max) {
+                    (0 | 1, None) => repeat,
+                    (min, None) => Self::Sequence(vec![repeat, Self::Comment(format!("{} or more", min))]),
+                    (min, Some(max)) if min == max => Self::Sequence(vec![repeat, Self::Comment(format!("{} times", min))]),
+                    (min, Some(max)) => Self::Sequence(vec![repeat, Self::Comment(format!("{} to {} times", min, max))]),
+                };
+                match min {
+                    0 => Self::Choice(vec![Self::Skip, repeat]),
+                    _ => repeat,
+                }
+            }
+            GrammarExpr::Positive(_) | GrammarExpr::Negative(_) => Self::Comment(expr.to_string()),
+            GrammarExpr::Literal { .. } | GrammarExpr::Class { .. } => Self::Terminal(expr.to_string()),
+            GrammarExpr::Set(set) => Self::Terminal(set.get_name().to_string()),
+            GrammarExpr::Any => Self::Terminal("ANY".to_string()),
+            GrammarExpr::Eof => Self::Comment("end of input".to_string()),
+            GrammarExpr::Trivia => Self::Skip,
+            GrammarExpr::Rule { name, .. } => Self::NonTerminal(name.clone()),
+        }
+    }
+}
+
+impl Diagram {
+    fn width(&self) -> i32 {
+        match self {
+            Self::Terminal(text) | Self::NonTerminal(text) => text_width(text) + 2 * GAP,
+            Self::Comment(text) => text_width(text) + GAP,
+            Self::Skip => 0,
+            Self::Sequence(items) => items.iter().map(Self::width).sum::<i32>() + GAP * (items.len() as i32 - 1),
+            Self::Choice(items) => items.iter().map(Self::width).max().unwrap_or(0) + 4 * R,
+            Self::Loop(item) => item.width() + 4 * R,
+        }
+    }
+    /// The height above the baseline
+    fn up(&self) -> i32 {
+        match self {
+            Self::Terminal(_) | Self::NonTerminal(_) | Self::Comment(_) => BOX,
+            Self::Skip => 0,
+            Self::Sequence(items) => items.iter().map(Self::up).max().unwrap_or(0),
+            Self::Choice(items) => items.first().map_or(0, Self::up),
+            Self::Loop(item) => item.up(),
+        }
+    }
+    /// The height below the baseline
+    fn down(&self) -> i32 {
+        match self {
+            Self::Terminal(_) | Self::NonTerminal(_) | Self::Comment(_) => BOX,
+            Self::Skip => 0,
+            Self::Sequence(items) => items.iter().map(Self::down).max().unwrap_or(0),
+            Self::Choice(items) => match self.branches().last() {
+                Some(offset) => offset + items.last().map_or(0, Self::down),
+                None => 0,
+            },
+            Self::Loop(item) => loop_offset(item),
+        }
+    }
+    /// The baseline offsets of the branches of a choice
+    fn branches(&self) -> Vec<i32> {
+        let Self::Choice(items) = self
+        else {
+            return vec![];
+        };
+        let mut offsets = Vec::with_capacity(items.len());
+        let mut offset = 0;
+        for (index, item) in items.iter().enumerate() {
+            if index != 0 {
+                offset = (offset + items[index - 1].down() + GAP + item.up()).max(offset + 2 * R);
+            }
+            offsets.push(offset);
+        }
+        offsets
+    }
+    /// Draw the diagram with the entry at `(x, y)`
+    fn render(&self, x: i32, y: i32, out: &mut String) {
+        let width = self.width();
+        match self {
+            Self::Terminal(text) | Self::NonTerminal(text) => {
+                let rx = if matches!(self, Self::Terminal(_)) { BOX } else { 0 };
+                writeln!(out, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"/>", x, y - BOX, width, 2 * BOX, rx)
+                    .unwrap();
+                writeln!(out, "<text x=\"{}\" y=\"{}\">{}</text>", x + width / 2, y + 5, escape(text)).unwrap();
+            }
+            Self::Comment(text) => {
+                writeln!(out, "<path d=\"M{} {} h{} m{} 0 h{}\"/>", x, y, GAP / 2, width - GAP, GAP / 2).unwrap();
+                writeln!(out, "<text class=\"comment\" x=\"{}\" y=\"{}\">{}</text>", x + width / 2, y + 5, escape(text))
+                    .unwrap();
+            }
+            Self::Skip => {}
+            Self::Sequence(items) => {
+                let mut x = x;
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        writeln!(out, "<path d=\"M{} {} h{}\"/>", x, y, GAP).unwrap();
+                        x += GAP;
+                    }
+                    item.render(x, y, out);
+                    x += item.width();
+                }
+            }
+            Self::Choice(items) => {
+                let inner = width - 4 * R;
+                for (item, offset) in items.iter().zip(self.branches()) {
+                    let left = (inner - item.width()) / 2;
+                    let right = inner - item.width() - left;
+                    match offset {
+                        0 => writeln!(out, "<path d=\"M{} {} h{}\"/>", x, y, 2 * R + left).unwrap(),
+                        _ => writeln!(
+                            out,
+                            "<path d=\"M{} {} a{R} {R} 0 0 1 {R} {R} v{} a{R} {R} 0 0 0 {R} {R} h{}\"/>",
+                            x,
+                            y,
+                            offset - 2 * R,
+                            left
+                        )
+                        .unwrap(),
+                    }
+                    item.render(x + 2 * R + left, y + offset, out);
+                    let end = x + 2 * R + left + item.width();
+                    match offset {
+                        0 => writeln!(out, "<path d=\"M{} {} h{}\"/>", end, y, right + 2 * R).unwrap(),
+                        _ => writeln!(
+                            out,
+                            "<path d=\"M{} {} h{} a{R} {R} 0 0 0 {R} {} v{} a{R} {R} 0 0 1 {R} {}\"/>",
+                            end,
+                            y + offset,
+                            right,
+                            -R,
+                            -(offset - 2 * R),
+                            -R
+                        )
+                        .unwrap(),
+                    }
+                }
+            }
+            Self::Loop(item) => {
+                let inner = item.width();
+                let offset = loop_offset(item);
+                writeln!(out, "<path d=\"M{} {} h{}\"/>", x, y, 2 * R).unwrap();
+                item.render(x + 2 * R, y, out);
+                writeln!(out, "<path d=\"M{} {} h{}\"/>", x + 2 * R + inner, y, 2 * R).unwrap();
+                writeln!(
+                    out,
+                    "<path d=\"M{} {} a{R} {R} 0 0 1 {R} {R} v{} a{R} {R} 0 0 1 {} {R} h{} a{R} {R} 0 0 1 {} {} v{} a{R} {R} 0 0 1 {R} {}\"/>",
+                    x + 2 * R + inner,
+                    y,
+                    offset - 2 * R,
+                    -R,
+                    -inner,
+                    -R,
+                    -R,
+                    -(offset - 2 * R),
+                    -R
+                )
+                .unwrap();
+            }
+        }
+    }
+}
+
+/// The baseline offset of the line back to the start of a loop
+fn loop_offset(item: &Diagram) -> i32 {
+    (item.down() + GAP).max(2 * R)
+}
+
+fn text_width(text: &str) -> i32 {
+    text.chars().count() as i32 * CHAR
+}
+
+/// Escape the text in the svg
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,126 @@
+// This file is generated by pex-grammar, do not edit it by hand.
+
+/// Match a rule and return the matched text, a failure at the start of the rule expects the rule name
+fn match_named<'i, T>(
+    input: ::pex::ParseState<'i>,
+    name: &'static str,
+    parse: impl FnOnce(::pex::ParseState<'i>) -> ::pex::ParseResult<'i, T>,
+) -> ::pex::ParseResult<'i, &'i str> {
+    match input.match_rule(name, parse) {
+        ::pex::ParseResult::Pending(state, _) => state.finish(&input.residual[..state.start_offset - input.start_offset]),
+        ::pex::ParseResult::Stop(error) if error.is_fatal() || error.range().start > input.start_offset => {
+            ::pex::ParseResult::Stop(error)
+        }
+        ::pex::ParseResult::Stop(_) => ::pex::StopBecause::must_be(name, input.start_offset)?,
+    }
+}
+
+/// `date-fullyear = DIGIT{4};`
+pub fn date_fullyear<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "date-fullyear", |s| s.match_repeat_m_n(4, 4, |s| digit(s)))
+}
+
+/// `date-month = DIGIT{2};`
+pub fn date_month<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "date-month", |s| s.match_repeat_m_n(2, 2, |s| digit(s)))
+}
+
+/// `date-mday = DIGIT{2};`
+pub fn date_mday<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "date-mday", |s| s.match_repeat_m_n(2, 2, |s| digit(s)))
+}
+
+/// `time-hour = DIGIT{2};`
+pub fn time_hour<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-hour", |s| s.match_repeat_m_n(2, 2, |s| digit(s)))
+}
+
+/// `time-minute = DIGIT{2};`
+pub fn time_minute<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-minute", |s| s.match_repeat_m_n(2, 2, |s| digit(s)))
+}
+
+/// `time-second = DIGIT{2};`
+pub fn time_second<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-second", |s| s.match_repeat_m_n(2, 2, |s| digit(s)))
+}
+
+/// `time-secfrac = "." DIGIT+;`
+pub fn time_secfrac<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-secfrac", |s| s.match_fn(|s| {
+        let (s, _) = s.match_char('.')?;
+        let (s, _) = s.match_repeat_m_n(1, usize::MAX, |s| digit(s))?;
+        s.finish(())
+    }))
+}
+
+/// `time-numoffset = ("+" | "-") time-hour ":" time-minute;`
+pub fn time_numoffset<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-numoffset", |s| s.match_fn(|s| {
+        let (s, _) = s.begin_choice()
+            .or_else(|s| s.match_char('+').map_value(()))
+            .or_else(|s| s.match_char('-').map_value(()))
+            .end_choice()?;
+        let (s, _) = time_hour(s)?;
+        let (s, _) = s.match_char(':')?;
+        let (s, _) = time_minute(s)?;
+        s.finish(())
+    }))
+}
+
+/// `time-offset = i"Z" | time-numoffset;`
+pub fn time_offset<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "time-offset", |s| s.begin_choice()
+        .or_else(|s| s.match_str_insensitive("Z").map_value(()))
+        .or_else(|s| time_numoffset(s).map_value(()))
+        .end_choice())
+}
+
+/// `partial-time = time-hour ":" time-minute ":" time-second time-secfrac?;`
+pub fn partial_time<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "partial-time", |s| s.match_fn(|s| {
+        let (s, _) = time_hour(s)?;
+        let (s, _) = s.match_char(':')?;
+        let (s, _) = time_minute(s)?;
+        let (s, _) = s.match_char(':')?;
+        let (s, _) = time_second(s)?;
+        let (s, _) = s.match_optional(|s| time_secfrac(s))?;
+        s.finish(())
+    }))
+}
+
+/// `full-date = date-fullyear "-" date-month "-" date-mday;`
+pub fn full_date<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "full-date", |s| s.match_fn(|s| {
+        let (s, _) = date_fullyear(s)?;
+        let (s, _) = s.match_char('-')?;
+        let (s, _) = date_month(s)?;
+        let (s, _) = s.match_char('-')?;
+        let (s, _) = date_mday(s)?;
+        s.finish(())
+    }))
+}
+
+/// `full-time = partial-time time-offset;`
+pub fn full_time<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "full-time", |s| s.match_fn(|s| {
+        let (s, _) = partial_time(s)?;
+        let (s, _) = time_offset(s)?;
+        s.finish(())
+    }))
+}
+
+/// `date-time = full-date i"T" full-time;`
+pub fn date_time<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "date-time", |s| s.match_fn(|s| {
+        let (s, _) = full_date(s)?;
+        let (s, _) = s.match_str_insensitive("T")?;
+        let (s, _) = full_time(s)?;
+        s.finish(())
+    }))
+}
+
+/// `DIGIT = [0-9];`
+pub fn digit<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {
+    match_named(input, "DIGIT", |s| s.match_char_range('0', '9'))
+}
@@ -0,0 +1,413 @@
+// This file is generated by pex-grammar, do not edit it by hand.
+
+#[allow(clippy::large_enum_variant)]
+enum AnyNode {
+    Value(Value),
+    Pair(Pair),
+    String(String),
+}
+
+/// `@trivia space = [ \t\r\n]+;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Space {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Space {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = space_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[allow(clippy::manual_is_ascii_check)]
+fn space_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    s.match_repeat_m_n(1, usize::MAX, |s| s.match_char_if(|c| matches!(c, ' ' | '\t' | '\r' | '\n'), "[ \\t\\r\\n]")).map_value(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for Space {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `json = ~ value ~ $;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Json {
+    pub value: Value,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Json {
+    #[allow(clippy::single_match)]
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = json_body(_pex_input)?;
+        let mut value = ::core::option::Option::None;
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::Value(_pex_node) => value = ::core::option::Option::Some(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            value: value.expect("matched by the grammar"),
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn json_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, node) = s.match_fn(<Value as ::pex::PexNode>::parse)?;
+    nodes.push(AnyNode::Value(node));
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_eof()?;
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Json {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `value = object | array | string | number | null;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    Object(Object),
+    Array(Array),
+    String(String),
+    Number(Number),
+    Null(Null),
+}
+
+impl ::pex::PexNode for Value {
+    fn parse(input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        input
+            .begin_choice()
+            .or_else(|s| s.match_fn(<Object as ::pex::PexNode>::parse).map_inner(Self::Object))
+            .or_else(|s| s.match_fn(<Array as ::pex::PexNode>::parse).map_inner(Self::Array))
+            .or_else(|s| s.match_fn(<String as ::pex::PexNode>::parse).map_inner(Self::String))
+            .or_else(|s| s.match_fn(<Number as ::pex::PexNode>::parse).map_inner(Self::Number))
+            .or_else(|s| s.match_fn(<Null as ::pex::PexNode>::parse).map_inner(Self::Null))
+            .end_choice()
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        match self {
+            Self::Object(node) => ::pex::PexNode::get_range(node),
+            Self::Array(node) => ::pex::PexNode::get_range(node),
+            Self::String(node) => ::pex::PexNode::get_range(node),
+            Self::Number(node) => ::pex::PexNode::get_range(node),
+            Self::Null(node) => ::pex::PexNode::get_range(node),
+        }
+    }
+}
+
+impl ::core::str::FromStr for Value {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `object = "{" ~ (pair (~ "," ~ pair)*)? ~ "}";`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Object {
+    pub pair: ::std::vec::Vec<Pair>,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Object {
+    #[allow(clippy::single_match)]
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = object_body(_pex_input)?;
+        let mut pair = ::std::vec::Vec::new();
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::Pair(_pex_node) => pair.push(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            pair,
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn object_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, _) = s.match_str("{")?;
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, more) = s.match_optional(|s| s.match_fn(|s| {
+        let mut nodes = ::std::vec::Vec::new();
+        let (s, node) = s.match_fn(<Pair as ::pex::PexNode>::parse)?;
+        nodes.push(AnyNode::Pair(node));
+        let (s, more) = s.match_repeats(|s| s.match_fn(|s| {
+            let mut nodes = ::std::vec::Vec::new();
+            let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+            let (s, _) = s.match_str(",")?;
+            let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+            let (s, node) = s.match_fn(<Pair as ::pex::PexNode>::parse)?;
+            nodes.push(AnyNode::Pair(node));
+            s.finish(nodes)
+        })).map_inner(|nodes| nodes.into_iter().flatten().collect::<::std::vec::Vec<AnyNode>>())?;
+        nodes.extend(more);
+        s.finish(nodes)
+    })).map_inner(|nodes| nodes.unwrap_or_default())?;
+    nodes.extend(more);
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_str("}")?;
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Object {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `pair = string ~ ":" ~ value;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pair {
+    pub string: String,
+    pub value: Value,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Pair {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = pair_body(_pex_input)?;
+        let mut string = ::core::option::Option::None;
+        let mut value = ::core::option::Option::None;
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::String(_pex_node) => string = ::core::option::Option::Some(_pex_node),
+                AnyNode::Value(_pex_node) => value = ::core::option::Option::Some(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            string: string.expect("matched by the grammar"),
+            value: value.expect("matched by the grammar"),
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn pair_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, node) = s.match_fn(<String as ::pex::PexNode>::parse)?;
+    nodes.push(AnyNode::String(node));
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_str(":")?;
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, node) = s.match_fn(<Value as ::pex::PexNode>::parse)?;
+    nodes.push(AnyNode::Value(node));
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Pair {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `array = "[" ~ (value (~ "," ~ value)*)? ~ "]";`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Array {
+    pub value: ::std::vec::Vec<Value>,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Array {
+    #[allow(clippy::single_match)]
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = array_body(_pex_input)?;
+        let mut value = ::std::vec::Vec::new();
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::Value(_pex_node) => value.push(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            value,
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn array_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, _) = s.match_str("[")?;
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, more) = s.match_optional(|s| s.match_fn(|s| {
+        let mut nodes = ::std::vec::Vec::new();
+        let (s, node) = s.match_fn(<Value as ::pex::PexNode>::parse)?;
+        nodes.push(AnyNode::Value(node));
+        let (s, more) = s.match_repeats(|s| s.match_fn(|s| {
+            let mut nodes = ::std::vec::Vec::new();
+            let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+            let (s, _) = s.match_str(",")?;
+            let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+            let (s, node) = s.match_fn(<Value as ::pex::PexNode>::parse)?;
+            nodes.push(AnyNode::Value(node));
+            s.finish(nodes)
+        })).map_inner(|nodes| nodes.into_iter().flatten().collect::<::std::vec::Vec<AnyNode>>())?;
+        nodes.extend(more);
+        s.finish(nodes)
+    })).map_inner(|nodes| nodes.unwrap_or_default())?;
+    nodes.extend(more);
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_str("]")?;
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Array {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `string = "\"" ([^"\\] | "\\" ANY)* "\"";`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct String {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for String {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = string_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[allow(clippy::manual_is_ascii_check)]
+fn string_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let (s, _) = s.match_str("\"")?;
+    let (s, _) = s.match_repeats(|s| s.begin_choice()
+        .or_else(|s| s.match_char_if(|c| !matches!(c, '"' | '\\'), "[^\"\\\\]").map_value(()))
+        .or_else(|s| s.match_fn(|s| {
+            let (s, _) = s.match_str("\\")?;
+            let (s, _) = s.match_char_any()?;
+            s.finish(())
+        }).map_value(()))
+        .end_choice())?;
+    let (s, _) = s.match_str("\"")?;
+    s.finish(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for String {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `number = "-"? [0-9]+ ("." [0-9]+)?;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Number {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Number {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = number_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[allow(clippy::manual_is_ascii_check)]
+fn number_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let (s, _) = s.match_optional(|s| s.match_str("-"))?;
+    let (s, _) = s.match_repeat_m_n(1, usize::MAX, |s| s.match_char_if(|c| matches!(c, '0'..='9'), "[0-9]"))?;
+    let (s, _) = s.match_optional(|s| s.match_fn(|s| {
+        let (s, _) = s.match_str(".")?;
+        let (s, _) = s.match_repeat_m_n(1, usize::MAX, |s| s.match_char_if(|c| matches!(c, '0'..='9'), "[0-9]"))?;
+        s.finish(())
+    }))?;
+    s.finish(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for Number {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `null = i"null";`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Null {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Null {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = null_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn null_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    s.match_str_insensitive("null").map_value(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for Null {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
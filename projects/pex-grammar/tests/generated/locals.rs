@@ -0,0 +1,211 @@
+// This file is generated by pex-grammar, do not edit it by hand.
+
+#[allow(clippy::large_enum_variant)]
+enum AnyNode {
+    Nodes(Nodes),
+    State(State),
+    Node(Node),
+}
+
+/// `@trivia space = [ \t\r\n]+;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Space {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Space {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = space_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[allow(clippy::manual_is_ascii_check)]
+fn space_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    s.match_repeat_m_n(1, usize::MAX, |s| s.match_char_if(|c| matches!(c, ' ' | '\t' | '\r' | '\n'), "[ \\t\\r\\n]")).map_value(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for Space {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `input = ~ "[" ~ nodes ~ "]" ~ node? ~ $;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Input {
+    pub node: ::core::option::Option<Node>,
+    pub nodes: Nodes,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Input {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = input_body(_pex_input)?;
+        let mut node = ::core::option::Option::None;
+        let mut nodes = ::core::option::Option::None;
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::Node(_pex_node) => node = ::core::option::Option::Some(_pex_node),
+                AnyNode::Nodes(_pex_node) => nodes = ::core::option::Option::Some(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            node,
+            nodes: nodes.expect("matched by the grammar"),
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn input_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_str("[")?;
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, node) = s.match_fn(<Nodes as ::pex::PexNode>::parse)?;
+    nodes.push(AnyNode::Nodes(node));
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_str("]")?;
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, more) = s.match_optional(|s| s.match_fn(<Node as ::pex::PexNode>::parse).map_inner(|node| ::std::vec![AnyNode::Node(node)])).map_inner(|nodes| nodes.unwrap_or_default())?;
+    nodes.extend(more);
+    let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+    let (s, _) = s.match_eof()?;
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Input {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `nodes = state (~ "," ~ state)*;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Nodes {
+    pub state: ::std::vec::Vec<State>,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Nodes {
+    #[allow(clippy::single_match)]
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _pex_nodes) = nodes_body(_pex_input)?;
+        let mut state = ::std::vec::Vec::new();
+        for _pex_node in _pex_nodes {
+            match _pex_node {
+                AnyNode::State(_pex_node) => state.push(_pex_node),
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+        }
+        _pex_state.finish(Self {
+            state,
+            span: _pex_input.start_offset.._pex_state.start_offset,
+        })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn nodes_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    let mut nodes = ::std::vec::Vec::new();
+    let (s, node) = s.match_fn(<State as ::pex::PexNode>::parse)?;
+    nodes.push(AnyNode::State(node));
+    let (s, more) = s.match_repeats(|s| s.match_fn(|s| {
+        let mut nodes = ::std::vec::Vec::new();
+        let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+        let (s, _) = s.match_str(",")?;
+        let (s, _) = s.try_skip(<Space as ::pex::PexNode>::parse)?;
+        let (s, node) = s.match_fn(<State as ::pex::PexNode>::parse)?;
+        nodes.push(AnyNode::State(node));
+        s.finish(nodes)
+    })).map_inner(|nodes| nodes.into_iter().flatten().collect::<::std::vec::Vec<AnyNode>>())?;
+    nodes.extend(more);
+    s.finish(nodes)
+}
+
+impl ::core::str::FromStr for Nodes {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `state = [a-z]+;`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct State {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for State {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = state_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+#[allow(clippy::manual_is_ascii_check)]
+fn state_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    s.match_repeat_m_n(1, usize::MAX, |s| s.match_char_if(|c| matches!(c, 'a'..='z'), "[a-z]")).map_value(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for State {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
+
+/// `node = "!";`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Node {
+    pub text: ::std::string::String,
+    pub span: ::core::ops::Range<usize>,
+}
+
+impl ::pex::PexNode for Node {
+    fn parse(_pex_input: ::pex::ParseState) -> ::pex::ParseResult<Self> {
+        let (_pex_state, _) = node_body(_pex_input)?;
+        let text = _pex_input.residual[.._pex_state.start_offset - _pex_input.start_offset].to_string();
+        _pex_state.finish(Self { text, span: _pex_input.start_offset.._pex_state.start_offset })
+    }
+    fn get_range(&self) -> ::core::ops::Range<usize> {
+        self.span.clone()
+    }
+}
+
+fn node_body(s: ::pex::ParseState) -> ::pex::ParseResult<::std::vec::Vec<AnyNode>> {
+    s.match_str("!").map_value(::std::vec::Vec::new())
+}
+
+impl ::core::str::FromStr for Node {
+    type Err = ::pex::StopBecause;
+
+    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+        ::pex::helpers::make_from_str(::pex::ParseState::new(s), <Self as ::pex::PexNode>::parse)
+    }
+}
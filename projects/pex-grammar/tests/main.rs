@@ -0,0 +1,339 @@
+use pex::{ParseContext, ParseResult, ParseState, RuleRegistry};
+use pex_grammar::{Grammar, GrammarCompiler, GrammarExporter, GrammarInterpreter};
+
+mod json {
+    include!("generated/json.rs");
+}
+
+mod datetime {
+    include!("generated/datetime.rs");
+}
+
+mod locals {
+    include!("generated/locals.rs");
+}
+
+#[test]
+fn ready() {
+    println!("it works!")
+}
+
+#[test]
+fn parse_grammar() {
+    let grammar: Grammar = include_str!("grammars/json.ygg").parse().unwrap();
+    assert_eq!(grammar.rules.len(), 9);
+    assert_eq!(grammar.get_trivia().unwrap().name, "space");
+    let text = grammar.to_string();
+    assert!(text.contains("object = \"{\" ~ (pair (~ \",\" ~ pair)*)? ~ \"}\";"));
+    assert!(text.contains("string = \"\\\"\" ([^\"\\\\] | \"\\\\\" ANY)* \"\\\"\";"));
+    // the display is a valid grammar
+    let again: Grammar = text.parse().unwrap();
+    assert_eq!(again.to_string(), text);
+    let grammar: Grammar = "a = &b !'c'{2,} [^a-z\\]]? | (b c){,3};\nb = 'b'; c = i'c';".parse().unwrap();
+    assert_eq!(grammar.rules[0].to_string(), "a = &b !\"c\"{2,} [^a-z\\]]? | (b c){0,3};");
+}
+
+#[test]
+fn generate_json() {
+    let code = GrammarCompiler::new("tests/grammars/json.ygg").compile(include_str!("grammars/json.ygg")).unwrap();
+    assert_eq!(code, include_str!("generated/json.rs"));
+    assert!(code.contains("pub enum Value {"));
+    assert!(code.contains("pub pair: ::std::vec::Vec<Pair>,"));
+}
+
+#[test]
+fn json_parser() {
+    let json: json::Json = r#" {"a": [1, -2.5, null], "b": {}} "#.parse().unwrap();
+    let json::Value::Object(object) = &json.value
+    else {
+        panic!("expect object")
+    };
+    assert_eq!(object.span, 1..32);
+    assert_eq!(object.pair.len(), 2);
+    assert_eq!(object.pair[0].string.text, "\"a\"");
+    let json::Value::Array(array) = &object.pair[0].value
+    else {
+        panic!("expect array")
+    };
+    assert!(matches!(&array.value[1], json::Value::Number(n) if n.text == "-2.5"));
+    assert!(matches!(&array.value[2], json::Value::Null(n) if n.span == (17..21)));
+    assert!("[1, 2,]".parse::<json::Json>().is_err());
+    assert!("NULL".parse::<json::Value>().is_ok());
+}
+
+#[test]
+fn generate_locals() {
+    let code = GrammarCompiler::new("tests/grammars/locals.ygg").compile(include_str!("grammars/locals.ygg")).unwrap();
+    assert_eq!(code, include_str!("generated/locals.rs"));
+    // the rules named `nodes` and `state` do not shadow the locals of the parser
+    let input: locals::Input = " [a, bc] ! ".parse().unwrap();
+    assert_eq!(input.nodes.state.iter().map(|state| state.text.as_str()).collect::<Vec<_>>(), ["a", "bc"]);
+    assert_eq!(input.node.unwrap().span, 9..10);
+    assert!("[a,]".parse::<locals::Input>().is_err());
+}
+
+#[test]
+fn recursive_types() {
+    let grammar: Grammar =
+        "expr = term (~ '+' ~ expr)?; term = atom | group; group = '(' ~ expr ~ ')'; atom = [0-9]+;".parse().unwrap();
+    let code = grammar.to_rust().unwrap();
+    assert!(code.contains("pub expr: ::core::option::Option<::std::boxed::Box<Expr>>,"));
+    assert!(code.contains("pub expr: ::std::boxed::Box<Expr>,"));
+    assert!(code.contains("pub term: ::std::boxed::Box<Term>,"));
+    assert!(code.contains("Group(::std::boxed::Box<Group>),"));
+}
+
+#[test]
+fn grammar_errors() {
+    let compiler = GrammarCompiler::new("test.ygg");
+    let errors = compiler.compile("a = b c;\nb = 'b' | b2;\nc = d 'c';\nd = 'd'? c;").unwrap_err();
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(errors, ["test.ygg:2:11: undefined rule `b2`", "test.ygg:4:10: left recursion is not supported: c -> d -> c",]);
+    let errors = compiler.compile("a = 'a' ;\nb = ('b' | c;").unwrap_err();
+    let error = errors[0].to_string();
+    assert!(error.starts_with("test.ygg:2:13: expected one of"), "{}", error);
+    assert!(error.ends_with("IDENTIFIER, `|`, `)`"), "{}", error);
+    let errors = compiler.compile("@trivia s = ' '*;\na = [z-a] ~;").unwrap_err();
+    assert_eq!(errors[0].to_string(), "test.ygg:2:6: the character range is out of order");
+    let errors = compiler.compile("a = ('a'?)*; a = 'b';").unwrap_err();
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        [
+            "test.ygg:1:14: rule `a` is already defined",
+            "test.ygg:1:1: `(\"a\"?)*` in rule `a` may repeat an empty match forever",
+        ]
+    );
+}
+
+#[test]
+fn interpreter() {
+    let grammar: Grammar = include_str!("grammars/json.ygg").parse().unwrap();
+    let interpreter = GrammarInterpreter::new(&grammar).unwrap();
+    let input = r#" {"a": [1, null]} "#;
+    let tree = interpreter.parse_text("json", input).unwrap();
+    assert_eq!(
+        tree.to_text(input),
+        r#"json 0..18
+  value 1..17
+    object 1..17
+      pair 2..16
+        string 2..5 "\"a\""
+        value 7..16
+          array 7..16
+            value 8..9
+              number 8..9 "1"
+            value 11..15
+              null 11..15 "null"
+"#
+    );
+    // the furthest failure is at the missing value
+    let context = ParseContext::default();
+    let result = interpreter.parse("json", ParseState::new("[1, ]").with_context(&context));
+    let failure = context.merge_error(result.as_result().unwrap_err());
+    assert_eq!(failure.position, 4);
+    assert!(GrammarInterpreter::new(&"a = b;".parse().unwrap()).is_err());
+    // the names are leaked once per grammar load, and shared by the clones of the interpreter
+    let again = interpreter.clone().parse_text("json", input).unwrap();
+    assert!(std::ptr::eq(again.rule, tree.rule));
+}
+
+#[test]
+fn command_line() {
+    use std::process::Command;
+    let pex = env!("CARGO_BIN_EXE_pex");
+    let help = Command::new(pex).arg("--help").output().unwrap();
+    assert!(help.status.success());
+    assert!(String::from_utf8_lossy(&help.stdout).starts_with("usage: pex <GRAMMAR> <INPUT>"));
+    let input = std::env::temp_dir().join("pex-grammar-command-line.json");
+    std::fs::write(&input, "[1, null]").unwrap();
+    let output = Command::new(pex).arg("tests/grammars/json.ygg").arg(&input).output().unwrap();
+    assert!(output.status.success());
+    let grammar: Grammar = include_str!("grammars/json.ygg").parse().unwrap();
+    let tree = GrammarInterpreter::new(&grammar).unwrap().parse_text("json", "[1, null]").unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), tree.to_text("[1, null]"));
+    // the trace is printed before the tree
+    let output =
+        Command::new(pex).arg("tests/grammars/json.ygg").arg(&input).args(["--trace", "--rule", "value"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("value 0..9\n"));
+    // a parse error is reported with the exit code 1, a usage error with 2
+    std::fs::write(&input, "[1, ]").unwrap();
+    let output = Command::new(pex).arg("tests/grammars/json.ygg").arg(&input).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: expected one of"));
+    let output = Command::new(pex).arg("tests/grammars/json.ygg").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn pest_importer() {
+    let import = Grammar::from_pest(include_str!("grammars/calc.pest")).unwrap();
+    assert!(import.unsupported.is_empty());
+    let grammar = import.grammar;
+    assert_eq!(grammar.rules[0].to_string(), "program = (statement (~ statement)*)? ~ $;");
+    assert_eq!(grammar.rules[5].to_string(), "number = \"-\"? [0-9]+ (\".\" [0-9]+)?;");
+    assert_eq!(grammar.rules[6].to_string(), "ident = XID_START XID_CONTINUE*;");
+    assert_eq!(grammar.get_trivia().unwrap().to_string(), "@trivia trivia = (WHITESPACE | COMMENT)*;");
+    assert!(grammar.to_rust().is_ok());
+    let interpreter = GrammarInterpreter::new(&grammar).unwrap();
+    let input = "größe = 2 * (x + 1.5); # comment\n-3;";
+    let tree = interpreter.parse_text("program", input).unwrap();
+    assert_eq!(tree.children.len(), 2);
+    assert_eq!(tree.children[0].children[0].to_text(input), "ident 0..7 \"größe\"\n");
+    assert!(interpreter.parse_text("program", "1 + ;").is_err());
+    // the constructs which can not be translated
+    let import =
+        Grammar::from_pest("a = { PUSH(b) ~ POP ~ #x = b ~ SOI ~ LETTER }\nb = @{ \"b\" ~ c }\nc = { \"c\" }\nd = { c }\n")
+            .unwrap();
+    let unsupported: Vec<String> = import.unsupported.iter().map(|e| e.message.clone()).collect();
+    assert_eq!(
+        unsupported,
+        [
+            "rule `c` is called inside and outside atomic rules, it skips whitespace",
+            "the stack operation `PUSH` is not supported, only its expression is matched",
+            "the stack operation `POP` is not supported",
+            "the tag `#x` is dropped",
+            "`SOI` is only supported at the start of a rule",
+            "the builtin rule `LETTER` is not supported",
+        ]
+    );
+    assert!(Grammar::from_pest("a = { 'b'..'a' }").is_err());
+    // a byte escape takes exactly two hex digits
+    let import = Grammar::from_pest("a = { \"\\x41BC\" }").unwrap();
+    assert_eq!(import.grammar.rules[0].to_string(), "a = \"ABC\";");
+    assert!(Grammar::from_pest("a = { \"\\x4\" }").is_err());
+}
+
+#[test]
+fn generate_datetime() {
+    let code = GrammarCompiler::new("tests/grammars/datetime.abnf").compile(include_str!("grammars/datetime.abnf")).unwrap();
+    assert_eq!(code, include_str!("generated/datetime.rs"));
+    assert!(code.contains("pub fn date_time<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {"));
+    assert!(code.contains("s.match_char_range('0', '9')"));
+}
+
+#[test]
+fn datetime_parser() {
+    let input = "1985-04-12T23:20:50.52Z";
+    assert!(
+        matches!(datetime::date_time(ParseState::new(input)), ParseResult::Pending(s, text) if text == input && s.residual.is_empty())
+    );
+    assert!(matches!(datetime::time_offset(ParseState::new("+08:00")), ParseResult::Pending(_, "+08:00")));
+    // a failure at the start of a rule expects the rule name
+    let context = ParseContext::default();
+    let result = datetime::date_time(ParseState::new("1985-04-12 23:20:50Z").with_context(&context));
+    let failure = context.merge_error(result.as_result().unwrap_err());
+    assert_eq!(failure.position, 10);
+    let context = ParseContext::default();
+    let result = datetime::full_time(ParseState::new("noon").with_context(&context));
+    assert_eq!(context.merge_error(result.as_result().unwrap_err()).to_string(), "expected one of DIGIT, full-time at 0");
+}
+
+#[test]
+fn abnf_importer() {
+    let grammar = Grammar::from_abnf(include_str!("grammars/datetime.abnf")).unwrap();
+    assert_eq!(
+        grammar.get_rule("time-numoffset").unwrap().to_string(),
+        "time-numoffset = (\"+\" | \"-\") time-hour \":\" time-minute;"
+    );
+    assert_eq!(
+        grammar.get_rule("partial-time").unwrap().to_string(),
+        "partial-time = time-hour \":\" time-minute \":\" time-second time-secfrac?;"
+    );
+    assert_eq!(grammar.rules.last().unwrap().to_string(), "DIGIT = [0-9];");
+    let grammar =
+        Grammar::from_abnf("rule = %x41-5A / %d97.98 / %b1010 / %s\"Ab\" / 2*3foo\nFOO = \"x\"\nrule =/ *bar\nBar = CRLF\n")
+            .unwrap();
+    assert_eq!(
+        grammar.to_string(),
+        "rule = [A-Z] | \"ab\" | \"\\n\" | \"Ab\" | FOO{2,3} | Bar*;\nFOO = i\"x\";\nBar = CRLF;\nCRLF = CR LF;\nCR = \"\\r\";\nLF = \"\\n\";\n"
+    );
+    let interpreter = GrammarInterpreter::new(&grammar).unwrap();
+    assert!(interpreter.parse_text("rule", "xX").is_ok());
+    let error = Grammar::from_abnf("a = b\nc =/ d\n").unwrap_err();
+    assert_eq!(error.to_string(), "2:1: rule `c` must be defined before `=/`");
+    let error = Grammar::from_abnf("a = <some prose>\n").unwrap_err();
+    assert_eq!(error.to_string(), "1:5: prose values are not supported, define the rule instead");
+}
+
+#[test]
+fn ebnf_importer() {
+    let grammar = Grammar::from_ebnf(
+        "(* ISO 14977 *)
+        integer = [sign], digit, {digit} ;
+        sign = '+' | '-' ;
+        digit = '0' | non zero digit ;
+        non zero digit = digit excluding zero - '0' ;
+        digit excluding zero = '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' ;
+        pair = 2 * digit, ( 'a' / \"b\" ) .",
+    )
+    .unwrap();
+    assert_eq!(grammar.rules[0].to_string(), "integer = sign? digit digit*;");
+    assert_eq!(grammar.rules[3].to_string(), "non zero digit = !\"0\" digit excluding zero;");
+    assert_eq!(grammar.rules[5].to_string(), "pair = digit{2} (\"a\" | \"b\");");
+    let interpreter = GrammarInterpreter::new(&grammar).unwrap();
+    assert!(interpreter.parse_text("integer", "-120").is_ok());
+    assert!(interpreter.parse_text("pair", "12b").is_ok());
+    let code = grammar.to_rust_functions().unwrap();
+    assert!(code.contains("pub fn non_zero_digit<'i>(input: ::pex::ParseState<'i>) -> ::pex::ParseResult<'i, &'i str> {"));
+    let errors = grammar.to_rust().unwrap_err();
+    assert_eq!(errors[0].message, "rule `non zero digit` is not a valid type name, generate functions instead");
+    let error = Grammar::from_ebnf("a = ? letters ? ;").unwrap_err();
+    assert_eq!(error.to_string(), "1:5: special sequences are not supported, define the rule instead");
+}
+
+#[test]
+fn export_json() {
+    let grammar: Grammar = include_str!("grammars/json.ygg").parse().unwrap();
+    let ebnf = grammar.to_ebnf();
+    assert!(ebnf.contains("object ::= '{' space? (pair (space? ',' space? pair)*)? space? '}'\n"));
+    assert!(ebnf.contains("string ::= '\"' ([^\"#x5C] | '\\' [#x0-#x10FFFF])* '\"'\n"));
+    assert!(ebnf.contains("json ::= space? value space? /* end of input */\n"));
+    let page = grammar.to_markdown("json");
+    assert!(page.starts_with("# json\n\n- [space](#space)\n"));
+    assert!(page.contains("| `MissingString` | `{` |\n| `MissingString` | `,` |\n| `MissingString` | `}` |\n"));
+    assert!(page.contains("Uses: [pair](#pair), [space](#space)\n\nUsed by: [value](#value)\n"));
+    let svg = grammar.get_rule("object").unwrap().to_railroad();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.contains("<text x=\"135\" y=\"81\">pair</text>"));
+    assert!(!svg.contains("href"));
+    let output = std::env::temp_dir().join("pex-grammar-export");
+    let files = GrammarExporter::new("tests/grammars/json.ygg").with_output(&output).export().unwrap();
+    assert_eq!(files.len(), 2 + grammar.rules.len());
+    assert_eq!(std::fs::read_to_string(output.join("json.ebnf")).unwrap(), ebnf);
+    assert_eq!(std::fs::read_to_string(output.join("null.svg")).unwrap(), grammar.get_rule("null").unwrap().to_railroad());
+}
+
+#[test]
+fn export_ebnf() {
+    let grammar: Grammar = "a = \"x\"{2,} !\"y\" ANY &\"z\" b{0,2}; b = \"it's \\\"q\\\"\" | \"\\t\";".parse().unwrap();
+    assert_eq!(
+        grammar.to_ebnf(),
+        "a ::= 'x' 'x'+ ([#x0-#x10FFFF] - 'y') /* &\"z\" */ (b b?)?\nb ::= \"it's \" '\"' 'q' '\"' | #x9\n"
+    );
+    let grammar = Grammar::from_ebnf("digit excluding zero = '1' | '2' ;").unwrap();
+    assert_eq!(grammar.to_ebnf(), "digit_excluding_zero ::= '1' | '2'\n");
+    assert!(grammar.to_markdown("digits").contains("- [digit excluding zero](#digit-excluding-zero)\n"));
+    // the errors of the functions generated for abnf
+    let output = std::env::temp_dir().join("pex-grammar-export-abnf");
+    GrammarExporter::new("tests/grammars/datetime.abnf").with_output(&output).export().unwrap();
+    let page = std::fs::read_to_string(output.join("datetime.md")).unwrap();
+    assert!(page.contains("| `MustBe` | `time-numoffset` |\n| `MissingCharacterRange` | `+` |\n"));
+}
+
+#[test]
+fn export_registry() {
+    let registry =
+        RuleRegistry::default().with_rule("list", "\"[\" (item (\",\" item)*)? \"]\"").with_rule("item", "number | list");
+    let grammar = Grammar::from_registry(&registry).unwrap();
+    assert_eq!(grammar.to_ebnf(), "list ::= '[' (item (',' item)*)? ']'\nitem ::= number | list\n");
+    // the parser functions without a rule are not linked
+    let page = grammar.to_markdown("list");
+    assert!(page.contains("Uses: [item](#item)\n"));
+    assert!(page.contains("Uses: [list](#list), `number`\n"));
+    let output = std::env::temp_dir().join("pex-grammar-export-registry");
+    let files = GrammarExporter::new("list").with_output(&output).export_registry(&registry).unwrap();
+    assert_eq!(files.len(), 4);
+    assert_eq!(std::fs::read_to_string(output.join("list.md")).unwrap(), page);
+    assert!(Grammar::from_registry(&RuleRegistry::default().with_rule("a", "(")).is_err());
+}
@@ -0,0 +1,120 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Formatter, Write},
+};
+
+/// The case folding tables parsed from `CaseFolding.txt`
+pub struct CaseFoldingTable {
+    version: String,
+    max_width: usize,
+    common: BTreeMap<char, char>,
+    simple: BTreeMap<char, char>,
+    full: BTreeMap<char, String>,
+    turkic: BTreeMap<char, char>,
+}
+
+impl Debug for CaseFoldingTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaseFoldingTable")
+            .field("version", &self.version)
+            .field("common", &self.common.len())
+            .field("simple", &self.simple.len())
+            .field("full", &self.full.len())
+            .field("turkic", &self.turkic.len())
+            .finish()
+    }
+}
+
+impl Default for CaseFoldingTable {
+    fn default() -> Self {
+        Self {
+            version: String::new(),
+            max_width: 144,
+            common: BTreeMap::new(),
+            simple: BTreeMap::new(),
+            full: BTreeMap::new(),
+            turkic: BTreeMap::new(),
+        }
+    }
+}
+
+impl CaseFoldingTable {
+    /// Parse the content of `CaseFolding.txt`, return the first malformed or duplicated line if failed
+    pub fn new(ucd: &str) -> Result<Self, String> {
+        let mut out = Self::default();
+        for line in ucd.lines() {
+            if let Some(version) = line.strip_prefix("# CaseFolding-").and_then(|s| s.strip_suffix(".txt")) {
+                out.version = version.to_string();
+            }
+            let data = line.split('#').next().unwrap_or_default().trim();
+            if data.is_empty() {
+                continue;
+            }
+            out.insert_line(data).ok_or_else(|| line.to_string())?;
+        }
+        Ok(out)
+    }
+    fn insert_line(&mut self, data: &str) -> Option<()> {
+        let mut fields = data.split(';').map(|s| s.trim());
+        let code = parse_char(fields.next()?)?;
+        let status = fields.next()?;
+        let mapping = fields.next()?.split_whitespace().map(parse_char).collect::<Option<String>>()?;
+        let mut chars = mapping.chars();
+        let first = chars.next()?;
+        match (status, chars.next()) {
+            ("C", None) => self.common.insert(code, first).is_none(),
+            ("S", None) => self.simple.insert(code, first).is_none(),
+            ("T", None) => self.turkic.insert(code, first).is_none(),
+            ("F", Some(_)) => self.full.insert(code, mapping).is_none(),
+            _ => false,
+        }
+        .then_some(())
+    }
+    /// Set the maximum width of a line
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        assert!(max_width >= 42, "max_width must be at least 42");
+        self.max_width = max_width;
+        self
+    }
+    /// Export the tables as a rust code, sorted for binary search
+    pub fn export_rust_code(&self) -> Result<String, core::fmt::Error> {
+        let mut code = String::new();
+        writeln!(code, "// DO NOT EDIT THIS FILE. IT WAS AUTOMATICALLY GENERATED BY pex-trie.")?;
+        writeln!(code, "//")?;
+        writeln!(code, "// Unicode version: {}.", self.version)?;
+        let pairs =
+            |map: &BTreeMap<char, char>| map.iter().map(|(k, v)| format!("('{}', '{}')", escape(*k), escape(*v))).collect();
+        self.write_table(&mut code, "CASE_FOLDING_COMMON", "(char, char)", pairs(&self.common))?;
+        self.write_table(&mut code, "CASE_FOLDING_SIMPLE", "(char, char)", pairs(&self.simple))?;
+        let full =
+            self.full.iter().map(|(k, v)| format!("('{}', \"{}\")", escape(*k), v.chars().map(escape).collect::<String>()));
+        self.write_table(&mut code, "CASE_FOLDING_FULL", "(char, &str)", full.collect())?;
+        self.write_table(&mut code, "CASE_FOLDING_TURKIC", "(char, char)", pairs(&self.turkic))?;
+        Ok(code)
+    }
+    fn write_table(&self, code: &mut String, name: &str, item: &str, entries: Vec<String>) -> core::fmt::Result {
+        write!(code, "\n#[rustfmt::skip]\npub const {name}: &[{item}] = &[")?;
+        let mut line_width = self.max_width;
+        for entry in entries {
+            if line_width + entry.len() + 1 > self.max_width {
+                code.write_str("\n    ")?;
+                line_width = 4;
+            }
+            else {
+                code.write_char(' ')?;
+                line_width += 1;
+            }
+            write!(code, "{entry},")?;
+            line_width += entry.len() + 1;
+        }
+        code.write_str("\n];\n")
+    }
+}
+
+fn parse_char(hex: &str) -> Option<char> {
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+fn escape(c: char) -> String {
+    format!("\\u{{{:x}}}", c as u32)
+}
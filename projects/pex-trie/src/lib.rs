@@ -4,9 +4,10 @@
 #![doc(html_logo_url = "https://avatars.githubusercontent.com/u/91894079")]
 #![doc(html_favicon_url = "https://avatars.githubusercontent.com/u/91894079")]
 
+mod case_folding;
 pub mod generate;
 mod regex_set;
 mod unicode_set;
 mod writer;
 
-pub use unicode_set::UnicodeSet;
+pub use crate::{case_folding::CaseFoldingTable, unicode_set::UnicodeSet};
@@ -2,7 +2,7 @@ use pex::{
     helpers::{decimal_string, optional, whitespace, CharactersTrie, UnicodeUnescape},
     BracketPattern, ParseResult, ParseState,
 };
-use pex_trie::{generate::xid::XID_START, UnicodeSet};
+use pex_trie::{generate::xid::XID_START, CaseFoldingTable, UnicodeSet};
 use regex_automata::dfa::regex::Regex;
 use ucd_trie::{TrieSetOwned, TrieSetSlice};
 
@@ -18,6 +18,35 @@ fn dump_xid_start() {
     println!("{}", xid.export_rust_code().unwrap());
 }
 
+/// The XID tables of `pex::helpers` must be the tries of the UCD ranges in `pex_trie::generate::xid`
+#[test]
+fn xid_tables_match() {
+    use pex_trie::generate::xid;
+    let tables = [(pex::helpers::XID_START, xid::XID_START), (pex::helpers::XID_CONTINUE, xid::XID_CONTINUE)];
+    for (table, ranges) in tables {
+        let trie = TrieSetOwned::from_scalars(ranges.iter().flat_map(|&(start, end)| start..=end)).unwrap();
+        let trie = trie.as_slice();
+        assert_eq!(table.tree1_level1, trie.tree1_level1, "{}", table.set_name);
+        assert_eq!(table.tree2_level1, trie.tree2_level1, "{}", table.set_name);
+        assert_eq!(table.tree2_level2, trie.tree2_level2, "{}", table.set_name);
+        assert_eq!(table.tree3_level1, trie.tree3_level1, "{}", table.set_name);
+        assert_eq!(table.tree3_level2, trie.tree3_level2, "{}", table.set_name);
+        assert_eq!(table.tree3_level3, trie.tree3_level3, "{}", table.set_name);
+    }
+}
+
+#[test]
+fn dump_case_folding() {
+    let ucd = "# CaseFolding-15.0.0.txt\n0041; C; 0061; # LATIN CAPITAL LETTER A\n0049; C; 0069; # LATIN CAPITAL LETTER I\n0049; T; 0131; # LATIN CAPITAL LETTER I\n00DF; F; 0073 0073; # LATIN SMALL LETTER SHARP S\n1E9E; F; 0073 0073; # LATIN CAPITAL LETTER SHARP S\n1E9E; S; 00DF; # LATIN CAPITAL LETTER SHARP S\n";
+    let table = CaseFoldingTable::new(ucd).unwrap();
+    let code = table.export_rust_code().unwrap();
+    println!("{}", code);
+    assert!(code.contains("// Unicode version: 15.0.0."));
+    assert!(code.contains("('\\u{df}', \"\\u{73}\\u{73}\")"));
+    assert!(code.contains("('\\u{1e9e}', '\\u{df}')"));
+    assert!(CaseFoldingTable::new("0041; X; 0061;").is_err());
+}
+
 #[test]
 fn test() {
     let pat = BracketPattern::new("(", ")").with_one_tailing(true);